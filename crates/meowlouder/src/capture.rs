@@ -0,0 +1,338 @@
+use crate::err_fn;
+use anyhow::{anyhow, Result};
+use cpal::{
+	traits::{DeviceTrait, StreamTrait},
+	Device, SampleFormat, SampleRate, SizedSample, Stream, SupportedStreamConfig,
+	SupportedStreamConfigRange,
+};
+use crossbeam_channel::{Receiver, Sender};
+use meowlouder_opus::{
+	util, FrameDuration, FrameSize, OpusApplication, OpusEncodable, OpusEncoder,
+	SampleRate as OpusSampleRate,
+};
+use std::borrow::Cow;
+
+/// The sample rates Opus supports, from most to least preferred.
+const OPUS_SAMPLE_RATES: [u32; 5] = [48000, 24000, 16000, 12000, 8000];
+
+/// Captures audio from a cpal input device, and encodes it into 20 ms Opus
+/// packets.
+///
+/// If the input device has a different number of channels than is being
+/// encoded, the captured audio is downmixed or upmixed to match.
+///
+/// The input stream is started when the pipeline is created, and stopped when
+/// it is dropped.
+pub struct CpalEncoderPipeline {
+	stream: Stream,
+	config: SupportedStreamConfig,
+	channels: u16,
+	frame_size: usize,
+	lookahead: usize,
+	packets: Receiver<Vec<u8>>,
+}
+
+impl CpalEncoderPipeline {
+	pub fn new(device: &Device, channels: u16) -> Result<Self> {
+		let config = negotiate_config(device.supported_input_configs()?, &OPUS_SAMPLE_RATES)
+			.ok_or_else(|| anyhow!("input device has no Opus-compatible config"))?;
+		check_remix(config.channels(), channels)?;
+		let sample_rate = OpusSampleRate::try_from(config.sample_rate().0)?;
+		let frame_size = FrameSize::from_duration(FrameDuration::Ms20, sample_rate).samples();
+		let mut encoder = OpusEncoder::new(
+			sample_rate,
+			i32::from(channels).try_into()?,
+			OpusApplication::Audio,
+		)?;
+		let lookahead = encoder.lookahead()? as usize;
+		let (tx, packets) = crossbeam_channel::unbounded();
+		let stream = match config.sample_format() {
+			SampleFormat::F32 => build_stream::<f32>(device, &config, encoder, frame_size, tx)?,
+			SampleFormat::I16 => build_stream::<i16>(device, &config, encoder, frame_size, tx)?,
+			format => return Err(anyhow!("unsupported sample format {format}")),
+		};
+		stream.play()?;
+		Ok(Self {
+			stream,
+			config,
+			channels,
+			frame_size,
+			lookahead,
+			packets,
+		})
+	}
+
+	/// The config the input stream was opened with.
+	pub fn config(&self) -> &SupportedStreamConfig {
+		&self.config
+	}
+
+	/// The number of channels being encoded, which may differ from the input
+	/// device's.
+	pub fn channels(&self) -> u16 {
+		self.channels
+	}
+
+	/// The number of samples (per channel) in each encoded packet.
+	pub fn frame_size(&self) -> usize {
+		self.frame_size
+	}
+
+	/// The number of samples (per channel) of delay added by the encoder.
+	pub fn lookahead(&self) -> usize {
+		self.lookahead
+	}
+
+	/// The encoded Opus packets, in the order they were captured.
+	pub fn packets(&self) -> &Receiver<Vec<u8>> {
+		&self.packets
+	}
+}
+
+impl Drop for CpalEncoderPipeline {
+	fn drop(&mut self) {
+		if let Err(err) = self.stream.pause() {
+			eprintln!("failed to stop input stream: {}", err);
+		}
+	}
+}
+
+/// Picks the supported config closest to what Opus wants, preferring stereo,
+/// and the earliest of `sample_rates` that is supported.
+pub(crate) fn negotiate_config(
+	configs: impl Iterator<Item = SupportedStreamConfigRange>,
+	sample_rates: &[u32],
+) -> Option<SupportedStreamConfig> {
+	let configs: Vec<_> = configs
+		.filter(|config| {
+			(1..=2).contains(&config.channels())
+				&& matches!(
+					config.sample_format(),
+					SampleFormat::F32 | SampleFormat::I16
+				)
+		})
+		.collect();
+	sample_rates.iter().find_map(|&rate| {
+		configs
+			.iter()
+			.filter(|config| {
+				config.min_sample_rate().0 <= rate && rate <= config.max_sample_rate().0
+			})
+			.max_by_key(|config| {
+				(
+					config.channels(),
+					config.sample_format() == SampleFormat::F32,
+				)
+			})
+			.map(|config| config.with_sample_rate(SampleRate(rate)))
+	})
+}
+
+/// Checks that captured audio with `input_channels` channels can be remixed
+/// into `channels` channels, which is only supported between mono and
+/// stereo.
+fn check_remix(input_channels: u16, channels: u16) -> Result<()> {
+	match (input_channels, channels) {
+		(1 | 2, 1 | 2) => Ok(()),
+		_ => Err(anyhow!(
+			"can't remix {input_channels} input channels into {channels}"
+		)),
+	}
+}
+
+fn build_stream<T>(
+	device: &Device,
+	config: &SupportedStreamConfig,
+	encoder: OpusEncoder,
+	frame_size: usize,
+	packets: Sender<Vec<u8>>,
+) -> Result<Stream>
+where
+	T: SizedSample + OpusEncodable + Remix + Send + 'static,
+{
+	let mut frames = FrameEncoder::<T> {
+		encoder,
+		input_channels: config.channels() as usize,
+		frame_size,
+		buffer: Vec::new(),
+		packets,
+	};
+	Ok(device.build_input_stream(
+		&config.config(),
+		move |data: &[T], _: &_| frames.push(data),
+		err_fn,
+		None,
+	)?)
+}
+
+/// Accumulates captured samples until there's a full frame, and encodes it.
+struct FrameEncoder<T> {
+	encoder: OpusEncoder,
+	input_channels: usize,
+	frame_size: usize,
+	buffer: Vec<T>,
+	packets: Sender<Vec<u8>>,
+}
+
+impl<T: OpusEncodable + Remix + Copy> FrameEncoder<T> {
+	fn push(&mut self, input: &[T]) {
+		// Any other combination is rejected by `check_remix` up front.
+		let input = match (self.input_channels, self.encoder.channels()) {
+			(2, 1) => Cow::Owned(T::downmix(input)),
+			(1, 2) => Cow::Owned(T::upmix(input)),
+			_ => Cow::Borrowed(input),
+		};
+		self.buffer.extend_from_slice(&input);
+		let frame_len = self.frame_size * self.encoder.channels();
+		while self.buffer.len() >= frame_len {
+			match self
+				.encoder
+				.encode(&self.buffer[..frame_len], self.frame_size)
+			{
+				Ok(packet) => self.packets.send(packet).unwrap_or_default(),
+				Err(e) => eprintln!("Encoding error: {}", e),
+			}
+			self.buffer.drain(..frame_len);
+		}
+	}
+}
+
+/// Converts captured samples between mono and stereo.
+trait Remix: Sized {
+	fn downmix(stereo: &[Self]) -> Vec<Self>;
+	fn upmix(mono: &[Self]) -> Vec<Self>;
+}
+
+impl Remix for i16 {
+	fn downmix(stereo: &[Self]) -> Vec<Self> {
+		util::downmix_stereo_to_mono(stereo)
+	}
+
+	fn upmix(mono: &[Self]) -> Vec<Self> {
+		util::upmix_mono_to_stereo(mono)
+	}
+}
+
+impl Remix for f32 {
+	fn downmix(stereo: &[Self]) -> Vec<Self> {
+		util::downmix_stereo_to_mono_f32(stereo)
+	}
+
+	fn upmix(mono: &[Self]) -> Vec<Self> {
+		util::upmix_mono_to_stereo_f32(mono)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cpal::SupportedBufferSize;
+
+	fn config_range(
+		channels: u16,
+		min: u32,
+		max: u32,
+		format: SampleFormat,
+	) -> SupportedStreamConfigRange {
+		SupportedStreamConfigRange::new(
+			channels,
+			SampleRate(min),
+			SampleRate(max),
+			SupportedBufferSize::Unknown,
+			format,
+		)
+	}
+
+	fn frame_encoder(
+		input_channels: usize,
+		channels: i32,
+	) -> (FrameEncoder<f32>, Receiver<Vec<u8>>) {
+		let encoder = OpusEncoder::new(
+			OpusSampleRate::Hz48000,
+			channels.try_into().unwrap(),
+			OpusApplication::Audio,
+		)
+		.unwrap();
+		let (packets, receiver) = crossbeam_channel::unbounded();
+		let frames = FrameEncoder {
+			encoder,
+			input_channels,
+			frame_size: 960,
+			buffer: Vec::new(),
+			packets,
+		};
+		(frames, receiver)
+	}
+
+	#[test]
+	fn negotiates_48k_stereo() {
+		let configs = [
+			config_range(1, 8000, 48000, SampleFormat::I16),
+			config_range(2, 44100, 48000, SampleFormat::I16),
+			config_range(2, 44100, 48000, SampleFormat::F32),
+			config_range(6, 48000, 48000, SampleFormat::F32),
+		];
+		let config = negotiate_config(configs.into_iter(), &OPUS_SAMPLE_RATES).unwrap();
+		assert_eq!(config.channels(), 2);
+		assert_eq!(config.sample_rate(), SampleRate(48000));
+		assert_eq!(config.sample_format(), SampleFormat::F32);
+
+		// Falls back to a lower rate Opus supports, rather than 44.1 kHz.
+		let configs = [
+			config_range(1, 44100, 44100, SampleFormat::F32),
+			config_range(1, 16000, 16000, SampleFormat::I16),
+		];
+		let config = negotiate_config(configs.into_iter(), &OPUS_SAMPLE_RATES).unwrap();
+		assert_eq!(config.sample_rate(), SampleRate(16000));
+
+		let configs = [config_range(2, 44100, 44100, SampleFormat::F32)];
+		assert!(negotiate_config(configs.into_iter(), &OPUS_SAMPLE_RATES).is_none());
+	}
+
+	#[test]
+	fn encodes_captured_f32_buffers() {
+		let (mut frames, packets) = frame_encoder(2, 2);
+		// Half a second of a stereo tone, delivered in callbacks which don't
+		// line up with the frames.
+		let input = (0..24000)
+			.flat_map(|i| {
+				let sample = (i as f32 * 440.0 / 48000.0 * std::f32::consts::TAU).sin() * 0.5;
+				[sample, sample]
+			})
+			.collect::<Vec<_>>();
+		for data in input.chunks(2 * 441) {
+			frames.push(data);
+		}
+		let packets = packets.try_iter().collect::<Vec<_>>();
+		assert_eq!(packets.len(), 25);
+		for packet in &packets {
+			assert_eq!(
+				meowlouder_opus::packet::nb_samples(packet, 48000).unwrap(),
+				960
+			);
+		}
+	}
+
+	#[test]
+	fn remixes_captured_channels() {
+		let (mut frames, packets) = frame_encoder(2, 1);
+		frames.push(&[0.25; 960 * 2]);
+		assert_eq!(packets.try_iter().count(), 1);
+		assert!(frames.buffer.is_empty());
+
+		let (mut frames, packets) = frame_encoder(1, 2);
+		frames.push(&[0.25; 960]);
+		assert_eq!(packets.try_iter().count(), 1);
+		assert!(frames.buffer.is_empty());
+	}
+
+	#[test]
+	fn rejects_unsupported_remixes() {
+		for (input_channels, channels) in [(1, 1), (1, 2), (2, 1), (2, 2)] {
+			assert!(check_remix(input_channels, channels).is_ok());
+		}
+		for (input_channels, channels) in [(6, 2), (2, 6), (0, 1), (1, 0)] {
+			assert!(check_remix(input_channels, channels).is_err());
+		}
+	}
+}
@@ -1,96 +1,27 @@
+mod capture;
+mod ogg;
+mod playback;
+
+use crate::{
+	capture::CpalEncoderPipeline,
+	ogg::{OggOpusReader, OggOpusWriter},
+	playback::OpusCpalPlaybackPipeline,
+};
 use anyhow::Result;
-use cpal::{
-	traits::{DeviceTrait, HostTrait, StreamTrait},
-	Sample, SampleFormat, StreamConfig,
+use cpal::{traits::HostTrait, Device};
+use crossbeam_channel::select;
+use meowlouder_opus::packet;
+use std::{
+	ffi::OsStr,
+	fs::File,
+	io::{BufRead, BufReader},
+	path::Path,
+	time::Duration,
 };
-use crossbeam_channel::{select, Receiver, Sender};
-use meowlouder_opus::{OpusApplication, OpusDecoder, OpusEncoder};
-use rubato::{InterpolationParameters, InterpolationType, Resampler, SincFixedIn, WindowFunction};
-use std::{io::BufRead, time::Duration};
-
-struct EncodedChunk {
-	data: Vec<u8>,
-	frame_size: usize,
-}
-
-struct AudioBuffer {
-	data: Vec<i16>,
-	channels: u16,
-	position: usize,
-}
-
-impl AudioBuffer {
-	fn new(data: Vec<i16>, channels: u16) -> Self {
-		Self {
-			data,
-			channels,
-			position: 0,
-		}
-	}
-
-	fn get_next_chunk(&mut self, chunk_size: usize) -> Option<&[i16]> {
-		if self.position >= self.data.len() {
-			return None;
-		}
-
-		let remaining = self.data.len() - self.position;
-		let chunk_samples = chunk_size * self.channels as usize;
-		let take_samples = chunk_samples.min(remaining);
-
-		let chunk = &self.data[self.position..self.position + take_samples];
-		self.position += take_samples;
-
-		Some(chunk)
-	}
-}
-
-fn create_resampler(
-	from_sample_rate: u32,
-	to_sample_rate: u32,
-	channels: usize,
-) -> Result<SincFixedIn<f32>> {
-	let params = InterpolationParameters {
-		sinc_len: 256,
-		f_cutoff: 0.95,
-		interpolation: InterpolationType::Linear,
-		oversampling_factor: 256,
-		window: WindowFunction::BlackmanHarris2,
-	};
-
-	Ok(SincFixedIn::<f32>::new(
-		from_sample_rate as f64 / to_sample_rate as f64,
-		params,
-		channels,
-		4096,
-	)?)
-}
-
-fn resample_audio(
-	input: &[i16],
-	channels: usize,
-	from_rate: u32,
-	to_rate: u32,
-	resampler: &mut SincFixedIn<f32>,
-) -> Vec<i16> {
-	// Convert i16 to f32
-	let mut input_f32: Vec<Vec<f32>> = vec![Vec::new(); channels];
-	for (i, sample) in input.iter().enumerate() {
-		input_f32[i % channels].push(*sample as f32 / 32768.0);
-	}
-
-	// Resample
-	let output_f32 = resampler.process(&input_f32, None).unwrap_or_default();
-
-	// Convert back to interleaved i16
-	let mut output = Vec::with_capacity(output_f32[0].len() * channels);
-	for i in 0..output_f32[0].len() {
-		for channel in 0..channels {
-			output.push((output_f32[channel][i] * 32768.0) as i16);
-		}
-	}
 
-	output
-}
+/// The number of channels recordings are encoded with, whatever the input
+/// device has.
+const ENCODE_CHANNELS: u16 = 2;
 
 fn main() -> Result<()> {
 	// Initialize the default host and devices
@@ -102,64 +33,24 @@ fn main() -> Result<()> {
 		.default_output_device()
 		.expect("no output device available");
 
-	// Get the default configs
-	let input_config = input_device.default_input_config()?;
-	println!("Input config: {:?}", input_config);
-
-	let output_config = output_device.default_output_config()?;
-	println!("Output config: {:?}", output_config);
+	// Play back a saved recording, if asked to
+	let mut args = std::env::args_os().skip(1);
+	let first_arg = args.next();
+	if first_arg.as_deref() == Some(OsStr::new("play")) {
+		let path = args.next().expect("no file to play given");
+		return play_file(Path::new(&path), &output_device);
+	}
 
-	let sample_rate = input_config.sample_rate().0;
-	let channels = input_config.channels();
+	// Start capturing and encoding audio
+	let pipeline = CpalEncoderPipeline::new(&input_device, ENCODE_CHANNELS)?;
+	println!("Input config: {:?}", pipeline.config());
 
-	// Calculate frame size based on desired duration
-	let chunk_duration = Duration::from_millis(40);
-	let samples_per_chunk =
-		(sample_rate as u128 * chunk_duration.as_nanos() / 1_000_000_000) as usize;
-	println!(
-		"Using {} samples per chunk ({:?})",
-		samples_per_chunk, chunk_duration
-	);
+	let sample_rate = pipeline.config().sample_rate().0;
+	let channels = pipeline.channels();
+	let frame_size = pipeline.frame_size();
+	let pre_skip = pipeline.lookahead() * 48000 / sample_rate as usize;
 
-	// Create channels for the audio data
-	let (tx, rx) = crossbeam_channel::unbounded();
 	let (finish_tx, finish_rx) = crossbeam_channel::unbounded::<()>();
-	//let (encoded_tx, encoded_rx) = mpsc::channel();
-
-	// Create encoder and decoder
-	let mut encoder = OpusEncoder::new(
-		sample_rate as i32,
-		channels.min(2) as i32,
-		OpusApplication::Audio,
-	)?;
-
-	// Set up the audio input stream
-	let stream = match input_config.sample_format() {
-		SampleFormat::F32 => input_device.build_input_stream(
-			&input_config.into(),
-			move |data: &[f32], _: &_| handle_input_data_f32(data, &tx, channels),
-			err_fn,
-			None,
-		)?,
-		SampleFormat::I16 => input_device.build_input_stream(
-			&input_config.into(),
-			move |data: &[i16], _: &_| handle_input_data_i16(data, &tx, channels),
-			err_fn,
-			None,
-		)?,
-		SampleFormat::U16 => input_device.build_input_stream(
-			&input_config.into(),
-			move |data: &[u16], _: &_| {
-				let i16_data: Vec<i16> = data.iter().map(|&x| (x as i32 - 32768) as i16).collect();
-				handle_input_data_i16(&i16_data, &tx, channels);
-			},
-			err_fn,
-			None,
-		)?,
-		_ => unimplemented!(),
-	};
-
-	stream.play()?;
 
 	// Spawn a thread to handle user input for stopping
 	std::thread::spawn(move || {
@@ -170,194 +61,93 @@ fn main() -> Result<()> {
 		let _ = finish_tx.send(());
 	});
 
-	// Buffer for accumulating samples
-	let mut sample_buffer = Vec::new();
 	let mut encoded_chunks = Vec::new();
 
-	// Process incoming audio data in chunks
+	// Collect encoded packets until recording is stopped
 	loop {
 		let data = select! {
-			recv(rx) -> msg => match msg {
+			recv(pipeline.packets()) -> msg => match msg {
 				Ok(data) => data,
 				_ => break,
 			},
 			recv(finish_rx) -> _ => break,
 		};
-		sample_buffer.extend(data);
-
-		// Process complete chunks
-		while sample_buffer.len() >= samples_per_chunk * channels as usize {
-			let chunk: Vec<i16> = sample_buffer
-				.drain(..samples_per_chunk * channels as usize)
-				.collect();
-
-			match encoder.encode(&chunk, samples_per_chunk) {
-				Ok(encoded) => {
-					println!("Encoded chunk of {} bytes", encoded.len());
-					encoded_chunks.push(EncodedChunk {
-						data: encoded,
-						frame_size: samples_per_chunk,
-					});
-				}
-				Err(e) => eprintln!("Encoding error: {}", e),
-			}
-		}
+		println!("Encoded chunk of {} bytes", data.len());
+		encoded_chunks.push(data);
 	}
-
-	// Process any remaining samples
-	if !sample_buffer.is_empty() {
-		let frame_size = sample_buffer.len() / channels as usize;
-		match encoder.encode(&sample_buffer, frame_size) {
-			Ok(encoded) => {
-				println!("Encoded final chunk of {} bytes", encoded.len());
-				encoded_chunks.push(EncodedChunk {
-					data: encoded,
-					frame_size,
-				});
-			}
-			Err(e) => eprintln!("Encoding error: {}", e),
+	drop(pipeline);
+
+	// Save the recording, if a path was given
+	if let Some(path) = first_arg {
+		let mut writer = OggOpusWriter::new(
+			File::create(path)?,
+			channels as u8,
+			pre_skip as u16,
+			sample_rate,
+		)?;
+		for chunk in &encoded_chunks {
+			writer.write_packet(chunk.clone())?;
 		}
+		writer.finalize()?;
 	}
 
 	println!("Playing back recorded audio...");
 
-	// Create decoder for playback
-	let mut decoder = OpusDecoder::new(sample_rate as i32, channels.min(2) as i32)?;
+	let duration =
+		Duration::from_secs_f64((encoded_chunks.len() * frame_size) as f64 / sample_rate as f64);
 
-	// Set up output stream
+	// Queue up the recording, and start decoding and playing it back
 	let (playback_tx, playback_rx) = crossbeam_channel::unbounded();
-
-	let output_stream = match output_config.sample_format() {
-		SampleFormat::F32 => output_device.build_output_stream(
-			&output_config.into(),
-			move |data: &mut [f32], _: &_| handle_output_data_f32(data, &playback_rx),
-			err_fn,
-			None,
-		)?,
-		SampleFormat::I16 => output_device.build_output_stream(
-			&output_config.into(),
-			move |data: &mut [i16], _: &_| handle_output_data_i16(data, &playback_rx),
-			err_fn,
-			None,
-		)?,
-		SampleFormat::U16 => output_device.build_output_stream(
-			&output_config.into(),
-			move |data: &mut [u16], _: &_| handle_output_data_u16(data, &playback_rx),
-			err_fn,
-			None,
-		)?,
-		_ => unimplemented!(),
-	};
-
-	output_stream.play()?;
-
-	// Decode and play back each chunk
 	for chunk in encoded_chunks {
-		match decoder.decode(Some(&chunk.data), chunk.frame_size, false) {
-			Ok(decoded) => {
-				playback_tx.send(decoded)?;
-			}
-			Err(e) => eprintln!("Decoding error: {}", e),
-		}
+		playback_tx.send(chunk)?;
 	}
+	let playback = OpusCpalPlaybackPipeline::new(playback_rx, &output_device, sample_rate)?;
+	println!("Output config: {:?}", playback.config());
 
-	// Wait a bit for the last samples to play
-	std::thread::sleep(Duration::from_secs(1));
+	// Wait for the recording to play, plus a bit for the last samples
+	std::thread::sleep(duration + Duration::from_secs(1));
+	println!(
+		"Playback finished with {} underflows",
+		playback.underflows()
+	);
+	drop(playback);
 
 	Ok(())
 }
 
-fn handle_output_data_f32(output: &mut [f32], rx: &Receiver<Vec<i16>>) {
-	if let Ok(data) = rx.try_recv() {
-		for (i, sample) in data.iter().enumerate() {
-			if i < output.len() {
-				output[i] = *sample as f32 / 32768.0;
-			}
-		}
-	}
-}
-
-fn handle_output_data_i16(output: &mut [i16], rx: &Receiver<Vec<i16>>) {
-	if let Ok(data) = rx.try_recv() {
-		for (i, sample) in data.iter().enumerate() {
-			if i < output.len() {
-				output[i] = *sample;
-			}
-		}
-	}
-}
-
-fn handle_output_data_u16(output: &mut [u16], rx: &Receiver<Vec<i16>>) {
-	if let Ok(data) = rx.try_recv() {
-		for (i, sample) in data.iter().enumerate() {
-			if i < output.len() {
-				output[i] = (*sample as i32 + 32768) as u16;
-			}
-		}
-	}
-}
-
-// Previous input handling functions remain the same
-fn handle_input_data_f32(input: &[f32], tx: &Sender<Vec<i16>>, channels: u16) {
-	let mut processed: Vec<i16> = Vec::with_capacity(input.len());
-
-	if channels <= 2 {
-		for &sample in input {
-			processed.push((sample * 32767.0) as i16);
-		}
-	} else {
-		for chunk in input.chunks(channels as usize) {
-			let mut left = 0.0;
-			let mut right = 0.0;
-
-			for (i, &sample) in chunk.iter().enumerate() {
-				if i % 2 == 0 {
-					left += sample;
-				} else {
-					right += sample;
-				}
-			}
-
-			left /= channels as f32 / 2.0;
-			right /= channels as f32 / 2.0;
+/// Decodes and plays back an Ogg Opus file, waiting until it has finished.
+fn play_file(path: &Path, output_device: &Device) -> Result<()> {
+	let mut reader = OggOpusReader::new(BufReader::new(File::open(path)?))?;
+	let head = *reader.head();
+	println!(
+		"Playing {}: {} channels (mapping family {}), recorded at {} Hz, {} samples pre-skip, {} \
+		 dB gain",
+		path.display(),
+		head.channels,
+		head.mapping_family,
+		head.input_sample_rate,
+		head.pre_skip,
+		head.output_gain as f32 / 256.0,
+	);
 
-			processed.push((left * 32767.0) as i16);
-			processed.push((right * 32767.0) as i16);
-		}
+	let (playback_tx, playback_rx) = crossbeam_channel::unbounded();
+	let mut samples = 0;
+	for packet in reader.packets() {
+		let packet = packet?;
+		samples += packet::nb_samples(&packet, 48000)?;
+		playback_tx.send(packet)?;
 	}
+	let playback = OpusCpalPlaybackPipeline::new(playback_rx, output_device, 48000)?;
+	println!("Output config: {:?}", playback.config());
 
-	tx.send(processed).unwrap_or_default();
-}
-
-fn handle_input_data_i16(input: &[i16], tx: &Sender<Vec<i16>>, channels: u16) {
-	if channels <= 2 {
-		tx.send(input.to_vec()).unwrap_or_default();
-	} else {
-		let mut processed: Vec<i16> = Vec::with_capacity(input.len() * 2 / channels as usize);
-
-		for chunk in input.chunks(channels as usize) {
-			let mut left = 0i32;
-			let mut right = 0i32;
-
-			for (i, &sample) in chunk.iter().enumerate() {
-				if i % 2 == 0 {
-					left += sample as i32;
-				} else {
-					right += sample as i32;
-				}
-			}
-
-			left /= channels as i32 / 2;
-			right /= channels as i32 / 2;
-
-			processed.push(left as i16);
-			processed.push(right as i16);
-		}
-
-		tx.send(processed).unwrap_or_default();
-	}
+	std::thread::sleep(Duration::from_secs_f64(samples as f64 / 48000.0) + Duration::from_secs(1));
+	println!(
+		"Playback finished with {} underflows",
+		playback.underflows()
+	);
+	Ok(())
 }
 
-fn err_fn(err: cpal::StreamError) {
+pub(crate) fn err_fn(err: cpal::StreamError) {
 	eprintln!("an error occurred on stream: {}", err);
 }
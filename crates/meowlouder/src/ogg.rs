@@ -0,0 +1,362 @@
+use anyhow::{anyhow, bail, Error, Result};
+use meowlouder_opus::{libopus_version, packet};
+use ogg::{
+	reading::{BasePacketReader, OggPage, PageParser},
+	writing::{PacketWriteEndInfo, PacketWriter},
+};
+use std::{
+	io::{ErrorKind, Read, Write},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Writes Opus packets into an Ogg Opus stream, as specified by
+/// [RFC 7845](https://www.rfc-editor.org/rfc/rfc7845).
+///
+/// The stream isn't complete until [`OggOpusWriter::finalize`] is called.
+pub struct OggOpusWriter<W: Write> {
+	writer: PacketWriter<'static, W>,
+	serial: u32,
+	granule_position: u64,
+	/// The last packet written, which is held back so that it can be marked as
+	/// the end of the stream.
+	pending: Option<(Vec<u8>, u64)>,
+}
+
+impl<W: Write> OggOpusWriter<W> {
+	/// Creates a new writer, and writes the Opus headers to `inner`.
+	///
+	/// `pre_skip` is the number of samples (at 48 kHz) to skip at the start of
+	/// the decoded audio, which should be the encoder's lookahead, and
+	/// `input_sample_rate` is the sampling rate of the original audio.
+	pub fn new(inner: W, channels: u8, pre_skip: u16, input_sample_rate: u32) -> Result<Self> {
+		let mut writer = PacketWriter::new(inner);
+		let serial = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |time| time.subsec_nanos() ^ time.as_secs() as u32);
+
+		let mut head = Vec::with_capacity(19);
+		head.extend_from_slice(b"OpusHead");
+		head.push(1); // version
+		head.push(channels);
+		head.extend_from_slice(&pre_skip.to_le_bytes());
+		head.extend_from_slice(&input_sample_rate.to_le_bytes());
+		head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+		head.push(0); // channel mapping family
+		writer.write_packet(head, serial, PacketWriteEndInfo::EndPage, 0)?;
+
+		let vendor = libopus_version().as_bytes();
+		let mut tags = Vec::with_capacity(16 + vendor.len());
+		tags.extend_from_slice(b"OpusTags");
+		tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+		tags.extend_from_slice(vendor);
+		tags.extend_from_slice(&0u32.to_le_bytes()); // user comment count
+		writer.write_packet(tags, serial, PacketWriteEndInfo::EndPage, 0)?;
+
+		Ok(Self {
+			writer,
+			serial,
+			granule_position: 0,
+			pending: None,
+		})
+	}
+
+	/// Writes an encoded packet to the stream.
+	pub fn write_packet(&mut self, packet: Vec<u8>) -> Result<()> {
+		self.granule_position += packet::nb_samples(&packet, 48000)? as u64;
+		if let Some((pending, granule_position)) =
+			self.pending.replace((packet, self.granule_position))
+		{
+			self.writer.write_packet(
+				pending,
+				self.serial,
+				PacketWriteEndInfo::NormalPacket,
+				granule_position,
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Writes the last packet, marking it as the end of the stream, and
+	/// returns the inner writer.
+	pub fn finalize(mut self) -> Result<W> {
+		if let Some((pending, granule_position)) = self.pending.take() {
+			self.writer.write_packet(
+				pending,
+				self.serial,
+				PacketWriteEndInfo::EndStream,
+				granule_position,
+			)?;
+		}
+		let mut inner = self.writer.into_inner();
+		inner.flush()?;
+		Ok(inner)
+	}
+}
+
+/// The identification header of an Ogg Opus stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpusHead {
+	pub channels: u8,
+	/// The number of samples (at 48 kHz) to skip at the start of the decoded
+	/// audio.
+	pub pre_skip: u16,
+	/// The sampling rate of the original audio. This is informational only,
+	/// and doesn't need to be used for playback.
+	pub input_sample_rate: u32,
+	/// The gain to apply when decoding, in Q7.8 dB.
+	pub output_gain: i16,
+	pub mapping_family: u8,
+}
+
+impl OpusHead {
+	fn parse(data: &[u8]) -> Result<Self> {
+		if data.len() < 19 || !data.starts_with(b"OpusHead") {
+			bail!("first packet isn't an OpusHead header");
+		}
+		// Only the major version (the upper 4 bits) is incompatible.
+		if data[8] >> 4 != 0 {
+			bail!("unsupported Ogg Opus version {}", data[8]);
+		}
+		let channels = data[9];
+		if channels == 0 {
+			bail!("OpusHead header has no channels");
+		}
+		Ok(Self {
+			channels,
+			pre_skip: u16::from_le_bytes([data[10], data[11]]),
+			input_sample_rate: u32::from_le_bytes([data[12], data[13], data[14], data[15]]),
+			output_gain: i16::from_le_bytes([data[16], data[17]]),
+			mapping_family: data[18],
+		})
+	}
+}
+
+/// Reads Opus packets from an Ogg Opus stream, as specified by
+/// [RFC 7845](https://www.rfc-editor.org/rfc/rfc7845).
+///
+/// Only the first logical stream is read; pages belonging to any other
+/// stream are skipped.
+pub struct OggOpusReader<R: Read> {
+	packets: OggPacketReader<R>,
+	head: OpusHead,
+	finished: bool,
+}
+
+impl<R: Read> OggOpusReader<R> {
+	/// Creates a new reader, reading and validating the Opus headers from
+	/// `inner`.
+	pub fn new(inner: R) -> Result<Self> {
+		let mut packets = OggPacketReader {
+			inner,
+			reader: BasePacketReader::new(),
+			serial: None,
+		};
+
+		let head = packets
+			.read_packet()?
+			.ok_or_else(|| anyhow!("stream is empty"))?;
+		if !head.first_in_stream() || !head.last_in_page() {
+			bail!("OpusHead header must be alone on the first page");
+		}
+		let head_data = OpusHead::parse(&head.data)?;
+
+		let tags = packets
+			.read_packet()?
+			.ok_or_else(|| anyhow!("stream ends before the OpusTags header"))?;
+		if !tags.data.starts_with(b"OpusTags") {
+			bail!("second packet isn't an OpusTags header");
+		}
+
+		Ok(Self {
+			packets,
+			head: head_data,
+			finished: tags.last_in_stream(),
+		})
+	}
+
+	/// The stream's identification header.
+	pub fn head(&self) -> &OpusHead {
+		&self.head
+	}
+
+	/// Returns an iterator over the remaining audio packets in the stream.
+	///
+	/// The iterator ends after the last packet of the stream, or after the
+	/// first error.
+	pub fn packets(&mut self) -> impl Iterator<Item = Result<Vec<u8>, Error>> + '_ {
+		std::iter::from_fn(move || {
+			if self.finished {
+				return None;
+			}
+			let packet = self.packets.read_packet().transpose()?;
+			match &packet {
+				Ok(packet) => self.finished = packet.last_in_stream(),
+				Err(_) => self.finished = true,
+			}
+			Some(packet.map(|packet| packet.data))
+		})
+	}
+}
+
+/// Reads the packets of a single logical stream from a physical Ogg stream,
+/// without requiring the reader to be seekable.
+struct OggPacketReader<R> {
+	inner: R,
+	reader: BasePacketReader,
+	/// The serial of the stream being read, which is the first one found.
+	serial: Option<u32>,
+}
+
+impl<R: Read> OggPacketReader<R> {
+	/// Reads the next packet of the stream, skipping those of other streams.
+	fn read_packet(&mut self) -> Result<Option<ogg::Packet>> {
+		loop {
+			while let Some(packet) = self.reader.read_packet() {
+				if *self.serial.get_or_insert(packet.stream_serial()) == packet.stream_serial() {
+					return Ok(Some(packet));
+				}
+			}
+			match self.read_page()? {
+				Some(page) => self.reader.push_page(page)?,
+				None => return Ok(None),
+			}
+		}
+	}
+
+	/// Reads the next page, returning `None` if the stream ends cleanly
+	/// before it.
+	fn read_page(&mut self) -> Result<Option<OggPage>> {
+		let mut header = [0; 27];
+		let read = read_fully(&mut self.inner, &mut header)?;
+		if read == 0 {
+			return Ok(None);
+		} else if read < header.len() {
+			bail!("stream ends in the middle of a page header");
+		}
+		if !header.starts_with(b"OggS") {
+			bail!("no Ogg capture pattern found");
+		}
+		let (mut parser, segments_len) = PageParser::new(header)?;
+		let mut segments = vec![0; segments_len];
+		self.inner.read_exact(&mut segments)?;
+		let mut data = vec![0; parser.parse_segments(segments)];
+		self.inner.read_exact(&mut data)?;
+		Ok(Some(parser.parse_packet_data(data)?))
+	}
+}
+
+/// Reads into `buf` until it's full or the reader ends, returning the number
+/// of bytes read.
+fn read_fully(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+	let mut read = 0;
+	while read < buf.len() {
+		match reader.read(&mut buf[read..]) {
+			Ok(0) => break,
+			Ok(n) => read += n,
+			Err(err) if err.kind() == ErrorKind::Interrupted => {}
+			Err(err) => return Err(err),
+		}
+	}
+	Ok(read)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use meowlouder_opus::{Channels, OpusApplication, OpusEncoder, SampleRate};
+	use ogg::PacketReader;
+	use std::io::Cursor;
+
+	/// Encodes 1 second of a 48 kHz mono tone, returning the packets along
+	/// with the encoder's lookahead.
+	fn encode_one_second() -> (Vec<Vec<u8>>, u16) {
+		let mut encoder =
+			OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Audio).unwrap();
+		let lookahead = encoder.lookahead().unwrap() as u16;
+		let packets = (0..50)
+			.map(|frame| {
+				let pcm = (frame * 960..(frame + 1) * 960)
+					.map(|i| {
+						let t = i as f32 / 48000.0;
+						((t * 440.0 * std::f32::consts::TAU).sin() * 16384.0) as i16
+					})
+					.collect::<Vec<_>>();
+				encoder.encode(&pcm, 960).unwrap()
+			})
+			.collect();
+		(packets, lookahead)
+	}
+
+	fn write_stream(packets: &[Vec<u8>], pre_skip: u16) -> Vec<u8> {
+		let mut writer = OggOpusWriter::new(Vec::new(), 1, pre_skip, 48000).unwrap();
+		for packet in packets {
+			writer.write_packet(packet.clone()).unwrap();
+		}
+		writer.finalize().unwrap()
+	}
+
+	#[test]
+	fn writes_parseable_stream() {
+		let (packets, pre_skip) = encode_one_second();
+		let stream = write_stream(&packets, pre_skip);
+
+		let mut reader = PacketReader::new(Cursor::new(stream));
+		let head = reader.read_packet_expected().unwrap();
+		assert!(head.first_in_stream() && head.last_in_page());
+		assert_eq!(OpusHead::parse(&head.data).unwrap(), OpusHead {
+			channels: 1,
+			pre_skip,
+			input_sample_rate: 48000,
+			output_gain: 0,
+			mapping_family: 0,
+		});
+		let tags = reader.read_packet_expected().unwrap();
+		assert!(tags.data.starts_with(b"OpusTags"));
+		assert!(tags.last_in_page());
+
+		let mut read = Vec::new();
+		while let Some(packet) = reader.read_packet().unwrap() {
+			assert_eq!(packet.stream_serial(), head.stream_serial());
+			read.push(packet);
+		}
+		let last = read.last().unwrap();
+		assert!(last.last_in_stream());
+		assert_eq!(last.absgp_page(), 48000);
+		let read = read
+			.into_iter()
+			.map(|packet| packet.data)
+			.collect::<Vec<_>>();
+		assert_eq!(read, packets);
+	}
+
+	#[test]
+	fn reader_round_trips_writer() {
+		let (packets, pre_skip) = encode_one_second();
+		let stream = write_stream(&packets, pre_skip);
+
+		let mut reader = OggOpusReader::new(&stream[..]).unwrap();
+		assert_eq!(reader.head().channels, 1);
+		assert_eq!(reader.head().pre_skip, pre_skip);
+		assert_eq!(reader.head().input_sample_rate, 48000);
+		let read = reader.packets().collect::<Result<Vec<_>>>().unwrap();
+		assert_eq!(read, packets);
+		assert!(reader.packets().next().is_none());
+	}
+
+	#[test]
+	fn reader_rejects_invalid_headers() {
+		assert!(OggOpusReader::new(&[][..]).is_err());
+		assert!(OggOpusReader::new(&b"not an ogg stream at all, just some bytes"[..]).is_err());
+
+		// An Ogg stream whose first packet isn't an OpusHead header.
+		let mut stream = Vec::new();
+		let mut writer = PacketWriter::new(&mut stream);
+		writer
+			.write_packet(&b"OpusTags"[..], 1, PacketWriteEndInfo::EndPage, 0)
+			.unwrap();
+		writer
+			.write_packet(&b"OpusTags"[..], 1, PacketWriteEndInfo::EndStream, 0)
+			.unwrap();
+		assert!(OggOpusReader::new(&stream[..]).is_err());
+	}
+}
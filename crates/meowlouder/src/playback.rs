@@ -0,0 +1,224 @@
+use crate::{capture::negotiate_config, err_fn};
+use anyhow::{anyhow, Result};
+use cpal::{
+	traits::{DeviceTrait, StreamTrait},
+	Device, FromSample, Sample, SampleFormat, SizedSample, Stream, SupportedStreamConfig,
+};
+use crossbeam_channel::Receiver;
+use meowlouder_opus::{error::OpusResult, Channels, OpusDecoder, SampleRate};
+use std::{
+	collections::VecDeque,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+/// Decodes Opus packets, and plays them back on a cpal output device.
+///
+/// Whenever the output device needs more audio than has been received, the
+/// missing audio is concealed, so playback never stalls. The output stream
+/// is started when the pipeline is created, and stopped when it is dropped.
+pub struct OpusCpalPlaybackPipeline {
+	stream: Stream,
+	config: SupportedStreamConfig,
+	underflows: Arc<AtomicUsize>,
+}
+
+impl OpusCpalPlaybackPipeline {
+	pub fn new(packets: Receiver<Vec<u8>>, device: &Device, sample_rate: u32) -> Result<Self> {
+		let config = negotiate_config(device.supported_output_configs()?, &[sample_rate])
+			.ok_or_else(|| anyhow!("output device doesn't support {sample_rate} Hz"))?;
+		let underflows = Arc::new(AtomicUsize::new(0));
+		let decoder = FrameDecoder::new(
+			OpusDecoder::new(
+				SampleRate::try_from(sample_rate)?,
+				Channels::try_from(i32::from(config.channels()))?,
+			)?,
+			packets,
+			sample_rate as usize,
+			underflows.clone(),
+		);
+		let stream = match config.sample_format() {
+			SampleFormat::F32 => build_stream::<f32>(device, &config, decoder)?,
+			SampleFormat::I16 => build_stream::<i16>(device, &config, decoder)?,
+			format => return Err(anyhow!("unsupported sample format {format}")),
+		};
+		stream.play()?;
+		Ok(Self {
+			stream,
+			config,
+			underflows,
+		})
+	}
+
+	/// The config the output stream was opened with.
+	pub fn config(&self) -> &SupportedStreamConfig {
+		&self.config
+	}
+
+	/// The number of times the output device asked for more audio than could
+	/// be decoded or concealed.
+	pub fn underflows(&self) -> usize {
+		self.underflows.load(Ordering::Relaxed)
+	}
+}
+
+impl Drop for OpusCpalPlaybackPipeline {
+	fn drop(&mut self) {
+		if let Err(err) = self.stream.pause() {
+			eprintln!("failed to stop output stream: {}", err);
+		}
+	}
+}
+
+fn build_stream<T>(
+	device: &Device,
+	config: &SupportedStreamConfig,
+	mut decoder: FrameDecoder,
+) -> Result<Stream>
+where
+	T: SizedSample + FromSample<i16> + Send + 'static,
+{
+	Ok(device.build_output_stream(
+		&config.config(),
+		move |data: &mut [T], _: &_| decoder.fill(data),
+		err_fn,
+		None,
+	)?)
+}
+
+/// Decodes packets as the output device asks for audio, buffering whatever
+/// doesn't fit in the device's buffer for the next callback.
+struct FrameDecoder {
+	decoder: OpusDecoder,
+	packets: Receiver<Vec<u8>>,
+	/// The largest possible frame (120 ms), for decoding packets.
+	max_frame_size: usize,
+	/// The duration concealed at a time (20 ms) when no packet is available.
+	conceal_frame_size: usize,
+	pcm: Vec<i16>,
+	buffer: VecDeque<i16>,
+	underflows: Arc<AtomicUsize>,
+}
+
+impl FrameDecoder {
+	fn new(
+		decoder: OpusDecoder,
+		packets: Receiver<Vec<u8>>,
+		sample_rate: usize,
+		underflows: Arc<AtomicUsize>,
+	) -> Self {
+		let max_frame_size = sample_rate * 120 / 1000;
+		let channels = decoder.channels();
+		Self {
+			decoder,
+			packets,
+			max_frame_size,
+			conceal_frame_size: sample_rate / 50,
+			pcm: vec![0; max_frame_size * channels],
+			buffer: VecDeque::new(),
+			underflows,
+		}
+	}
+
+	fn fill<T: Sample + FromSample<i16>>(&mut self, output: &mut [T]) {
+		while self.buffer.len() < output.len() {
+			let decoded = match self.packets.try_recv() {
+				Ok(packet) => self.decode(Some(&packet)).or_else(|err| {
+					eprintln!("Decoding error: {}", err);
+					self.decode(None)
+				}),
+				Err(_) => self.decode(None),
+			};
+			if let Err(err) = decoded {
+				eprintln!("Concealment error: {}", err);
+				break;
+			}
+		}
+		if self.buffer.len() < output.len() {
+			self.underflows.fetch_add(1, Ordering::Relaxed);
+		}
+		for sample in output {
+			*sample = self
+				.buffer
+				.pop_front()
+				.map_or(T::EQUILIBRIUM, T::from_sample);
+		}
+	}
+
+	fn decode(&mut self, packet: Option<&[u8]>) -> OpusResult<()> {
+		let frame_size = match packet {
+			Some(_) => self.max_frame_size,
+			None => self.conceal_frame_size,
+		};
+		let len = self
+			.decoder
+			.decode_into(packet, &mut self.pcm, frame_size, false)?;
+		self.buffer
+			.extend(&self.pcm[..len * self.decoder.channels()]);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use meowlouder_opus::{OpusApplication, OpusEncoder};
+
+	/// Runs a decoder fed with 1 second of encoded stereo audio, asking for
+	/// `buffer_size` samples (per channel) at a time until 1 second has been
+	/// played, returning the decoder and the played audio.
+	fn play_one_second(buffer_size: usize) -> (FrameDecoder, Vec<f32>) {
+		let mut encoder = OpusEncoder::new(
+			SampleRate::Hz48000,
+			Channels::Stereo,
+			OpusApplication::Audio,
+		)
+		.unwrap();
+		let (sender, packets) = crossbeam_channel::unbounded();
+		for frame in 0..50 {
+			let pcm = (frame * 960..(frame + 1) * 960)
+				.flat_map(|i| {
+					let sample = (i as f32 * 440.0 / 48000.0 * std::f32::consts::TAU).sin();
+					[(sample * 16384.0) as i16; 2]
+				})
+				.collect::<Vec<_>>();
+			sender.send(encoder.encode(&pcm, 960).unwrap()).unwrap();
+		}
+		let mut decoder = FrameDecoder::new(
+			OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).unwrap(),
+			packets,
+			48000,
+			Arc::new(AtomicUsize::new(0)),
+		);
+		let mut played = Vec::new();
+		let mut output = vec![0.0; buffer_size * 2];
+		while played.len() < 48000 * 2 {
+			decoder.fill(&mut output);
+			played.extend_from_slice(&output);
+		}
+		(decoder, played)
+	}
+
+	#[test]
+	fn plays_without_underflows() {
+		// Device buffers both smaller and larger than an Opus frame.
+		for buffer_size in [256, 441, 960, 2048] {
+			let (decoder, played) = play_one_second(buffer_size);
+			assert_eq!(decoder.underflows.load(Ordering::Relaxed), 0);
+			assert!(played.iter().any(|&sample| sample.abs() > 0.25));
+		}
+	}
+
+	#[test]
+	fn conceals_when_out_of_packets() {
+		let (mut decoder, _) = play_one_second(960);
+		// Every packet has been played by now, so this can only be concealed.
+		let mut output = vec![0.0f32; 960 * 2 * 10];
+		decoder.fill(&mut output);
+		assert!(decoder.packets.is_empty());
+		assert_eq!(decoder.underflows.load(Ordering::Relaxed), 0);
+		assert!(decoder.decoder.concealment_count() > 0);
+	}
+}
@@ -29,28 +29,114 @@ fn build_opus_with_cmake(libopus_dir: &Path) -> PathBuf {
 		"cargo:info=Building libopus from {} with cmake.",
 		libopus_dir.display()
 	);
-	cmake::Config::new(libopus_dir)
+	let mut config = cmake::Config::new(libopus_dir);
+	configure_features(&mut config);
+	config.build()
+}
+
+/// Builds libopus for `wasm32-unknown-unknown` with the emscripten toolchain
+/// from the active emsdk environment, as cmake can't target it otherwise.
+fn build_opus_with_emscripten(libopus_dir: &Path) -> PathBuf {
+	let emsdk = std::env::var("EMSDK")
+		.map(PathBuf::from)
+		.unwrap_or_else(|_| {
+			panic!(
+				"building libopus for wasm32-unknown-unknown needs emscripten: activate the emsdk \
+				 environment, or set LIBOPUS_WASM_PATH to a directory with a prebuilt libopus.a"
+			)
+		});
+	println!(
+		"cargo:info=Building libopus from {} with emscripten from {}.",
+		libopus_dir.display(),
+		emsdk.display()
+	);
+	let mut config = cmake::Config::new(libopus_dir);
+	config
+		.target("wasm32-unknown-emscripten")
 		.define(
-			"OPUS_DRED",
-			std::env::var("CARGO_FEATURE_DRED")
-				.map(|_| "True")
-				.unwrap_or("False"),
+			"CMAKE_TOOLCHAIN_FILE",
+			emsdk.join("upstream/emscripten/cmake/Modules/Platform/Emscripten.cmake"),
 		)
-		.build()
+		// Neither is supported by emscripten.
+		.define("OPUS_STACK_PROTECTOR", "False")
+		.define("OPUS_FORTIFY_SOURCE", "False");
+	configure_features(&mut config);
+	config.build()
+}
+
+/// Enables the libopus build options for the enabled cargo features.
+fn configure_features(config: &mut cmake::Config) {
+	config
+		.define("OPUS_CUSTOM_MODES", cmake_feature("CUSTOM"))
+		.define("OPUS_DRED", cmake_feature("DRED"))
+		// OSCE builds on top of the deep PLC, so it needs both.
+		.define("OPUS_DEEP_PLC", cmake_feature("OSCE"))
+		.define("OPUS_OSCE", cmake_feature("OSCE"));
+	// Leave the neural network weights out of the library, to be loaded at
+	// runtime instead. libopus has no build option for this.
+	if std::env::var("CARGO_FEATURE_WEIGHTS_FILE").is_ok() {
+		config.cflag("-DUSE_WEIGHTS_FILE");
+	}
+}
+
+/// Returns whether libopus is being built for `wasm32-unknown-unknown`.
+fn is_wasm_unknown() -> bool {
+	std::env::var("TARGET").is_ok_and(|target| target == "wasm32-unknown-unknown")
+}
+
+/// Returns the directory with a prebuilt WebAssembly `libopus.a` given by
+/// `LIBOPUS_WASM_PATH`, if any.
+fn get_prebuilt_wasm_dir() -> Option<PathBuf> {
+	let dir = PathBuf::from(std::env::var_os("LIBOPUS_WASM_PATH")?);
+	if !dir.join("libopus.a").exists() {
+		panic!(
+			"given LIBOPUS_WASM_PATH directory ({}) doesn't contain libopus.a!",
+			dir.display()
+		);
+	}
+	Some(dir)
 }
 
-fn link_opus(libopus_build_dir: &Path) {
+/// Returns the cmake boolean for whether a cargo feature is enabled.
+fn cmake_feature(feature: &str) -> &'static str {
+	std::env::var(format!("CARGO_FEATURE_{feature}"))
+		.map(|_| "True")
+		.unwrap_or("False")
+}
+
+fn link_opus(libopus_lib_dir: &Path) {
 	println!(
 		"cargo:info=Linking libopus from {}",
-		libopus_build_dir.display()
+		libopus_lib_dir.display()
 	);
 	println!("cargo:rustc-link-lib=static=opus");
 	println!(
 		"cargo:rustc-link-search=native={}",
-		libopus_build_dir.join("lib").display()
+		libopus_lib_dir.display()
 	);
 }
 
+/// Links the system libopus found with pkg-config.
+fn link_system_opus() {
+	const SOURCE_BUILD_FEATURES: [&str; 4] = ["CUSTOM", "DRED", "OSCE", "WEIGHTS_FILE"];
+	if let Some(feature) = SOURCE_BUILD_FEATURES
+		.iter()
+		.find(|feature| std::env::var(format!("CARGO_FEATURE_{feature}")).is_ok())
+	{
+		panic!(
+			"the {} feature configures the libopus build, so it can't be combined with \
+			 system-libopus",
+			feature.to_lowercase().replace('_', "-")
+		);
+	}
+	// The projection API, which the bindings include, was added in 1.3.
+	let library = pkg_config::Config::new()
+		.atleast_version("1.3")
+		.probe("opus")
+		.expect("failed to find the system libopus with pkg-config");
+	println!("cargo:info=Linking the system libopus {}", library.version);
+}
+
 fn generate_bindings() {
 	const ALLOW_LINTS: &str = r#"
 #![allow(
@@ -63,7 +149,7 @@ fn generate_bindings() {
 	let out_file = std::env::var("CARGO_MANIFEST_DIR")
 		.map(PathBuf::from)
 		.expect("CARGO_MANIFEST_DIR not set")
-		.join("src/lib.rs");
+		.join("src/bindings.rs");
 	let bindings = bindgen::Builder::default()
 		.header("src/bindings.h")
 		.raw_line(ALLOW_LINTS.trim())
@@ -84,9 +170,28 @@ fn main() {
 	println!("cargo:rerun-if-changed=src/bindings.h");
 	println!("cargo:rerun-if-changed=libopus/include");
 	println!("cargo:rerun-if-changed=libopus/src");
+	println!("cargo:rerun-if-env-changed=LIBOPUS_WASM_PATH");
+	// The system libopus has its bindings in src/lib_system.rs instead, as its
+	// headers may not be the ones from the submodule.
+	if std::env::var("CARGO_FEATURE_SYSTEM_LIBOPUS").is_ok() {
+		link_system_opus();
+		return;
+	}
+	if is_wasm_unknown() {
+		match get_prebuilt_wasm_dir() {
+			Some(dir) => link_opus(&dir),
+			None => {
+				let build_dir = build_opus_with_emscripten(&get_libopus_dir());
+				link_opus(&build_dir.join("lib"));
+			}
+		}
+		// bindgen can't parse the headers for this target without a libc
+		// sysroot, and the committed bindings are the same on every target.
+		return;
+	}
 	let libopus_dir = get_libopus_dir();
 	let build_dir = build_opus_with_cmake(&libopus_dir);
-	link_opus(&build_dir);
+	link_opus(&build_dir.join("lib"));
 	generate_bindings();
 }
 
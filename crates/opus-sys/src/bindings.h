@@ -1 +1,4 @@
-#include "../libopus/include/opus_multistream.h"
+#include "../libopus/include/opus_projection.h"
+/* Declares opus_custom_encoder_init, which is only exported with custom modes. */
+#define CUSTOM_MODES
+#include "../libopus/include/opus_custom.h"
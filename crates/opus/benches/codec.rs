@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Encoding and decoding throughput benchmarks.
+//!
+//! Every frame is 20 ms of mono audio at 48 kHz, encoded at 64 kbps, and the
+//! codec benchmarks are run at complexities 0, 5 and 10.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use meowlouder_opus::{
+	packet, BufferPool, Channels, OpusApplication, OpusDecoder, OpusEncoder, OpusPacket, SampleRate,
+};
+
+const FRAME_SIZE: usize = 960;
+const BITRATE: i32 = 64000;
+const BATCH_FRAMES: usize = 100;
+const COMPLEXITIES: [i32; 3] = [0, 5, 10];
+
+/// Generates `frames` frames of a chord with some noise, so the encoder has
+/// something more realistic than silence to work with.
+fn pcm_f32(frames: usize) -> Vec<f32> {
+	let mut noise = 0x1234_5678u32;
+	(0..frames * FRAME_SIZE)
+		.map(|i| {
+			// A xorshift generator, so the input is the same on every run.
+			noise ^= noise << 13;
+			noise ^= noise >> 17;
+			noise ^= noise << 5;
+			let t = i as f32 / 48000.0;
+			let tone = [220.0, 277.2, 329.6]
+				.iter()
+				.map(|freq| (t * freq * std::f32::consts::TAU).sin())
+				.sum::<f32>();
+			0.2 * tone + 0.05 * (noise as f32 / u32::MAX as f32 - 0.5)
+		})
+		.collect()
+}
+
+fn pcm_i16(frames: usize) -> Vec<i16> {
+	pcm_f32(frames)
+		.into_iter()
+		.map(|sample| (sample * 32767.0) as i16)
+		.collect()
+}
+
+fn encoder(complexity: i32) -> OpusEncoder {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	encoder.set_bitrate(BITRATE).expect("failed to set bitrate");
+	encoder
+		.set_complexity(complexity)
+		.expect("failed to set complexity");
+	encoder
+}
+
+fn decoder(complexity: i32) -> OpusDecoder {
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Mono).expect("failed to create decoder");
+	decoder
+		.set_complexity(complexity)
+		.expect("failed to set complexity");
+	decoder
+}
+
+/// Encodes `frames` frames at complexity 10, to have packets to decode.
+fn packets(frames: usize) -> Vec<Vec<u8>> {
+	encoder(10)
+		.batch_encode(&pcm_i16(frames), FRAME_SIZE)
+		.expect("failed to encode")
+}
+
+fn encode(c: &mut Criterion) {
+	let pcm_i16 = pcm_i16(1);
+	let pcm_f32 = pcm_f32(1);
+	let mut data = [0; 1275];
+
+	let mut group = c.benchmark_group("encode_i16_48k_20ms");
+	group.throughput(Throughput::Elements(FRAME_SIZE as u64));
+	for complexity in COMPLEXITIES {
+		let mut encoder = encoder(complexity);
+		group.bench_with_input(
+			BenchmarkId::from_parameter(complexity),
+			&pcm_i16,
+			|b, pcm| b.iter(|| encoder.encode_into(black_box(pcm), FRAME_SIZE, &mut data)),
+		);
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("encode_f32_48k_20ms");
+	group.throughput(Throughput::Elements(FRAME_SIZE as u64));
+	for complexity in COMPLEXITIES {
+		let mut encoder = encoder(complexity);
+		group.bench_with_input(
+			BenchmarkId::from_parameter(complexity),
+			&pcm_f32,
+			|b, pcm| b.iter(|| encoder.encode_into(black_box(pcm), FRAME_SIZE, &mut data)),
+		);
+	}
+	group.finish();
+}
+
+/// Compares allocating a new buffer for every packet with taking them from a
+/// [`BufferPool`], in the steady state where every packet is dropped before
+/// the next is encoded.
+fn pooled(c: &mut Criterion) {
+	let pcm = pcm_i16(1);
+	let pool = BufferPool::default();
+
+	let mut group = c.benchmark_group("encode_alloc_48k_20ms");
+	group.throughput(Throughput::Elements(FRAME_SIZE as u64));
+	let mut plain_encoder = encoder(5);
+	group.bench_function("encode", |b| {
+		b.iter(|| plain_encoder.encode(black_box(&pcm), FRAME_SIZE))
+	});
+	let mut pooled_encoder = encoder(5);
+	group.bench_function("encode_pooled", |b| {
+		b.iter(|| pooled_encoder.encode_pooled(black_box(&pcm), FRAME_SIZE, &pool))
+	});
+	group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+	let packets = packets(1);
+	let packet = &packets[0];
+	let mut pcm_i16 = [0i16; FRAME_SIZE];
+	let mut pcm_f32 = [0f32; FRAME_SIZE];
+
+	let mut group = c.benchmark_group("decode_i16_48k_20ms");
+	group.throughput(Throughput::Elements(FRAME_SIZE as u64));
+	for complexity in COMPLEXITIES {
+		let mut decoder = decoder(complexity);
+		group.bench_with_input(
+			BenchmarkId::from_parameter(complexity),
+			packet,
+			|b, packet| {
+				b.iter(|| {
+					decoder.decode_into(Some(black_box(packet)), &mut pcm_i16, FRAME_SIZE, false)
+				})
+			},
+		);
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("decode_f32_48k_20ms");
+	group.throughput(Throughput::Elements(FRAME_SIZE as u64));
+	for complexity in COMPLEXITIES {
+		let mut decoder = decoder(complexity);
+		group.bench_with_input(
+			BenchmarkId::from_parameter(complexity),
+			packet,
+			|b, packet| {
+				b.iter(|| {
+					decoder.decode_float_into(
+						Some(black_box(packet)),
+						&mut pcm_f32,
+						FRAME_SIZE,
+						false,
+					)
+				})
+			},
+		);
+	}
+	group.finish();
+}
+
+fn batch(c: &mut Criterion) {
+	let pcm = pcm_i16(BATCH_FRAMES);
+	let packets = packets(BATCH_FRAMES);
+	let mut decoded = [0i16; FRAME_SIZE];
+
+	let mut group = c.benchmark_group("encode_batch_100_frames");
+	group.throughput(Throughput::Elements((BATCH_FRAMES * FRAME_SIZE) as u64));
+	for complexity in COMPLEXITIES {
+		let mut batch_encoder = encoder(complexity);
+		group.bench_with_input(
+			BenchmarkId::new("batch_encode", complexity),
+			&pcm,
+			|b, pcm| b.iter(|| batch_encoder.batch_encode(black_box(pcm), FRAME_SIZE)),
+		);
+		// The same frames encoded one by one, allocating a full size buffer
+		// for each packet, to compare against the shared buffer of
+		// `batch_encode`.
+		let mut encoder = encoder(complexity);
+		group.bench_with_input(BenchmarkId::new("encode", complexity), &pcm, |b, pcm| {
+			b.iter(|| {
+				black_box(pcm)
+					.chunks_exact(FRAME_SIZE)
+					.map(|frame| encoder.encode(frame, FRAME_SIZE))
+					.collect::<Result<Vec<_>, _>>()
+			})
+		});
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("decode_batch_100_frames");
+	group.throughput(Throughput::Elements((BATCH_FRAMES * FRAME_SIZE) as u64));
+	for complexity in COMPLEXITIES {
+		let mut decoder = decoder(complexity);
+		group.bench_with_input(
+			BenchmarkId::from_parameter(complexity),
+			&packets,
+			|b, packets| {
+				b.iter(|| {
+					for packet in packets {
+						decoder
+							.decode_into(Some(black_box(packet)), &mut decoded, FRAME_SIZE, false)
+							.expect("failed to decode");
+					}
+				})
+			},
+		);
+	}
+	group.finish();
+}
+
+fn inspect(c: &mut Criterion) {
+	let data = packets(1).remove(0);
+	let packet = OpusPacket::try_from(data.clone()).expect("invalid packet");
+
+	let mut group = c.benchmark_group("packet");
+	group.bench_function("nb_frames", |b| {
+		b.iter(|| packet::nb_frames(black_box(&data)))
+	});
+	group.bench_function("OpusPacket::frames", |b| {
+		b.iter(|| black_box(&packet).frames())
+	});
+	group.bench_function("validate_packet", |b| {
+		b.iter(|| packet::validate_packet(black_box(&data)))
+	});
+	group.finish();
+}
+
+criterion_group!(benches, encode, pooled, decode, batch, inspect);
+criterion_main!(benches);
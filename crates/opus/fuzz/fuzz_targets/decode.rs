@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MPL-2.0
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use meowlouder_opus::{error::OpusErrorCode, packet, Channels, OpusDecoder, SampleRate};
+
+const FRAME_SIZE: usize = 960;
+
+fuzz_target!(|data: &[u8]| {
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).expect("failed to create decoder");
+	match decoder.decode(Some(data), FRAME_SIZE, false) {
+		Ok(pcm) => assert!(pcm.len() <= FRAME_SIZE * 2),
+		Err(err) if err == OpusErrorCode::InvalidPacket => {}
+		// A valid packet longer than 20 ms doesn't fit in the frame.
+		Err(err) if err == OpusErrorCode::BufferTooSmall => {
+			assert!(packet::nb_samples(data, 48000).is_ok_and(|samples| samples > FRAME_SIZE));
+		}
+		Err(err) => panic!("unexpected error: {err}"),
+	}
+});
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MPL-2.0
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use meowlouder_opus::{
+	util::pcm_i16_to_f32, Channels, FrameDuration, FrameSize, OpusApplication, OpusEncoder,
+	SampleRate,
+};
+
+const SAMPLE_RATES: [SampleRate; 5] = [
+	SampleRate::Hz8000,
+	SampleRate::Hz12000,
+	SampleRate::Hz16000,
+	SampleRate::Hz24000,
+	SampleRate::Hz48000,
+];
+
+const FRAME_DURATIONS: [FrameDuration; 9] = [
+	FrameDuration::Ms2_5,
+	FrameDuration::Ms5,
+	FrameDuration::Ms10,
+	FrameDuration::Ms20,
+	FrameDuration::Ms40,
+	FrameDuration::Ms60,
+	FrameDuration::Ms80,
+	FrameDuration::Ms100,
+	FrameDuration::Ms120,
+];
+
+fuzz_target!(|input: (Vec<i16>, u8)| {
+	let (mut pcm, config) = input;
+	let config = config as usize;
+	let sample_rate = SAMPLE_RATES[config % SAMPLE_RATES.len()];
+	let channels = if config / SAMPLE_RATES.len() % 2 == 0 {
+		Channels::Mono
+	} else {
+		Channels::Stereo
+	};
+	let duration = FRAME_DURATIONS[config / (SAMPLE_RATES.len() * 2) % FRAME_DURATIONS.len()];
+	let frame_size = FrameSize::from_duration(duration, sample_rate);
+	// Pad short input with silence rather than skipping it, so every input
+	// reaches the encoder.
+	pcm.resize(frame_size.samples() * channels.count(), 0);
+
+	let mut encoder = OpusEncoder::new(sample_rate, channels, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	encoder
+		.encode(&pcm, frame_size)
+		.expect("failed to encode i16 PCM");
+
+	let mut pcm_f32 = vec![0.0; pcm.len()];
+	pcm_i16_to_f32(&pcm, &mut pcm_f32);
+	let mut encoder = OpusEncoder::new(sample_rate, channels, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	encoder
+		.encode(&pcm_f32, frame_size)
+		.expect("failed to encode f32 PCM");
+});
@@ -0,0 +1,10 @@
+// SPDX-License-Identifier: MPL-2.0
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use meowlouder_opus::{error::OpusErrorCode, packet};
+
+fuzz_target!(|data: &[u8]| {
+	if let Err(err) = packet::validate_packet(data) {
+		assert_eq!(err, OpusErrorCode::InvalidPacket);
+	}
+});
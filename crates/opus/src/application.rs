@@ -1,10 +1,15 @@
 // SPDX-License-Identifier: MPL-2.0
+use crate::error::{OpusErrorCode, ParseNameError};
+use core::{
+	fmt::{Display, Error as FmtError, Formatter},
+	str::FromStr,
+};
 use meowlouder_opus_sys::{
 	OPUS_APPLICATION_AUDIO, OPUS_APPLICATION_RESTRICTED_LOWDELAY, OPUS_APPLICATION_VOIP,
 };
 
 /// The coding mode for an Opus encoder.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u32)]
 pub enum OpusApplication {
 	/// Best for most VoIP/videoconference applications where listening quality
@@ -18,6 +23,26 @@ pub enum OpusApplication {
 	RestrictedLowDelay = OPUS_APPLICATION_RESTRICTED_LOWDELAY,
 }
 
+impl OpusApplication {
+	pub(crate) const NAMES: &'static [&'static str] = &["voip", "audio", "lowdelay"];
+
+	/// Returns the lowercase name of the application, as used in configuration
+	/// files.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Voip => "voip",
+			Self::Audio => "audio",
+			Self::RestrictedLowDelay => "lowdelay",
+		}
+	}
+}
+
+impl Display for OpusApplication {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.write_str(self.name())
+	}
+}
+
 impl From<OpusApplication> for u32 {
 	fn from(value: OpusApplication) -> Self {
 		value as u32
@@ -29,3 +54,37 @@ impl From<OpusApplication> for i32 {
 		value as i32
 	}
 }
+
+impl TryFrom<i32> for OpusApplication {
+	type Error = OpusErrorCode;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value as u32 {
+			OPUS_APPLICATION_VOIP => Ok(Self::Voip),
+			OPUS_APPLICATION_AUDIO => Ok(Self::Audio),
+			OPUS_APPLICATION_RESTRICTED_LOWDELAY => Ok(Self::RestrictedLowDelay),
+			_ => Err(OpusErrorCode::BadArg),
+		}
+	}
+}
+
+impl FromStr for OpusApplication {
+	type Err = ParseNameError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"voip" => Ok(Self::Voip),
+			"audio" => Ok(Self::Audio),
+			"lowdelay" => Ok(Self::RestrictedLowDelay),
+			_ => Err(ParseNameError::new("application", s, Self::NAMES)),
+		}
+	}
+}
+
+impl TryFrom<&str> for OpusApplication {
+	type Error = ParseNameError;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		value.parse()
+	}
+}
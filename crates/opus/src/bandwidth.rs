@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::{OpusErrorCode, ParseNameError};
+use core::{
+	fmt::{Display, Error as FmtError, Formatter},
+	str::FromStr,
+};
+use meowlouder_opus_sys::{
+	OPUS_BANDWIDTH_FULLBAND, OPUS_BANDWIDTH_MEDIUMBAND, OPUS_BANDWIDTH_NARROWBAND,
+	OPUS_BANDWIDTH_SUPERWIDEBAND, OPUS_BANDWIDTH_WIDEBAND,
+};
+
+/// The audio bandpass of an Opus stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum OpusBandwidth {
+	/// 4 kHz bandpass.
+	Narrowband = OPUS_BANDWIDTH_NARROWBAND,
+	/// 6 kHz bandpass.
+	Mediumband = OPUS_BANDWIDTH_MEDIUMBAND,
+	/// 8 kHz bandpass.
+	Wideband = OPUS_BANDWIDTH_WIDEBAND,
+	/// 12 kHz bandpass.
+	Superwideband = OPUS_BANDWIDTH_SUPERWIDEBAND,
+	/// 20 kHz bandpass.
+	Fullband = OPUS_BANDWIDTH_FULLBAND,
+}
+
+impl OpusBandwidth {
+	pub(crate) const NAMES: &'static [&'static str] = &[
+		"narrowband",
+		"mediumband",
+		"wideband",
+		"superwideband",
+		"fullband",
+	];
+
+	/// Returns the lowercase name of the bandwidth, as used in configuration
+	/// files.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Narrowband => "narrowband",
+			Self::Mediumband => "mediumband",
+			Self::Wideband => "wideband",
+			Self::Superwideband => "superwideband",
+			Self::Fullband => "fullband",
+		}
+	}
+}
+
+impl Display for OpusBandwidth {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.write_str(self.name())
+	}
+}
+
+impl From<OpusBandwidth> for u32 {
+	fn from(value: OpusBandwidth) -> Self {
+		value as u32
+	}
+}
+
+impl From<OpusBandwidth> for i32 {
+	fn from(value: OpusBandwidth) -> Self {
+		value as i32
+	}
+}
+
+impl TryFrom<i32> for OpusBandwidth {
+	type Error = OpusErrorCode;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value as u32 {
+			OPUS_BANDWIDTH_NARROWBAND => Ok(Self::Narrowband),
+			OPUS_BANDWIDTH_MEDIUMBAND => Ok(Self::Mediumband),
+			OPUS_BANDWIDTH_WIDEBAND => Ok(Self::Wideband),
+			OPUS_BANDWIDTH_SUPERWIDEBAND => Ok(Self::Superwideband),
+			OPUS_BANDWIDTH_FULLBAND => Ok(Self::Fullband),
+			_ => Err(OpusErrorCode::BadArg),
+		}
+	}
+}
+
+impl FromStr for OpusBandwidth {
+	type Err = ParseNameError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"narrowband" => Ok(Self::Narrowband),
+			"mediumband" => Ok(Self::Mediumband),
+			"wideband" => Ok(Self::Wideband),
+			"superwideband" => Ok(Self::Superwideband),
+			"fullband" => Ok(Self::Fullband),
+			_ => Err(ParseNameError::new("bandwidth", s, Self::NAMES)),
+		}
+	}
+}
+
+impl TryFrom<&str> for OpusBandwidth {
+	type Error = ParseNameError;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		value.parse()
+	}
+}
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	application::OpusApplication, channels::Channels, encode::OpusEncoder, sample_rate::SampleRate,
+};
+use meowlouder_opus_sys::{
+	opus_decoder_get_size, opus_encoder_ctl, OPUS_GET_DRED_DURATION_REQUEST, OPUS_UNIMPLEMENTED,
+};
+
+/// The size above which a mono decoder must hold the deep PLC's state, which
+/// is over 100 KiB on its own, while the rest of the decoder is around 20
+/// KiB.
+const DEEP_PLC_DECODER_SIZE: usize = 64 * 1024;
+
+/// The optional libopus features which were compiled in, as returned by
+/// [`capabilities`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+	/// Whether the deep packet loss concealment is available, which is
+	/// enabled by a decoder complexity of 5 or more. This is built along with
+	/// either the `dred` or `osce` feature.
+	pub deep_plc: bool,
+	/// Whether Deep REDundancy (DRED) is available. This is built with the
+	/// `dred` feature.
+	pub dred: bool,
+	/// Whether OSCE speech enhancement is available, which is enabled by a
+	/// decoder complexity of 6 or more. This is built with the `osce` feature.
+	pub osce: bool,
+}
+
+/// Returns which of the optional libopus features were compiled in.
+///
+/// The deep PLC and DRED are probed for in the linked libopus, but libopus
+/// has no way to tell whether OSCE was compiled in, so that reflects whether
+/// the `osce` feature is enabled.
+pub fn capabilities() -> Capabilities {
+	Capabilities {
+		deep_plc: has_deep_plc(),
+		dred: has_dred(),
+		osce: cfg!(feature = "osce"),
+	}
+}
+
+/// Returns whether the linked libopus supports Deep REDundancy (DRED).
+///
+/// Without it, the DRED functions fail with
+/// [`OpusErrorCode::Unimplemented`](crate::error::OpusErrorCode::Unimplemented),
+/// so this can be used to disable redundancy features up front.
+pub fn has_dred() -> bool {
+	let Ok(mut encoder) =
+		OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Voip)
+	else {
+		return false;
+	};
+	// The encoder only knows the DRED requests when DRED is compiled in.
+	let mut dred_duration = 0;
+	let ret = unsafe {
+		opus_encoder_ctl(
+			encoder.encoder_state.as_mut_ptr().cast(),
+			OPUS_GET_DRED_DURATION_REQUEST as _,
+			&mut dred_duration,
+		)
+	};
+	ret != OPUS_UNIMPLEMENTED
+}
+
+/// Returns whether the linked libopus supports the deep packet loss
+/// concealment.
+pub fn has_deep_plc() -> bool {
+	// There's no request which is only known with the deep PLC, but its state
+	// is stored in the decoder, and dwarfs everything else there.
+	unsafe { opus_decoder_get_size(1) as usize > DEEP_PLC_DECODER_SIZE }
+}
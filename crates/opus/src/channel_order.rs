@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Conversion between the SMPTE channel order, used by WAV files and most
+//! audio APIs, and the Vorbis channel order, used by Opus multistream audio
+//! with mapping family 1.
+//!
+//! The two orders agree for mono, stereo and quadraphonic audio, but differ
+//! in where the center and LFE channels go for everything else, so surround
+//! audio needs converting on its way into and out of the codec.
+
+/// For each number of channels from 1 to 8, the SMPTE channel at each
+/// position of the Vorbis channel order.
+const SMPTE_TO_VORBIS: [&[usize]; 8] = [
+	&[0],
+	&[0, 1],
+	&[0, 2, 1],
+	&[0, 1, 2, 3],
+	&[0, 2, 1, 3, 4],
+	&[0, 2, 1, 4, 5, 3],
+	&[0, 2, 1, 5, 6, 4, 3],
+	&[0, 2, 1, 6, 7, 4, 5, 3],
+];
+
+/// Returns the permutation from SMPTE to Vorbis channel order for
+/// `channels` channels, or `None` if there isn't a standard order for that
+/// many channels.
+///
+/// Each entry is the index of the SMPTE channel which goes at that position
+/// of the Vorbis order, so for 5.1 audio, which is front left, front right,
+/// center, LFE, rear left, rear right in SMPTE order, this is
+/// `[0, 2, 1, 4, 5, 3]`: front left, center, front right, rear left, rear
+/// right, LFE.
+pub fn smpte_to_vorbis_permutation(channels: usize) -> Option<&'static [usize]> {
+	SMPTE_TO_VORBIS.get(channels.checked_sub(1)?).copied()
+}
+
+/// Reorders interleaved audio from SMPTE to Vorbis channel order in-place.
+///
+/// This works on samples of any type, such as `i16` and `f32`.
+///
+/// # Panics
+///
+/// Panics if `channels` isn't between 1 and 8, or if `interleaved` doesn't
+/// hold a whole number of frames.
+pub fn smpte_to_vorbis<T: Copy>(channels: usize, interleaved: &mut [T]) {
+	let permutation = permutation_for(channels, interleaved);
+	for frame in interleaved.chunks_exact_mut(channels) {
+		let mut smpte = [frame[0]; 8];
+		smpte[..channels].copy_from_slice(frame);
+		for (sample, &source) in frame.iter_mut().zip(permutation) {
+			*sample = smpte[source];
+		}
+	}
+}
+
+/// Reorders interleaved audio from Vorbis to SMPTE channel order in-place,
+/// undoing [`smpte_to_vorbis`].
+///
+/// This works on samples of any type, such as `i16` and `f32`.
+///
+/// # Panics
+///
+/// Panics if `channels` isn't between 1 and 8, or if `interleaved` doesn't
+/// hold a whole number of frames.
+pub fn vorbis_to_smpte<T: Copy>(channels: usize, interleaved: &mut [T]) {
+	let permutation = permutation_for(channels, interleaved);
+	for frame in interleaved.chunks_exact_mut(channels) {
+		let mut vorbis = [frame[0]; 8];
+		vorbis[..channels].copy_from_slice(frame);
+		for (&sample, &destination) in vorbis.iter().zip(permutation) {
+			frame[destination] = sample;
+		}
+	}
+}
+
+fn permutation_for<T>(channels: usize, interleaved: &[T]) -> &'static [usize] {
+	let permutation = smpte_to_vorbis_permutation(channels)
+		.unwrap_or_else(|| panic!("no standard channel order for {channels} channels"));
+	assert!(
+		interleaved.len() % channels == 0,
+		"interleaved audio isn't a whole number of {channels} channel frames"
+	);
+	permutation
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::OpusErrorCode;
+
+/// The number of channels in a single-stream Opus encoder or decoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(i32)]
+pub enum Channels {
+	Mono = 1,
+	Stereo = 2,
+}
+
+impl Channels {
+	/// Returns the number of channels.
+	pub const fn count(self) -> usize {
+		self as usize
+	}
+}
+
+impl From<Channels> for i32 {
+	fn from(value: Channels) -> Self {
+		value as i32
+	}
+}
+
+impl From<Channels> for usize {
+	fn from(value: Channels) -> Self {
+		value.count()
+	}
+}
+
+impl TryFrom<i32> for Channels {
+	type Error = OpusErrorCode;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value {
+			1 => Ok(Self::Mono),
+			2 => Ok(Self::Stereo),
+			_ => Err(OpusErrorCode::BadArg),
+		}
+	}
+}
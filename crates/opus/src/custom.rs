@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Opus Custom, which codes CELT-only audio at sampling rates and frame sizes
+//! that standard Opus doesn't support, such as 128 sample frames at 44.1 kHz.
+//!
+//! The streams are not compatible with standard Opus, and can only be decoded
+//! by a decoder created from an identical [`OpusCustomMode`].
+
+mod decoder;
+mod encoder;
+mod mode;
+
+pub use self::{decoder::OpusCustomDecoder, encoder::OpusCustomEncoder, mode::OpusCustomMode};
@@ -0,0 +1,282 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	capabilities::has_deep_plc,
+	custom::OpusCustomMode,
+	error::{ErrorContext, Operation, OpusError, OpusErrorCode, OpusResult},
+	ffi, map_error,
+	state::StateBuffer,
+};
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+use meowlouder_opus_sys::{
+	opus_custom_decode, opus_custom_decode_float, opus_custom_decoder_ctl,
+	opus_custom_decoder_get_size, opus_custom_decoder_init, OPUS_RESET_STATE,
+};
+
+/// An Opus Custom decoder, which decodes the packets of an
+/// [`OpusCustomEncoder`](crate::custom::OpusCustomEncoder) created from an
+/// identical [`OpusCustomMode`].
+///
+/// Lost packets are concealed by passing `None` as the packet to decode,
+/// unless libopus was built with the deep packet loss concealment (see
+/// [`has_deep_plc`](crate::has_deep_plc)), which libopus doesn't support for
+/// custom modes, in which case [`OpusErrorCode::Unimplemented`] is returned.
+///
+/// This is [`Send`] but not [`Sync`], like
+/// [`OpusEncoder`](crate::encode::OpusEncoder#threading).
+pub struct OpusCustomDecoder {
+	decoder_state: StateBuffer,
+	/// The mode the decoder state points to, which has to outlive it.
+	mode: Arc<OpusCustomMode>,
+	channels: usize,
+	_not_sync: PhantomData<*mut ()>,
+}
+
+// SAFETY: The only pointer in the decoder state is to `mode`, which is `Sync`,
+// never written to, and kept alive by the `Arc` for as long as the decoder.
+unsafe impl Send for OpusCustomDecoder {}
+
+impl OpusCustomDecoder {
+	/// Creates a new decoder for `mode`, with 1 or 2 channels.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] for any other number of channels.
+	pub fn new(mode: Arc<OpusCustomMode>, channels: u8) -> OpusResult<Self> {
+		if !(1..=2).contains(&channels) {
+			return Err(OpusError::new(
+				OpusErrorCode::BadArg,
+				Operation::DecoderInit,
+			));
+		}
+		let decoder_size =
+			unsafe { opus_custom_decoder_get_size(mode.as_ptr(), channels as _) as usize };
+		let mut decoder_state = StateBuffer::new(decoder_size);
+		map_error!(unsafe {
+			opus_custom_decoder_init(
+				decoder_state.as_mut_ptr().cast(),
+				mode.as_ptr(),
+				channels as _,
+			)
+		})
+		.context(Operation::DecoderInit)?;
+		Ok(Self {
+			decoder_state,
+			mode,
+			channels: channels as usize,
+			_not_sync: PhantomData,
+		})
+	}
+
+	pub fn decode_into<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: usize,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[i16]>,
+	{
+		let pcm = pcm.as_mut();
+		let required = ffi::pcm_len(frame_size, self.channels, Operation::Decode)?;
+		if pcm.len() < required {
+			return Err(OpusError::buffer_too_small(
+				Operation::Decode,
+				required,
+				pcm.len(),
+			));
+		}
+		// SAFETY: `pcm` has room for `frame_size` samples for each channel, which
+		// is the most libopus ever decodes.
+		unsafe { self.decode_into_unchecked(data, pcm, frame_size) }
+	}
+
+	/// Decodes like [`OpusCustomDecoder::decode_into`], without checking that
+	/// `pcm` is large enough.
+	///
+	/// # Safety
+	///
+	/// `pcm` must have room for the decoded audio for each channel, which is
+	/// the duration of the packet when decoding one normally, and `frame_size`
+	/// samples otherwise.
+	pub unsafe fn decode_into_unchecked<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: usize,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[i16]>,
+	{
+		let pcm = pcm.as_mut();
+
+		let (data_ptr, data_len) = match &data {
+			Some(data) => {
+				let data = data.as_ref();
+				(data.as_ptr(), ffi::c_len(data.len(), Operation::Decode)?)
+			}
+			None => (core::ptr::null(), 0),
+		};
+		let frame_size = ffi::c_len(frame_size, Operation::Decode)?;
+		check_concealment(data_len)?;
+
+		map_error!(usize, unsafe {
+			opus_custom_decode(
+				self.decoder_state.as_mut_ptr().cast(),
+				data_ptr,
+				data_len,
+				pcm.as_mut_ptr(),
+				frame_size,
+			)
+		})
+		.context(Operation::Decode)
+	}
+
+	pub fn decode<Data>(&mut self, data: Option<Data>, frame_size: usize) -> OpusResult<Vec<i16>>
+	where
+		Data: AsRef<[u8]>,
+	{
+		// libopus rejects anything larger than a frame of the mode, so check
+		// that before allocating room for it.
+		if frame_size > self.mode.frame_size() {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Decode));
+		}
+		let mut pcm = vec![0; ffi::pcm_len(frame_size, self.channels, Operation::Decode)?];
+		let len = self.decode_into(data, &mut pcm, frame_size)?;
+		pcm.truncate(len * self.channels);
+		Ok(pcm)
+	}
+
+	pub fn decode_float_into<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: usize,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[f32]>,
+	{
+		let pcm = pcm.as_mut();
+		let required = ffi::pcm_len(frame_size, self.channels, Operation::Decode)?;
+		if pcm.len() < required {
+			return Err(OpusError::buffer_too_small(
+				Operation::Decode,
+				required,
+				pcm.len(),
+			));
+		}
+		// SAFETY: `pcm` has room for `frame_size` samples for each channel, which
+		// is the most libopus ever decodes.
+		unsafe { self.decode_float_into_unchecked(data, pcm, frame_size) }
+	}
+
+	/// Decodes like [`OpusCustomDecoder::decode_float_into`], without checking
+	/// that `pcm` is large enough.
+	///
+	/// # Safety
+	///
+	/// `pcm` must have room for the decoded audio for each channel, which is
+	/// the duration of the packet when decoding one normally, and `frame_size`
+	/// samples otherwise.
+	pub unsafe fn decode_float_into_unchecked<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: usize,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[f32]>,
+	{
+		let pcm = pcm.as_mut();
+
+		let (data_ptr, data_len) = match &data {
+			Some(data) => {
+				let data = data.as_ref();
+				(data.as_ptr(), ffi::c_len(data.len(), Operation::Decode)?)
+			}
+			None => (core::ptr::null(), 0),
+		};
+		let frame_size = ffi::c_len(frame_size, Operation::Decode)?;
+		check_concealment(data_len)?;
+
+		map_error!(usize, unsafe {
+			opus_custom_decode_float(
+				self.decoder_state.as_mut_ptr().cast(),
+				data_ptr,
+				data_len,
+				pcm.as_mut_ptr(),
+				frame_size,
+			)
+		})
+		.context(Operation::Decode)
+	}
+
+	pub fn decode_float<Data>(
+		&mut self,
+		data: Option<Data>,
+		frame_size: usize,
+	) -> OpusResult<Vec<f32>>
+	where
+		Data: AsRef<[u8]>,
+	{
+		// libopus rejects anything larger than a frame of the mode, so check
+		// that before allocating room for it.
+		if frame_size > self.mode.frame_size() {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Decode));
+		}
+		let mut pcm = vec![0.0; ffi::pcm_len(frame_size, self.channels, Operation::Decode)?];
+		let len = self.decode_float_into(data, &mut pcm, frame_size)?;
+		pcm.truncate(len * self.channels);
+		Ok(pcm)
+	}
+
+	/// Resets the codec state to be equivalent to a freshly initialized state.
+	pub fn reset(&mut self) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_custom_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_RESET_STATE as _,
+			)
+		})
+		.context(Operation::Ctl("reset state"))
+	}
+
+	/// Returns the mode the decoder was created from.
+	pub fn mode(&self) -> &Arc<OpusCustomMode> {
+		&self.mode
+	}
+
+	/// Returns the number of channels the decoder was initialized with.
+	pub fn channels(&self) -> usize {
+		self.channels
+	}
+}
+
+impl Debug for OpusCustomDecoder {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusCustomDecoder")
+			.field("mode", &self.mode)
+			.field("channels", &self.channels)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Returns [`OpusErrorCode::Unimplemented`] if decoding a packet of
+/// `data_len` bytes would conceal a lost packet with the deep PLC.
+///
+/// libopus decodes packets of up to a byte as lost, and its deep PLC doesn't
+/// check for the custom decoder lacking its state, crashing instead.
+fn check_concealment(data_len: i32) -> OpusResult<()> {
+	if data_len <= 1 && has_deep_plc() {
+		return Err(OpusError::new(
+			OpusErrorCode::Unimplemented,
+			Operation::Decode,
+		));
+	}
+	Ok(())
+}
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	custom::OpusCustomMode,
+	error::{ErrorContext, Operation, OpusError, OpusErrorCode, OpusResult},
+	ffi, map_error,
+	state::StateBuffer,
+};
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+use meowlouder_opus_sys::{
+	opus_custom_encode, opus_custom_encode_float, opus_custom_encoder_ctl,
+	opus_custom_encoder_get_size, opus_custom_encoder_init, OPUS_RESET_STATE,
+	OPUS_SET_BITRATE_REQUEST, OPUS_SET_COMPLEXITY_REQUEST, OPUS_SET_VBR_REQUEST,
+};
+
+/// The maximum size of a single compressed frame, in bytes.
+const MAX_DATA_BYTES: usize = 1275;
+
+/// An Opus Custom encoder, which encodes interleaved audio with the sampling
+/// rate and frame size of an [`OpusCustomMode`].
+///
+/// This is [`Send`] but not [`Sync`], like
+/// [`OpusEncoder`](crate::encode::OpusEncoder#threading).
+pub struct OpusCustomEncoder {
+	encoder_state: StateBuffer,
+	/// The mode the encoder state points to, which has to outlive it.
+	mode: Arc<OpusCustomMode>,
+	channels: usize,
+	_not_sync: PhantomData<*mut ()>,
+}
+
+// SAFETY: Besides the energy mask, which is never set here, the only pointer in
+// the encoder state is to `mode`, which is `Sync`, never written to, and kept
+// alive by the `Arc` for as long as the encoder.
+unsafe impl Send for OpusCustomEncoder {}
+
+impl OpusCustomEncoder {
+	/// Creates a new encoder for `mode`, with 1 or 2 channels.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] for any other number of channels.
+	pub fn new(mode: Arc<OpusCustomMode>, channels: u8) -> OpusResult<Self> {
+		if !(1..=2).contains(&channels) {
+			return Err(OpusError::new(
+				OpusErrorCode::BadArg,
+				Operation::EncoderInit,
+			));
+		}
+		let encoder_size =
+			unsafe { opus_custom_encoder_get_size(mode.as_ptr(), channels as _) as usize };
+		let mut encoder_state = StateBuffer::new(encoder_size);
+		map_error!(unsafe {
+			opus_custom_encoder_init(
+				encoder_state.as_mut_ptr().cast(),
+				mode.as_ptr(),
+				channels as _,
+			)
+		})
+		.context(Operation::EncoderInit)?;
+		Ok(Self {
+			encoder_state,
+			mode,
+			channels: channels as usize,
+			_not_sync: PhantomData,
+		})
+	}
+
+	/// Encodes a frame of interleaved audio into `data`, returning the length
+	/// of the packet.
+	///
+	/// `frame_size` must be the mode's frame size, or for some modes, that
+	/// divided by 2, 4 or 8. Returns [`OpusErrorCode::BadArg`] if it isn't, or
+	/// if `pcm` holds less than `frame_size` samples for every channel.
+	pub fn encode_into(
+		&mut self,
+		pcm: &[i16],
+		frame_size: usize,
+		data: &mut [u8],
+	) -> OpusResult<usize> {
+		if pcm.len() < ffi::pcm_len(frame_size, self.channels, Operation::Encode)? {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Encode));
+		}
+		map_error!(usize, unsafe {
+			opus_custom_encode(
+				self.encoder_state.as_mut_ptr().cast(),
+				pcm.as_ptr(),
+				ffi::c_len(frame_size, Operation::Encode)?,
+				data.as_mut_ptr(),
+				data.len().min(MAX_DATA_BYTES) as _,
+			)
+		})
+		.context(Operation::Encode)
+	}
+
+	/// Encodes a frame of interleaved audio into a new packet.
+	///
+	/// Unless a bitrate was set with [`OpusCustomEncoder::set_bitrate`], every
+	/// packet takes the maximum size of 1275 bytes, so use
+	/// [`OpusCustomEncoder::encode_into`] to encode at a constant bitrate.
+	pub fn encode(&mut self, pcm: &[i16], frame_size: usize) -> OpusResult<Vec<u8>> {
+		let mut data = vec![0; MAX_DATA_BYTES];
+		let len = self.encode_into(pcm, frame_size, &mut data)?;
+		data.truncate(len);
+		Ok(data)
+	}
+
+	/// Encodes a frame of interleaved float audio into `data`, returning the
+	/// length of the packet.
+	///
+	/// `frame_size` must be the mode's frame size, or for some modes, that
+	/// divided by 2, 4 or 8. Returns [`OpusErrorCode::BadArg`] if it isn't, or
+	/// if `pcm` holds less than `frame_size` samples for every channel.
+	pub fn encode_float_into(
+		&mut self,
+		pcm: &[f32],
+		frame_size: usize,
+		data: &mut [u8],
+	) -> OpusResult<usize> {
+		if pcm.len() < ffi::pcm_len(frame_size, self.channels, Operation::Encode)? {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Encode));
+		}
+		map_error!(usize, unsafe {
+			opus_custom_encode_float(
+				self.encoder_state.as_mut_ptr().cast(),
+				pcm.as_ptr(),
+				ffi::c_len(frame_size, Operation::Encode)?,
+				data.as_mut_ptr(),
+				data.len().min(MAX_DATA_BYTES) as _,
+			)
+		})
+		.context(Operation::Encode)
+	}
+
+	/// Encodes a frame of interleaved float audio into a new packet.
+	///
+	/// Like with [`OpusCustomEncoder::encode`], every packet takes the maximum
+	/// size unless a bitrate was set.
+	pub fn encode_float(&mut self, pcm: &[f32], frame_size: usize) -> OpusResult<Vec<u8>> {
+		let mut data = vec![0; MAX_DATA_BYTES];
+		let len = self.encode_float_into(pcm, frame_size, &mut data)?;
+		data.truncate(len);
+		Ok(data)
+	}
+
+	/// Resets the codec state to be equivalent to a freshly initialized state.
+	pub fn reset(&mut self) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_custom_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_RESET_STATE as _,
+			)
+		})
+		.context(Operation::Ctl("reset state"))
+	}
+
+	/// Configures the bitrate of the encoder, in bits per second, switching it
+	/// to variable bitrate (VBR).
+	///
+	/// By default, the encoder uses a constant bitrate, filling the whole
+	/// buffer given to [`OpusCustomEncoder::encode_into`] with every packet,
+	/// so the size of that buffer sets the bitrate instead. libopus can't
+	/// encode custom modes at a set constant bitrate, so setting one here
+	/// always switches to VBR.
+	pub fn set_bitrate(&mut self, bitrate: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_custom_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_VBR_REQUEST as _,
+				1,
+			)
+		})
+		.context(Operation::Ctl("set bitrate"))?;
+		map_error!((), unsafe {
+			opus_custom_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_BITRATE_REQUEST as _,
+				bitrate,
+			)
+		})
+		.context(Operation::Ctl("set bitrate"))
+	}
+
+	/// Configures the computational complexity of the encoder.
+	///
+	/// `complexity` is in the range of 0-10, inclusive, with 10 representing
+	/// the highest complexity.
+	pub fn set_complexity(&mut self, complexity: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_custom_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_COMPLEXITY_REQUEST as _,
+				complexity,
+			)
+		})
+		.context(Operation::Ctl("set complexity"))
+	}
+
+	/// Returns the mode the encoder was created from.
+	pub fn mode(&self) -> &Arc<OpusCustomMode> {
+		&self.mode
+	}
+
+	/// Returns the number of channels the encoder was initialized with.
+	pub fn channels(&self) -> usize {
+		self.channels
+	}
+}
+
+impl Debug for OpusCustomEncoder {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusCustomEncoder")
+			.field("mode", &self.mode)
+			.field("channels", &self.channels)
+			.finish_non_exhaustive()
+	}
+}
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	error::{ErrorContext, Operation, OpusErrorCode, OpusResult},
+	map_error,
+};
+use alloc::sync::Arc;
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	ptr::NonNull,
+};
+use meowlouder_opus_sys::{
+	opus_custom_mode_create, opus_custom_mode_destroy, OpusCustomMode as RawOpusCustomMode,
+};
+
+/// The sampling rate and frame size of an Opus Custom stream, which encoders
+/// and decoders are created from.
+///
+/// The mode is shared through an [`Arc`], as libopus requires it to outlive
+/// every encoder and decoder created from it.
+pub struct OpusCustomMode {
+	mode: NonNull<RawOpusCustomMode>,
+	sample_rate: i32,
+	frame_size: usize,
+}
+
+// SAFETY: libopus never modifies a mode after creating it, and only reads it
+// from the encoders and decoders, so it can be shared and moved between
+// threads.
+unsafe impl Send for OpusCustomMode {}
+unsafe impl Sync for OpusCustomMode {}
+
+impl OpusCustomMode {
+	/// Creates a new mode, for `frame_size` samples (per channel) at
+	/// `sample_rate`.
+	///
+	/// `sample_rate` can be anything from 8000 to 96000 Hz, and `frame_size`
+	/// must be an even number from 40 to 1024, lasting at least 1 ms. Returns
+	/// [`OpusErrorCode::BadArg`] if libopus can't build a mode for them, which
+	/// can also happen for some combinations of otherwise valid values.
+	pub fn new(sample_rate: i32, frame_size: usize) -> OpusResult<Arc<Self>> {
+		let frame_size_int = i32::try_from(frame_size)
+			.map_err(|_| OpusErrorCode::BadArg)
+			.context(Operation::ModeInit)?;
+		let mut error = 0;
+		let mode = unsafe { opus_custom_mode_create(sample_rate, frame_size_int, &mut error) };
+		map_error!(error).context(Operation::ModeInit)?;
+		let mode = NonNull::new(mode)
+			.ok_or(OpusErrorCode::AllocFail)
+			.context(Operation::ModeInit)?;
+		Ok(Arc::new(Self {
+			mode,
+			sample_rate,
+			frame_size,
+		}))
+	}
+
+	/// Returns the sampling rate of the mode, in Hz.
+	pub fn sample_rate(&self) -> i32 {
+		self.sample_rate
+	}
+
+	/// Returns the number of samples (per channel) in each frame of the mode.
+	pub fn frame_size(&self) -> usize {
+		self.frame_size
+	}
+
+	pub(crate) fn as_ptr(&self) -> *const RawOpusCustomMode {
+		self.mode.as_ptr()
+	}
+}
+
+impl Drop for OpusCustomMode {
+	fn drop(&mut self) {
+		unsafe { opus_custom_mode_destroy(self.mode.as_ptr()) }
+	}
+}
+
+impl Debug for OpusCustomMode {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusCustomMode")
+			.field("sample_rate", &self.sample_rate)
+			.field("frame_size", &self.frame_size)
+			.finish_non_exhaustive()
+	}
+}
@@ -1,154 +1,729 @@
 // SPDX-License-Identifier: MPL-2.0
-use crate::{error::OpusErrorCode, map_error};
+#[cfg(feature = "weights-file")]
+use crate::dnn_blob::DnnBlob;
+#[cfg(feature = "dred")]
+use crate::dred::{OpusDred, OpusDredDecoder};
+use crate::{
+	bandwidth::OpusBandwidth,
+	channels::Channels,
+	error::{DebugCtl, ErrorContext, Operation, OpusError, OpusErrorCode, OpusResult},
+	ffi,
+	frame_size::FrameSize,
+	map_error, packet,
+	sample_rate::SampleRate,
+	state::StateBuffer,
+	util,
+};
+#[cfg(feature = "weights-file")]
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+#[cfg(feature = "weights-file")]
+use meowlouder_opus_sys::OPUS_SET_DNN_BLOB_REQUEST;
 use meowlouder_opus_sys::{
-	opus_decode, opus_decode_float, opus_decoder_ctl, opus_decoder_get_size, opus_decoder_init,
-	OPUS_GET_BANDWIDTH_REQUEST, OPUS_GET_LAST_PACKET_DURATION_REQUEST, OPUS_GET_PITCH_REQUEST,
-	OPUS_GET_SAMPLE_RATE_REQUEST, OPUS_RESET_STATE,
+	opus_decoder_ctl, opus_decoder_get_nb_samples, opus_decoder_get_size, opus_decoder_init,
+	OPUS_GET_BANDWIDTH_REQUEST, OPUS_GET_COMPLEXITY_REQUEST, OPUS_GET_FINAL_RANGE_REQUEST,
+	OPUS_GET_GAIN_REQUEST, OPUS_GET_LAST_PACKET_DURATION_REQUEST, OPUS_GET_PITCH_REQUEST,
+	OPUS_GET_SAMPLE_RATE_REQUEST, OPUS_RESET_STATE, OPUS_SET_COMPLEXITY_REQUEST,
+	OPUS_SET_GAIN_REQUEST,
 };
+#[cfg(feature = "dred")]
+use meowlouder_opus_sys::{opus_decoder_dred_decode, opus_decoder_dred_decode_float};
+
+mod decodable;
+mod frame;
+mod outcome;
+#[cfg(feature = "std")]
+mod reader;
+#[cfg(feature = "futures")]
+mod stream;
+
+#[cfg(feature = "std")]
+pub use self::reader::OpusDecoderReader;
+#[cfg(feature = "futures")]
+pub use self::stream::OpusDecoderStream;
+pub use self::{decodable::OpusDecodable, frame::DecodedFrame, outcome::DecodeOutcome};
 
+/// An Opus decoder.
+///
+/// This is [`Send`] but not [`Sync`], like
+/// [`OpusEncoder`](crate::encode::OpusEncoder#threading).
+///
+/// Cloning the decoder mid-stream gives an independent decoder in exactly the
+/// same state, which continues decoding bit-exactly like the original would.
+//
+// Cloning the decoder copies its state byte-for-byte, which is sound because
+// libopus codec state is self-contained: sub-states are referenced by offsets
+// from the start of the state rather than by pointers, and the only pointers
+// it does hold (the static CELT mode, and the neural network weights) point
+// to data that lives outside of the state.
 #[derive(Clone)]
 pub struct OpusDecoder {
-	decoder_state: Box<[u8]>,
-	channels: usize,
+	decoder_state: StateBuffer,
+	sample_rate: SampleRate,
+	channels: Channels,
+	last_packet_dtx: bool,
+	/// Whether the stream is in a DTX period, which lasts from a DTX packet
+	/// until the next packet which isn't one.
+	in_dtx: bool,
+	/// The number of lost packets concealed since the decoder was initialized
+	/// or last reset.
+	concealment_count: u32,
+	/// The weights blobs the decoder state may point into.
+	#[cfg(feature = "weights-file")]
+	dnn_blobs: Vec<Arc<DnnBlob>>,
+	_not_sync: PhantomData<*mut ()>,
 }
 
+// SAFETY: As with `OpusEncoder`, the only pointers in the decoder state are to
+// the static CELT mode and to the weights in `dnn_blobs`, which are never
+// written to and are owned by the decoder.
+//
+// `_not_sync` opts out of `Sync` for the same reasons. `nb_samples` is the
+// only method that calls into libopus through `&self`, and it only reads the
+// sampling rate.
+unsafe impl Send for OpusDecoder {}
+
 impl OpusDecoder {
-	pub fn new(sample_rate: i32, channels: i32) -> Result<Self, OpusErrorCode> {
-		debug_assert!(channels <= 2, "channels cannot be over 2");
-		let decoder_size = unsafe { opus_decoder_get_size(channels) as usize };
-		let mut decoder_state = vec![0u8; decoder_size].into_boxed_slice();
+	pub fn new(sample_rate: SampleRate, channels: Channels) -> OpusResult<Self> {
+		Self::init(sample_rate, channels)
+	}
+
+	/// Creates a decoder like [`OpusDecoder::new`], with the sampling rate in
+	/// Hz and the number of channels as an integer.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if libopus doesn't support the
+	/// sampling rate, or there aren't 1 or 2 channels.
+	pub fn new_raw(sample_rate: i32, channels: i32) -> OpusResult<Self> {
+		let sample_rate = SampleRate::try_from(sample_rate).context(Operation::DecoderInit)?;
+		let channels = Channels::try_from(channels).context(Operation::DecoderInit)?;
+		Self::init(sample_rate, channels)
+	}
+
+	fn init(sample_rate: SampleRate, channels: Channels) -> OpusResult<Self> {
+		let decoder_size = unsafe { opus_decoder_get_size(channels.into()) as usize };
+		let mut decoder_state = StateBuffer::new(decoder_size);
 		map_error!(unsafe {
-			opus_decoder_init(decoder_state.as_mut_ptr().cast(), sample_rate, channels)
-		})?;
+			opus_decoder_init(
+				decoder_state.as_mut_ptr().cast(),
+				sample_rate.into(),
+				channels.into(),
+			)
+		})
+		.context(Operation::DecoderInit)?;
 		Ok(Self {
 			decoder_state,
-			channels: channels as usize,
+			sample_rate,
+			channels,
+			last_packet_dtx: false,
+			in_dtx: false,
+			concealment_count: 0,
+			#[cfg(feature = "weights-file")]
+			dnn_blobs: Vec::new(),
+			_not_sync: PhantomData,
 		})
 	}
 
-	pub fn decode_into<Data, Pcm>(
+	/// Decodes a packet, or conceals a lost one if `data` is `None`, into
+	/// `pcm` as any [`OpusDecodable`] sample type, returning the number of
+	/// samples decoded per channel.
+	pub fn decode_generic_into<T, Data, Pcm>(
 		&mut self,
 		data: Option<Data>,
 		mut pcm: Pcm,
-		frame_size: usize,
+		frame_size: impl Into<FrameSize>,
 		decode_fec: bool,
-	) -> Result<usize, OpusErrorCode>
+	) -> OpusResult<usize>
 	where
+		T: OpusDecodable,
 		Data: AsRef<[u8]>,
-		Pcm: AsMut<[i16]>,
+		Pcm: AsMut<[T]>,
 	{
+		let frame_size = frame_size.into().samples();
+		self.check_frame_size(frame_size)?;
 		let pcm = pcm.as_mut();
-		if !cfg!(feature = "i-can-be-trusted-to-size-my-decoder-buffer-correctly")
-			&& pcm.len() < frame_size * self.channels
-		{
-			return Err(OpusErrorCode::BufferTooSmall);
+		let required = ffi::pcm_len(frame_size, self.channels.count(), Operation::Decode)?;
+		if pcm.len() < required {
+			return Err(OpusError::buffer_too_small(
+				Operation::Decode,
+				required,
+				pcm.len(),
+			));
 		}
+		// SAFETY: `pcm` has room for `frame_size` samples for each channel, which
+		// is the most libopus ever decodes.
+		unsafe { self.decode_generic_into_unchecked(data, pcm, frame_size, decode_fec) }
+	}
 
-		let (data_ptr, data_len) = data
-			.as_ref()
-			.map(|d| {
-				let data = d.as_ref();
-				(data.as_ptr(), data.len() as i32)
-			})
-			.unwrap_or((std::ptr::null(), 0));
+	/// Decodes like [`OpusDecoder::decode_generic_into`], without checking that
+	/// `pcm` is large enough.
+	///
+	/// # Safety
+	///
+	/// `pcm` must have room for the decoded audio for each channel, which is
+	/// the duration of the packet when decoding one normally, and `frame_size`
+	/// samples otherwise.
+	pub unsafe fn decode_generic_into_unchecked<T, Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> OpusResult<usize>
+	where
+		T: OpusDecodable,
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[T]>,
+	{
+		let frame_size = frame_size.into().samples();
+		self.check_frame_size(frame_size)?;
+		let data = data.as_ref().map(AsRef::as_ref);
+		let dtx = data.is_some_and(packet::is_dtx);
 
-		map_error!(usize, unsafe {
-			opus_decode(
-				self.decoder_state.as_mut_ptr().cast(),
-				data_ptr,
-				data_len,
-				pcm.as_mut_ptr(),
-				frame_size as _,
-				decode_fec as _,
-			)
-		})
+		// SAFETY: The caller guarantees that `pcm` is large enough.
+		let len = unsafe { T::decode(self, data, pcm.as_mut(), frame_size, decode_fec) }?;
+		#[cfg(feature = "tracing")]
+		tracing::trace!(
+			decoded_samples = len,
+			frame_size,
+			sample_rate = self.sample_rate.as_i32(),
+			"decoded frame"
+		);
+		self.last_packet_dtx = dtx;
+		if data.is_some() {
+			self.in_dtx = dtx;
+		} else {
+			self.concealment_count += 1;
+		}
+		Ok(len)
 	}
 
-	pub fn decode<Data>(
+	/// Decodes a packet, or conceals a lost one if `data` is `None`, returning
+	/// the decoded audio interleaved as any [`OpusDecodable`] sample type.
+	///
+	/// This makes it possible to write code which is generic over the sample
+	/// type, together with [`OpusEncoder::encode`](crate::OpusEncoder::encode).
+	pub fn decode_generic<T, Data>(
 		&mut self,
 		data: Option<Data>,
-		frame_size: usize,
+		frame_size: impl Into<FrameSize>,
 		decode_fec: bool,
-	) -> Result<Vec<i16>, OpusErrorCode>
+	) -> OpusResult<Vec<T>>
 	where
+		T: OpusDecodable,
 		Data: AsRef<[u8]>,
 	{
-		let mut pcm = vec![0; frame_size * self.channels];
-		let len = self.decode_into(data, &mut pcm, frame_size, decode_fec)?;
-		pcm.truncate(len);
+		let frame_size = frame_size.into().samples();
+		self.check_frame_size(frame_size)?;
+		let mut pcm = vec![T::default(); frame_size * self.channels.count()];
+		let len = self.decode_generic_into(data, &mut pcm, frame_size, decode_fec)?;
+		pcm.truncate(len * self.channels.count());
 		Ok(pcm)
 	}
 
+	pub fn decode_into<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		pcm: Pcm,
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[i16]>,
+	{
+		self.decode_generic_into(data, pcm, frame_size, decode_fec)
+	}
+
+	/// Decodes like [`OpusDecoder::decode_into`], without checking that `pcm`
+	/// is large enough.
+	///
+	/// # Safety
+	///
+	/// `pcm` must have room for the decoded audio for each channel, which is
+	/// the duration of the packet when decoding one normally, and `frame_size`
+	/// samples otherwise.
+	pub unsafe fn decode_into_unchecked<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		pcm: Pcm,
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[i16]>,
+	{
+		// SAFETY: Upheld by the caller.
+		unsafe { self.decode_generic_into_unchecked(data, pcm, frame_size, decode_fec) }
+	}
+
+	/// Decodes a packet, or conceals a lost one if `data` is `None`, returning
+	/// the decoded audio interleaved, with a sample for each channel per
+	/// sample frame. [`OpusDecoder::decode_frame`] also returns the number of
+	/// samples per channel.
+	pub fn decode<Data>(
+		&mut self,
+		data: Option<Data>,
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> OpusResult<Vec<i16>>
+	where
+		Data: AsRef<[u8]>,
+	{
+		self.decode_generic(data, frame_size, decode_fec)
+	}
+
+	/// Decodes a frame like [`OpusDecoder::decode`], additionally returning
+	/// whether the stream is [in DTX](OpusDecoder::in_dtx) after it.
+	pub fn decode_and_in_dtx(
+		&mut self,
+		data: Option<&[u8]>,
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<(Vec<i16>, bool)> {
+		let pcm = self.decode(data, frame_size, false)?;
+		Ok((pcm, self.in_dtx))
+	}
+
+	/// Decodes the self-delimited packet at the start of `data`, such as one
+	/// from [`OpusEncoder::encode_self_delimited`](crate::OpusEncoder::encode_self_delimited).
+	///
+	/// Anything in `data` after the packet is ignored, so use
+	/// [`packet::self_delimited_packets`] to decode a buffer of concatenated
+	/// packets.
+	pub fn decode_self_delimited(
+		&mut self,
+		data: &[u8],
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<Vec<i16>> {
+		let (packet, _) = packet::from_self_delimited(data)?;
+		self.decode(Some(packet), frame_size, false)
+	}
+
+	/// Decodes a packet like [`OpusDecoder::decode`], after checking that it's
+	/// valid with [`packet::validate_packet`].
+	///
+	/// Returns [`OpusErrorCode::InvalidPacket`] without decoding anything if
+	/// the packet isn't valid, leaving the decoder state untouched.
+	pub fn decode_validated(
+		&mut self,
+		data: &[u8],
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> OpusResult<Vec<i16>> {
+		packet::validate_packet(data)?;
+		self.decode(Some(data), frame_size, decode_fec)
+	}
+
+	/// Decodes a frame like [`OpusDecoder::decode`], additionally returning
+	/// information about the decoded frame.
+	///
+	/// Passing no data conceals a lost packet. Returns
+	/// [`OpusErrorCode::BadArg`] when concealing before any packet has been
+	/// decoded, as there is no bandwidth to report yet.
+	pub fn decode_frame<Data>(
+		&mut self,
+		data: Option<Data>,
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> OpusResult<DecodedFrame>
+	where
+		Data: AsRef<[u8]>,
+	{
+		let frame_size = frame_size.into().samples();
+		let concealed = data.is_none();
+		self.check_frame_size(frame_size)?;
+		let mut pcm = vec![0; frame_size * self.channels.count()];
+		let nb_samples = self.decode_into(data, &mut pcm, frame_size, decode_fec)?;
+		pcm.truncate(nb_samples * self.channels.count());
+		Ok(DecodedFrame {
+			pcm,
+			nb_samples,
+			bandwidth: OpusBandwidth::try_from(self.bandwidth()?)
+				.context(Operation::Ctl("get bandwidth"))?,
+			pitch: self.pitch()?,
+			concealed,
+		})
+	}
+
 	pub fn decode_float_into<Data, Pcm>(
 		&mut self,
 		data: Option<Data>,
-		mut pcm: Pcm,
-		frame_size: usize,
+		pcm: Pcm,
+		frame_size: impl Into<FrameSize>,
 		decode_fec: bool,
-	) -> Result<usize, OpusErrorCode>
+	) -> OpusResult<usize>
 	where
 		Data: AsRef<[u8]>,
 		Pcm: AsMut<[f32]>,
 	{
-		let pcm = pcm.as_mut();
+		self.decode_generic_into(data, pcm, frame_size, decode_fec)
+	}
+
+	/// Decodes like [`OpusDecoder::decode_float_into`], without checking that
+	/// `pcm` is large enough.
+	///
+	/// # Safety
+	///
+	/// `pcm` must have room for the decoded audio for each channel, which is
+	/// the duration of the packet when decoding one normally, and `frame_size`
+	/// samples otherwise.
+	pub unsafe fn decode_float_into_unchecked<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		pcm: Pcm,
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[f32]>,
+	{
+		// SAFETY: Upheld by the caller.
+		unsafe { self.decode_generic_into_unchecked(data, pcm, frame_size, decode_fec) }
+	}
+
+	/// Decodes a packet to float PCM, as with [`OpusDecoder::decode`].
+	pub fn decode_float<Data>(
+		&mut self,
+		data: Option<Data>,
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> OpusResult<Vec<f32>>
+	where
+		Data: AsRef<[u8]>,
+	{
+		self.decode_generic(data, frame_size, decode_fec)
+	}
+
+	/// Conceals `frame_size` samples (per channel) of lost audio, returning
+	/// the concealed audio.
+	///
+	/// This is the same as passing no data to [`OpusDecoder::decode`].
+	pub fn conceal(&mut self, frame_size: impl Into<FrameSize>) -> OpusResult<Vec<i16>> {
+		self.decode(None::<&[u8]>, frame_size, false)
+	}
+
+	/// Conceals `frame_size` samples (per channel) of lost audio, returning
+	/// the concealed audio.
+	///
+	/// This is the same as passing no data to [`OpusDecoder::decode_float`].
+	pub fn conceal_float(&mut self, frame_size: impl Into<FrameSize>) -> OpusResult<Vec<f32>> {
+		self.decode_float(None::<&[u8]>, frame_size, false)
+	}
+
+	/// Conceals a single lost packet, writing the concealed audio into `pcm`.
+	///
+	/// The duration of the concealed audio is the duration of the last packet
+	/// successfully decoded or concealed, as the missing packet is assumed to
+	/// be the same length as the ones around it. Returns
+	/// [`OpusErrorCode::BadArg`] if no packet has been decoded yet, as there
+	/// is no duration to conceal.
+	pub fn conceal_loss_into<Pcm>(&mut self, pcm: Pcm) -> OpusResult<usize>
+	where
+		Pcm: AsMut<[i16]>,
+	{
+		let frame_size = self.concealment_frame_size()?;
+		self.decode_into(None::<&[u8]>, pcm, frame_size, false)
+	}
+
+	/// Conceals a single lost packet, returning the concealed audio.
+	///
+	/// See [`OpusDecoder::conceal_loss_into`] for how the duration is picked.
+	pub fn conceal_loss(&mut self) -> OpusResult<Vec<i16>> {
+		let frame_size = self.concealment_frame_size()?;
+		self.decode(None::<&[u8]>, frame_size, false)
+	}
+
+	/// Conceals a single lost packet, writing the concealed audio into `pcm`.
+	///
+	/// See [`OpusDecoder::conceal_loss_into`] for how the duration is picked.
+	pub fn conceal_loss_float_into<Pcm>(&mut self, pcm: Pcm) -> OpusResult<usize>
+	where
+		Pcm: AsMut<[f32]>,
+	{
+		let frame_size = self.concealment_frame_size()?;
+		self.decode_float_into(None::<&[u8]>, pcm, frame_size, false)
+	}
 
-		if !cfg!(feature = "i-can-be-trusted-to-size-my-decoder-buffer-correctly")
-			&& pcm.len() < frame_size * self.channels
-		{
-			return Err(OpusErrorCode::BufferTooSmall);
+	/// Conceals a single lost packet, returning the concealed audio.
+	///
+	/// See [`OpusDecoder::conceal_loss_into`] for how the duration is picked.
+	pub fn conceal_loss_float(&mut self) -> OpusResult<Vec<f32>> {
+		let frame_size = self.concealment_frame_size()?;
+		self.decode_float(None::<&[u8]>, frame_size, false)
+	}
+
+	/// Decodes an Opus packet like [`OpusDecoder::decode`], concealing the
+	/// packet instead of failing if it's missing or invalid.
+	///
+	/// An invalid packet is concealed with the duration of the last packet
+	/// successfully decoded or concealed, capped to `frame_size`, so that the
+	/// output stays continuous; if no packet has been decoded yet,
+	/// `frame_size` samples (per channel) are concealed. Any other error, such
+	/// as [`OpusErrorCode::InvalidState`] or
+	/// [`OpusErrorCode::BufferTooSmall`], is returned as
+	/// [`DecodeOutcome::Failed`].
+	pub fn decode_lossy(
+		&mut self,
+		data: Option<&[u8]>,
+		frame_size: impl Into<FrameSize>,
+		decode_fec: bool,
+	) -> DecodeOutcome {
+		let frame_size = frame_size.into().samples();
+		let Some(data) = data else {
+			return match self.conceal(frame_size) {
+				Ok(pcm) => DecodeOutcome::Concealed(pcm),
+				Err(err) => DecodeOutcome::Failed(err),
+			};
+		};
+		match self.decode(Some(data), frame_size, decode_fec) {
+			Ok(pcm) => DecodeOutcome::Decoded(pcm),
+			Err(err) if err.code() == OpusErrorCode::InvalidPacket => {
+				let duration = self
+					.concealment_frame_size()
+					.map_or(frame_size, |duration| duration.min(frame_size));
+				match self.conceal(duration) {
+					Ok(pcm) => DecodeOutcome::Concealed(pcm),
+					Err(err) => DecodeOutcome::Failed(err),
+				}
+			}
+			Err(err) => DecodeOutcome::Failed(err),
 		}
+	}
+
+	/// Recovers a lost packet using the in-band forward error correction (FEC)
+	/// data carried by `next_packet`, which is the packet received *after* the
+	/// lost one, returning the recovered audio.
+	///
+	/// `frame_size` is the duration of the lost packet, in samples per
+	/// channel. `next_packet` isn't decoded itself, and should be decoded as
+	/// normal afterwards. If it doesn't contain any FEC data, then the lost
+	/// audio is produced through packet loss concealment instead.
+	pub fn decode_fec<Data>(
+		&mut self,
+		next_packet: Data,
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<Vec<i16>>
+	where
+		Data: AsRef<[u8]>,
+	{
+		self.decode(Some(next_packet), frame_size, true)
+	}
 
-		let (data_ptr, data_len) = data
-			.as_ref()
-			.map(|d| {
-				let data = d.as_ref();
-				(data.as_ptr(), data.len() as i32)
-			})
-			.unwrap_or((std::ptr::null(), 0));
+	/// Recovers a lost packet using the in-band FEC data carried by
+	/// `next_packet`, as with [`OpusDecoder::decode_fec`].
+	pub fn decode_fec_float<Data>(
+		&mut self,
+		next_packet: Data,
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<Vec<f32>>
+	where
+		Data: AsRef<[u8]>,
+	{
+		self.decode_float(Some(next_packet), frame_size, true)
+	}
 
-		map_error!(usize, unsafe {
-			opus_decode_float(
+	/// Decodes `frame_size` samples (per channel) of lost audio from the DRED
+	/// data in `dred`, starting `dred_offset` samples before the start of the
+	/// packet it was parsed from, at the decoder's sampling rate.
+	///
+	/// `frame_size` must be a multiple of 2.5 ms. See
+	/// [`OpusDecoder::recover_with_dred`] for recovering whole lost packets.
+	#[cfg(feature = "dred")]
+	pub fn decode_dred(
+		&mut self,
+		dred: &OpusDred,
+		dred_offset: i32,
+		frame_size: usize,
+	) -> OpusResult<Vec<i16>> {
+		if frame_size > FrameSize::max_at(self.sample_rate).samples() {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Decode));
+		}
+		let mut pcm = vec![0; frame_size * self.channels.count()];
+		let len = map_error!(usize, unsafe {
+			opus_decoder_dred_decode(
 				self.decoder_state.as_mut_ptr().cast(),
-				data_ptr,
-				data_len,
+				dred.as_ptr().cast(),
+				dred_offset,
 				pcm.as_mut_ptr(),
 				frame_size as _,
-				decode_fec as _,
 			)
 		})
+		.context(Operation::Decode)?;
+		pcm.truncate(len * self.channels.count());
+		Ok(pcm)
 	}
 
-	pub fn decode_float<Data>(
+	/// Decodes lost audio from the DRED data in `dred`, as with
+	/// [`OpusDecoder::decode_dred`].
+	#[cfg(feature = "dred")]
+	pub fn decode_dred_float(
 		&mut self,
-		data: Option<Data>,
+		dred: &OpusDred,
+		dred_offset: i32,
 		frame_size: usize,
-		decode_fec: bool,
-	) -> Result<Vec<f32>, OpusErrorCode>
-	where
-		Data: AsRef<[u8]>,
-	{
-		let mut pcm = vec![0.0; frame_size * self.channels];
-		let len = self.decode_float_into(data, &mut pcm, frame_size, decode_fec)?;
-		pcm.truncate(len);
+	) -> OpusResult<Vec<f32>> {
+		if frame_size > FrameSize::max_at(self.sample_rate).samples() {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Decode));
+		}
+		let mut pcm = vec![0.0; frame_size * self.channels.count()];
+		let len = map_error!(usize, unsafe {
+			opus_decoder_dred_decode_float(
+				self.decoder_state.as_mut_ptr().cast(),
+				dred.as_ptr().cast(),
+				dred_offset,
+				pcm.as_mut_ptr(),
+				frame_size as _,
+			)
+		})
+		.context(Operation::Decode)?;
+		pcm.truncate(len * self.channels.count());
+		Ok(pcm)
+	}
+
+	/// Recovers the last `lost_packets` packets, each `frame_size` samples
+	/// (per channel) long, from the DRED data carried by `next_packet`, the
+	/// first packet received after them. Returns the recovered audio, oldest
+	/// first.
+	///
+	/// The DRED data is parsed into `dred` with `dred_decoder`. Any audio from
+	/// further back than the DRED data reaches is produced through packet loss
+	/// concealment instead, so the recovered audio is always
+	/// `lost_packets * frame_size` samples long. `next_packet` isn't decoded
+	/// itself, and should be decoded as normal afterwards.
+	#[cfg(feature = "dred")]
+	pub fn recover_with_dred(
+		&mut self,
+		dred_decoder: &mut OpusDredDecoder,
+		dred: &mut OpusDred,
+		next_packet: &[u8],
+		lost_packets: usize,
+		frame_size: usize,
+	) -> OpusResult<Vec<i16>> {
+		let sample_rate = self.sample_rate()?;
+		let lost_samples = lost_packets * frame_size;
+		// DRED is decoded in 40 ms chunks, which start a little after the start
+		// of the packet, so ask for an extra chunk to cover all of the lost
+		// audio.
+		let max_dred_samples = lost_samples + sample_rate as usize / 25;
+		let (available, _) =
+			dred_decoder.dred_parse(dred, next_packet, max_dred_samples, sample_rate, false)?;
+		let mut pcm = Vec::with_capacity(lost_samples * self.channels.count());
+		for lost in (1..=lost_packets).rev() {
+			let offset = lost * frame_size;
+			if offset <= available {
+				pcm.extend(self.decode_dred(dred, offset as i32, frame_size)?);
+			} else {
+				pcm.extend(self.conceal(frame_size)?);
+			}
+		}
 		Ok(pcm)
 	}
 
+	/// Recovers a single lost packet using the in-band forward error correction
+	/// data carried by the packet received after it, and then decodes that
+	/// packet as normal. Returns the recovered audio, followed by the audio of
+	/// `next_packet` itself.
+	///
+	/// `lost_duration_samples` is the duration of the lost packet, in samples
+	/// per channel. If `next_packet` doesn't contain any FEC data, then the
+	/// lost audio is silently produced through packet loss concealment
+	/// instead.
+	pub fn recover_with_fec(
+		&mut self,
+		next_packet: &[u8],
+		lost_duration_samples: usize,
+	) -> OpusResult<(Vec<i16>, Vec<i16>)> {
+		let recovered = self.decode_fec(next_packet, lost_duration_samples)?;
+		let frame_size = self.nb_samples(next_packet)?;
+		let decoded = self.decode(Some(next_packet), frame_size, false)?;
+		Ok((recovered, decoded))
+	}
+
+	/// Returns the number of samples (per channel) in the given packet, at the
+	/// decoder's sampling rate.
+	pub fn nb_samples(&self, packet: &[u8]) -> OpusResult<usize> {
+		map_error!(usize, unsafe {
+			opus_decoder_get_nb_samples(
+				self.decoder_state.as_ptr().cast(),
+				packet.as_ptr(),
+				ffi::c_len(packet.len(), Operation::ParsePacket)?,
+			)
+		})
+		.context(Operation::ParsePacket)
+	}
+
+	/// Returns [`OpusErrorCode::BadArg`] unless `frame_size` is a legal Opus
+	/// frame size at the decoder's sampling rate. Checking it before libopus
+	/// does also keeps a huge size from allocating a huge buffer first.
+	fn check_frame_size(&self, frame_size: usize) -> OpusResult<()> {
+		FrameSize::from_samples(frame_size, self.sample_rate)
+			.map(drop)
+			.context(Operation::Decode)
+	}
+
+	fn concealment_frame_size(&mut self) -> OpusResult<usize> {
+		match self.last_packet_duration()? {
+			0 => Err(OpusError::new(OpusErrorCode::BadArg, Operation::Decode)),
+			duration => Ok(duration as usize),
+		}
+	}
+
+	/// Returns whether the last packet successfully decoded was a
+	/// [DTX](packet::is_dtx) packet. Concealing a lost packet by passing no
+	/// data doesn't count as decoding a DTX packet.
+	pub fn last_packet_was_dtx(&self) -> bool {
+		self.last_packet_dtx
+	}
+
+	/// Returns whether the stream is in discontinuous transmission (DTX),
+	/// where the encoder only sends occasional DTX packets during silence,
+	/// and the frames in between are lost packets to be concealed.
+	///
+	/// Unlike [`OpusDecoder::last_packet_was_dtx`], this stays `true` while
+	/// concealing the frames after a DTX packet, until a packet which isn't
+	/// one is decoded. libopus doesn't track this in the decoder, so this is
+	/// only meaningful after a packet was decoded, and is `false` before then.
+	pub fn in_dtx(&self) -> bool {
+		self.in_dtx
+	}
+
+	/// Returns the number of lost packets concealed since the decoder was
+	/// initialized or last reset, counting every decode which was passed no
+	/// data.
+	pub fn concealment_count(&self) -> u32 {
+		self.concealment_count
+	}
+
+	/// Returns the number of channels the decoder was initialized with.
+	pub fn channels(&self) -> usize {
+		self.channels.count()
+	}
+
 	/// Resets the codec state to be equivalent to a freshly initialized state.
 	/// This should be called when switching streams in order to prevent the
 	/// back to back decoding from giving different results from one at a time
 	/// decoding.
-	pub fn reset(&mut self) -> Result<(), OpusErrorCode> {
+	pub fn reset(&mut self) -> OpusResult<()> {
 		map_error!((), unsafe {
 			opus_decoder_ctl(
 				self.decoder_state.as_mut_ptr().cast(),
 				OPUS_RESET_STATE as _,
 			)
-		})?;
+		})
+		.context(Operation::Ctl("reset state"))?;
+		self.in_dtx = false;
+		self.last_packet_dtx = false;
+		self.concealment_count = 0;
 		Ok(())
 	}
 
 	/// Returns the decoder's last bandpass.
-	pub fn bandwidth(&mut self) -> Result<i32, OpusErrorCode> {
+	pub fn bandwidth(&mut self) -> OpusResult<i32> {
 		let mut bandwidth = 0;
 		map_error!(&bandwidth, unsafe {
 			opus_decoder_ctl(
@@ -157,10 +732,11 @@ impl OpusDecoder {
 				&mut bandwidth,
 			)
 		})
+		.context(Operation::Ctl("get bandwidth"))
 	}
 
 	/// Returns the sampling rate the decoder was initialized with.
-	pub fn sample_rate(&mut self) -> Result<i32, OpusErrorCode> {
+	pub fn sample_rate(&mut self) -> OpusResult<i32> {
 		let mut sample_rate = 0;
 		map_error!(&sample_rate, unsafe {
 			opus_decoder_ctl(
@@ -169,11 +745,12 @@ impl OpusDecoder {
 				&mut sample_rate,
 			)
 		})
+		.context(Operation::Ctl("get sample rate"))
 	}
 
 	/// Returns the duration (in samples, at the current sampling rate) of the
 	/// last packet successfully decoded or concealed.
-	pub fn last_packet_duration(&mut self) -> Result<i32, OpusErrorCode> {
+	pub fn last_packet_duration(&mut self) -> OpusResult<i32> {
 		let mut packet_duration = 0;
 		map_error!(&packet_duration, unsafe {
 			opus_decoder_ctl(
@@ -182,6 +759,22 @@ impl OpusDecoder {
 				&mut packet_duration,
 			)
 		})
+		.context(Operation::Ctl("get last packet duration"))
+	}
+
+	/// Returns the final state of the entropy coder after the last frame
+	/// decoded, which matches between an encoder and a decoder that stay in
+	/// sync, and is what conformance tests compare.
+	pub fn final_range(&mut self) -> OpusResult<u32> {
+		let mut final_range = 0u32;
+		map_error!(&final_range, unsafe {
+			opus_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_GET_FINAL_RANGE_REQUEST as _,
+				&mut final_range,
+			)
+		})
+		.context(Operation::Ctl("get final range"))
 	}
 
 	/// Returns the pitch period (at 48 kHz) of the last decoded frame, if
@@ -189,9 +782,9 @@ impl OpusDecoder {
 	/// the use of pitch, e.g. time stretching/shortening. If the last frame
 	/// was not voiced, or if the pitch was not coded in the frame, then zero
 	/// is returned.
-	pub fn pitch(&mut self) -> Result<Option<i32>, OpusErrorCode> {
+	pub fn pitch(&mut self) -> OpusResult<Option<i32>> {
 		let mut pitch = 0;
-		map_error!(unsafe {
+		map_error!(&pitch, unsafe {
 			opus_decoder_ctl(
 				self.decoder_state.as_mut_ptr().cast(),
 				OPUS_GET_PITCH_REQUEST as _,
@@ -199,5 +792,130 @@ impl OpusDecoder {
 			)
 		})
 		.map(|pitch| if pitch == 0 { None } else { Some(pitch) })
+		.context(Operation::Ctl("get pitch"))
+	}
+
+	/// Returns the decoder's configured computational complexity, in the range
+	/// of 0-10, inclusive (default: 0).
+	pub fn complexity(&mut self) -> OpusResult<i32> {
+		let mut complexity = 0;
+		map_error!(&complexity, unsafe {
+			opus_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_GET_COMPLEXITY_REQUEST as _,
+				&mut complexity,
+			)
+		})
+		.context(Operation::Ctl("get complexity"))
+	}
+
+	/// Configures the decoder's computational complexity.
+	///
+	/// `complexity` is in the range of 0-10, inclusive. The complexity only
+	/// makes a difference when libopus was built with its neural network
+	/// features, which [`capabilities`](crate::capabilities()) reports: a
+	/// complexity of 5 or more enables the deep packet loss concealment, and
+	/// with the `osce` feature, 6 or more enables speech enhancement with
+	/// LACE, and 7 or more with the higher quality NoLACE.
+	pub fn set_complexity(&mut self, complexity: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_SET_COMPLEXITY_REQUEST as _,
+				complexity,
+			)
+		})
+		.context(Operation::Ctl("set complexity"))
+	}
+
+	/// Returns the output gain applied to decoded audio, in Q7.8 dB (default:
+	/// 0).
+	pub fn gain(&mut self) -> OpusResult<i32> {
+		let mut gain = 0;
+		map_error!(&gain, unsafe {
+			opus_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_GET_GAIN_REQUEST as _,
+				&mut gain,
+			)
+		})
+		.context(Operation::Ctl("get gain"))
+	}
+
+	/// Configures the output gain applied to decoded audio, in Q7.8 dB, in the
+	/// range of -32768 to 32767, inclusive.
+	pub fn set_gain(&mut self, gain: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_SET_GAIN_REQUEST as _,
+				gain,
+			)
+		})
+		.context(Operation::Ctl("set gain"))
+	}
+
+	/// Returns the output gain applied to decoded audio, in decibels.
+	pub fn gain_db(&mut self) -> OpusResult<f32> {
+		self.gain().map(util::q8_to_db)
+	}
+
+	/// Configures the output gain applied to decoded audio, in decibels. The
+	/// gain saturates to the range [`set_gain`](Self::set_gain) accepts.
+	pub fn set_gain_db(&mut self, db: f32) -> OpusResult<()> {
+		self.set_gain(util::db_to_q8(db))
+	}
+
+	/// Loads the neural network weights used by the DNN features from a
+	/// weights blob, which is needed before they can be used when libopus is
+	/// built with the `weights-file` feature.
+	///
+	/// The blob is copied, and the copy is kept alive for as long as the
+	/// decoder (or any clone of it) is, as libopus points into it rather than
+	/// copying the weights. Returns [`OpusErrorCode::BadArg`] if the blob is
+	/// truncated or otherwise invalid.
+	#[cfg(feature = "weights-file")]
+	pub fn set_dnn_blob(&mut self, blob: &[u8]) -> OpusResult<()> {
+		let operation = Operation::Ctl("set DNN blob");
+		let len = i32::try_from(blob.len())
+			.map_err(|_| OpusErrorCode::BadArg)
+			.context(operation)?;
+		let blob = DnnBlob::new(blob).context(operation)?;
+		// A failed load can still leave some of the models pointing into the
+		// blob, so it's kept either way.
+		self.dnn_blobs.push(Arc::clone(&blob));
+		let ret = unsafe {
+			opus_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_SET_DNN_BLOB_REQUEST as _,
+				blob.as_ptr(),
+				len,
+			)
+		};
+		// The decoder loads the PLC and OSCE models separately, and reports
+		// either failing as 1 rather than an error code.
+		match ret {
+			0 => Ok(()),
+			1 => Err(OpusErrorCode::BadArg),
+			_ => map_error!((), ret),
+		}
+		.context(operation)
+	}
+}
+
+impl Debug for OpusDecoder {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		// The getters need a mutable reference for the FFI call, but none of
+		// them actually modify the decoder state, so query a copy of it.
+		let mut decoder = self.clone();
+		f.debug_struct("OpusDecoder")
+			.field("sample_rate", &DebugCtl(decoder.sample_rate()))
+			.field("channels", &self.channels)
+			.field("bandwidth", &DebugCtl(decoder.bandwidth()))
+			.field(
+				"last_packet_duration",
+				&DebugCtl(decoder.last_packet_duration()),
+			)
+			.finish()
 	}
 }
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	decoder::OpusDecoder,
+	error::{ErrorContext, Operation, OpusResult},
+	ffi, map_error,
+};
+use meowlouder_opus_sys::{opus_decode, opus_decode_float};
+
+/// A sample type which Opus packets can be decoded to, as with
+/// [`OpusDecoder::decode_generic`].
+pub trait OpusDecodable: Copy + Default {
+	/// Decodes `data` into `pcm`, or conceals a lost packet if `data` is
+	/// `None`, returning the number of samples decoded per channel.
+	///
+	/// # Safety
+	///
+	/// `pcm` must have room for the decoded audio for each channel, as with
+	/// [`OpusDecoder::decode_into_unchecked`].
+	unsafe fn decode(
+		decoder: &mut OpusDecoder,
+		data: Option<&[u8]>,
+		pcm: &mut [Self],
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<usize>;
+}
+
+fn data_parts(data: Option<&[u8]>) -> OpusResult<(*const u8, i32)> {
+	Ok(match data {
+		Some(data) => (data.as_ptr(), ffi::c_len(data.len(), Operation::Decode)?),
+		None => (core::ptr::null(), 0),
+	})
+}
+
+impl OpusDecodable for i16 {
+	unsafe fn decode(
+		decoder: &mut OpusDecoder,
+		data: Option<&[u8]>,
+		pcm: &mut [Self],
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<usize> {
+		let (data_ptr, data_len) = data_parts(data)?;
+		#[cfg(feature = "tracing")]
+		let _span = tracing::trace_span!("opus_decode_ffi").entered();
+		map_error!(usize, unsafe {
+			opus_decode(
+				decoder.decoder_state.as_mut_ptr().cast(),
+				data_ptr,
+				data_len,
+				pcm.as_mut_ptr(),
+				ffi::c_len(frame_size, Operation::Decode)?,
+				decode_fec as _,
+			)
+		})
+		.context(Operation::Decode)
+	}
+}
+
+impl OpusDecodable for f32 {
+	unsafe fn decode(
+		decoder: &mut OpusDecoder,
+		data: Option<&[u8]>,
+		pcm: &mut [Self],
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<usize> {
+		let (data_ptr, data_len) = data_parts(data)?;
+		#[cfg(feature = "tracing")]
+		let _span = tracing::trace_span!("opus_decode_ffi").entered();
+		map_error!(usize, unsafe {
+			opus_decode_float(
+				decoder.decoder_state.as_mut_ptr().cast(),
+				data_ptr,
+				data_len,
+				pcm.as_mut_ptr(),
+				ffi::c_len(frame_size, Operation::Decode)?,
+				decode_fec as _,
+			)
+		})
+		.context(Operation::Decode)
+	}
+}
@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::bandwidth::OpusBandwidth;
+use alloc::vec::Vec;
+
+/// Decoded audio, along with information about the frame it was decoded from.
+///
+/// Returned by [`OpusDecoder::decode_frame`](crate::OpusDecoder::decode_frame).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedFrame {
+	/// The decoded interleaved audio.
+	pub pcm: Vec<i16>,
+	/// The number of samples (per channel) decoded.
+	pub nb_samples: usize,
+	/// The audio bandwidth of the decoded frame.
+	pub bandwidth: OpusBandwidth,
+	/// The pitch period (at 48 kHz) of the frame, if it was voiced and the
+	/// pitch was coded.
+	pub pitch: Option<i32>,
+	/// Whether the audio was produced by packet loss concealment, rather than
+	/// decoded from a packet.
+	pub concealed: bool,
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::{OpusError, OpusResult};
+use alloc::vec::Vec;
+
+/// The result of decoding a single packet with
+/// [`OpusDecoder::decode_lossy`](crate::OpusDecoder::decode_lossy).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeOutcome {
+	/// The packet was decoded as normal, producing this interleaved audio.
+	Decoded(Vec<i16>),
+	/// The packet was missing or invalid, so this interleaved audio was
+	/// produced through packet loss concealment instead.
+	Concealed(Vec<i16>),
+	/// Neither decoding nor concealment succeeded.
+	Failed(OpusError),
+}
+
+impl DecodeOutcome {
+	/// Returns the decoded or concealed audio, or `None` if decoding failed.
+	pub fn pcm(&self) -> Option<&[i16]> {
+		match self {
+			Self::Decoded(pcm) | Self::Concealed(pcm) => Some(pcm),
+			Self::Failed(_) => None,
+		}
+	}
+
+	/// Converts the outcome into a [`Result`], discarding whether the audio
+	/// was concealed.
+	pub fn into_result(self) -> OpusResult<Vec<i16>> {
+		match self {
+			Self::Decoded(pcm) | Self::Concealed(pcm) => Ok(pcm),
+			Self::Failed(err) => Err(err),
+		}
+	}
+
+	/// Returns whether the audio was produced through packet loss
+	/// concealment.
+	pub fn is_concealed(&self) -> bool {
+		matches!(self, Self::Concealed(_))
+	}
+}
@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{decoder::OpusDecoder, packet};
+use alloc::{vec, vec::Vec};
+use std::io::{BufRead, Error as IoError, ErrorKind as IoErrorKind, Read};
+
+/// A [`Read`] adapter that decodes a stream of Opus packets into raw PCM.
+///
+/// The inner reader must yield packets prefixed by their length as a 4-byte
+/// big-endian integer, as written by
+/// [`OpusEncoderWriter`](crate::encode::OpusEncoderWriter). The decoded audio
+/// is read as interleaved, little-endian `i16` samples.
+///
+/// A zero-length packet is treated as a lost packet, and is concealed.
+pub struct OpusDecoderReader<R: Read> {
+	decoder: OpusDecoder,
+	inner: R,
+	frame_size: usize,
+	packet: Vec<u8>,
+	pcm: Vec<u8>,
+	pos: usize,
+}
+
+impl<R: Read> OpusDecoderReader<R> {
+	/// Creates a new reader, which reads packets from `inner` and decodes them
+	/// with `decoder`.
+	///
+	/// `frame_size` is the maximum number of samples (per channel) that a
+	/// single packet may decode to, and is also the amount of audio produced
+	/// when concealing a lost packet.
+	pub fn new(decoder: OpusDecoder, inner: R, frame_size: usize) -> Self {
+		Self {
+			decoder,
+			inner,
+			frame_size,
+			packet: Vec::new(),
+			pcm: Vec::new(),
+			pos: 0,
+		}
+	}
+
+	/// Returns a reference to the decoder.
+	pub fn decoder(&self) -> &OpusDecoder {
+		&self.decoder
+	}
+
+	/// Returns a mutable reference to the decoder.
+	pub fn decoder_mut(&mut self) -> &mut OpusDecoder {
+		&mut self.decoder
+	}
+
+	/// Returns a reference to the inner reader.
+	pub fn get_ref(&self) -> &R {
+		&self.inner
+	}
+
+	/// Returns a mutable reference to the inner reader.
+	///
+	/// Reading from it directly will desynchronize the packet stream.
+	pub fn get_mut(&mut self) -> &mut R {
+		&mut self.inner
+	}
+
+	/// Returns the inner reader, discarding any buffered audio.
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+
+	/// Reads the next packet from the inner reader, returning `false` if the
+	/// stream has ended.
+	fn read_packet(&mut self) -> Result<bool, IoError> {
+		let mut len = [0; 4];
+		let mut filled = 0;
+		while filled < len.len() {
+			match self.inner.read(&mut len[filled..]) {
+				Ok(0) if filled == 0 => return Ok(false),
+				Ok(0) => return Err(IoErrorKind::UnexpectedEof.into()),
+				Ok(n) => filled += n,
+				Err(err) if err.kind() == IoErrorKind::Interrupted => {}
+				Err(err) => return Err(err),
+			}
+		}
+		// Checked before allocating, so that a corrupt length can't make this
+		// allocate gigabytes.
+		let len = u32::from_be_bytes(len) as usize;
+		if len > packet::max_size_of_frames(48) {
+			return Err(IoError::new(
+				IoErrorKind::InvalidData,
+				"packet length exceeds the largest Opus packet",
+			));
+		}
+		self.packet.resize(len, 0);
+		self.inner.read_exact(&mut self.packet)?;
+		Ok(true)
+	}
+
+	fn decode_packet(&mut self) -> Result<(), IoError> {
+		let channels = self.decoder.channels();
+		let mut pcm = vec![0i16; self.frame_size * channels];
+		let len = self
+			.decoder
+			.decode_into(Some(&self.packet), &mut pcm, self.frame_size, false)?;
+		self.pcm.clear();
+		self.pcm.extend(
+			pcm[..len * channels]
+				.iter()
+				.flat_map(|sample| sample.to_le_bytes()),
+		);
+		self.pos = 0;
+		Ok(())
+	}
+}
+
+impl<R: Read> Read for OpusDecoderReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
+		let available = self.fill_buf()?;
+		let len = available.len().min(buf.len());
+		buf[..len].copy_from_slice(&available[..len]);
+		self.consume(len);
+		Ok(len)
+	}
+}
+
+impl<R: Read> BufRead for OpusDecoderReader<R> {
+	fn fill_buf(&mut self) -> Result<&[u8], IoError> {
+		while self.pos >= self.pcm.len() {
+			if !self.read_packet()? {
+				break;
+			}
+			self.decode_packet()?;
+		}
+		Ok(&self.pcm[self.pos.min(self.pcm.len())..])
+	}
+
+	fn consume(&mut self, amt: usize) {
+		self.pos = (self.pos + amt).min(self.pcm.len());
+	}
+}
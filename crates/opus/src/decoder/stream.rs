@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{channels::Channels, decoder::OpusDecoder, error::OpusResult, sample_rate::SampleRate};
+use alloc::{vec, vec::Vec};
+use bytes::Bytes;
+use core::{
+	mem,
+	pin::Pin,
+	task::{Context, Poll},
+};
+use futures::{ready, Stream};
+
+/// A [`Stream`] adapter that decodes Opus packets from an inner stream into
+/// interleaved PCM.
+///
+/// An empty packet marks a lost packet. Concealment of a lost packet is held
+/// back until the packet after it arrives, so that any forward error
+/// correction data it carries can be used to recover the lost audio. If the
+/// inner stream ends while a lost packet is still pending, it is concealed
+/// before the stream ends.
+pub struct OpusDecoderStream<S> {
+	decoder: OpusDecoder,
+	inner: S,
+	frame_size: usize,
+	pending_loss: bool,
+	decoded: Option<OpusResult<Vec<i16>>>,
+}
+
+impl<S> OpusDecoderStream<S>
+where
+	S: Stream<Item = Bytes> + Unpin,
+{
+	/// Creates a new stream, which decodes packets from `inner` with
+	/// `decoder`.
+	///
+	/// `frame_size` is the duration of each packet, in samples (per channel),
+	/// and is used as the duration of any lost packets.
+	pub fn new(decoder: OpusDecoder, inner: S, frame_size: usize) -> Self {
+		Self {
+			decoder,
+			inner,
+			frame_size,
+			pending_loss: false,
+			decoded: None,
+		}
+	}
+
+	/// Creates a new stream, which decodes 20 ms packets from `inner` to
+	/// 48 kHz stereo.
+	pub fn from_stream_48k_20ms(inner: S) -> OpusResult<Self> {
+		Ok(Self::new(
+			OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo)?,
+			inner,
+			960,
+		))
+	}
+
+	/// Returns a reference to the decoder.
+	pub fn decoder(&self) -> &OpusDecoder {
+		&self.decoder
+	}
+
+	/// Returns a mutable reference to the decoder.
+	pub fn decoder_mut(&mut self) -> &mut OpusDecoder {
+		&mut self.decoder
+	}
+
+	/// Returns a reference to the inner stream.
+	pub fn get_ref(&self) -> &S {
+		&self.inner
+	}
+
+	/// Returns a mutable reference to the inner stream.
+	pub fn get_mut(&mut self) -> &mut S {
+		&mut self.inner
+	}
+
+	/// Returns the inner stream, discarding any pending audio.
+	pub fn into_inner(self) -> S {
+		self.inner
+	}
+
+	fn decode(&mut self, packet: Option<&[u8]>, decode_fec: bool) -> OpusResult<Vec<i16>> {
+		let channels = self.decoder.channels();
+		let mut pcm = vec![0; self.frame_size * channels];
+		let len = self
+			.decoder
+			.decode_into(packet, &mut pcm, self.frame_size, decode_fec)?;
+		pcm.truncate(len * channels);
+		Ok(pcm)
+	}
+}
+
+impl<S> Stream for OpusDecoderStream<S>
+where
+	S: Stream<Item = Bytes> + Unpin,
+{
+	type Item = OpusResult<Vec<i16>>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		if let Some(decoded) = this.decoded.take() {
+			return Poll::Ready(Some(decoded));
+		}
+		loop {
+			match ready!(Pin::new(&mut this.inner).poll_next(cx)) {
+				Some(packet) if packet.is_empty() => {
+					// Only the most recent loss can be recovered by the next
+					// packet, so any earlier one has to be concealed now.
+					if mem::replace(&mut this.pending_loss, true) {
+						return Poll::Ready(Some(this.decode(None, false)));
+					}
+				}
+				Some(packet) => {
+					if mem::take(&mut this.pending_loss) {
+						let recovered = this.decode(Some(&packet), true);
+						this.decoded = Some(this.decode(Some(&packet), false));
+						return Poll::Ready(Some(recovered));
+					}
+					return Poll::Ready(Some(this.decode(Some(&packet), false)));
+				}
+				None if mem::take(&mut this.pending_loss) => {
+					return Poll::Ready(Some(this.decode(None, false)));
+				}
+				None => return Poll::Ready(None),
+			}
+		}
+	}
+}
@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::OpusErrorCode;
+use alloc::{boxed::Box, sync::Arc, vec};
+
+/// The size of the header in front of each array of weights.
+const WEIGHT_HEAD_SIZE: usize = 64;
+
+/// A copy of a DNN weights blob, which the codec states loaded with it point
+/// into.
+///
+/// libopus reads the weights in place, as the structs and arrays they
+/// describe, so the bytes are stored in 8-byte aligned words.
+#[derive(Debug)]
+pub(crate) struct DnnBlob {
+	words: Box<[u64]>,
+}
+
+impl DnnBlob {
+	/// Copies a weights blob, returning [`OpusErrorCode::BadArg`] if it isn't
+	/// made up of whole, valid records.
+	pub(crate) fn new(blob: &[u8]) -> Result<Arc<Self>, OpusErrorCode> {
+		if !is_valid(blob) {
+			return Err(OpusErrorCode::BadArg);
+		}
+		let mut words = vec![0u64; blob.len().div_ceil(8)].into_boxed_slice();
+		// SAFETY: `words` holds at least `blob.len()` bytes, and a `u64`
+		// slice can be viewed as bytes.
+		unsafe {
+			core::ptr::copy_nonoverlapping(blob.as_ptr(), words.as_mut_ptr().cast(), blob.len());
+		}
+		Ok(Arc::new(Self { words }))
+	}
+
+	pub(crate) fn as_ptr(&self) -> *const u8 {
+		self.words.as_ptr().cast()
+	}
+}
+
+/// Checks that a weights blob is a sequence of records libopus can parse.
+///
+/// libopus doesn't handle failing to parse a blob, and goes on to load the
+/// models from a null list of weights, so this mirrors its checks.
+fn is_valid(mut blob: &[u8]) -> bool {
+	let read_int = |head: &[u8], offset: usize| {
+		i32::from_ne_bytes(head[offset..offset + 4].try_into().unwrap())
+	};
+	while !blob.is_empty() {
+		let Some((head, rest)) = blob.split_at_checked(WEIGHT_HEAD_SIZE) else {
+			return false;
+		};
+		let size = read_int(head, 12);
+		let block_size = read_int(head, 16);
+		// The name is a null-terminated string filling the rest of the header.
+		if size <= 0 || block_size < size || head[WEIGHT_HEAD_SIZE - 1] != 0 {
+			return false;
+		}
+		let Some(rest) = rest.get(block_size as usize..) else {
+			return false;
+		};
+		blob = rest;
+	}
+	true
+}
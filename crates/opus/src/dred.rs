@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Deep REDundancy (DRED), which lets the decoder recover up to about a second
+//! of audio from before a packet, when the packets carrying it were lost.
+//!
+//! Recovering audio with DRED takes two steps: the redundancy is parsed out of
+//! a received packet into an [`OpusDred`] with an [`OpusDredDecoder`], and is
+//! then decoded into audio for the lost packets with the regular decoder.
+//! Packets only carry DRED if the encoder was configured to add it with
+//! [`OpusEncoder::set_dred_duration`](crate::OpusEncoder::set_dred_duration).
+#[cfg(feature = "weights-file")]
+use crate::{dnn_blob::DnnBlob, error::OpusErrorCode};
+use crate::{
+	error::{ErrorContext, Operation, OpusResult},
+	map_error,
+	state::StateBuffer,
+};
+#[cfg(feature = "weights-file")]
+use alloc::{sync::Arc, vec::Vec};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+#[cfg(feature = "weights-file")]
+use meowlouder_opus_sys::OPUS_SET_DNN_BLOB_REQUEST;
+use meowlouder_opus_sys::{
+	opus_dred_decoder_ctl, opus_dred_decoder_get_size, opus_dred_decoder_init, opus_dred_get_size,
+	opus_dred_parse, opus_dred_process, OPUS_RESET_STATE,
+};
+
+/// A DRED decoder, which parses the redundancy carried by packets.
+///
+/// This is [`Send`] but not [`Sync`], like
+/// [`OpusEncoder`](crate::encode::OpusEncoder#threading).
+pub struct OpusDredDecoder {
+	decoder_state: StateBuffer,
+	/// The weights blobs the decoder state may point into.
+	#[cfg(feature = "weights-file")]
+	dnn_blobs: Vec<Arc<DnnBlob>>,
+	_not_sync: PhantomData<*mut ()>,
+}
+
+// SAFETY: The only pointers in the DRED decoder state are the model's, to
+// the built-in weights, or to the blobs in `dnn_blobs` once others have been
+// loaded, which are never written to and are owned by the decoder.
+unsafe impl Send for OpusDredDecoder {}
+
+impl OpusDredDecoder {
+	pub fn new() -> OpusResult<Self> {
+		let decoder_size = unsafe { opus_dred_decoder_get_size() as usize };
+		let mut decoder_state = StateBuffer::new(decoder_size);
+		map_error!(unsafe { opus_dred_decoder_init(decoder_state.as_mut_ptr().cast()) })
+			.context(Operation::DecoderInit)?;
+		Ok(Self {
+			decoder_state,
+			#[cfg(feature = "weights-file")]
+			dnn_blobs: Vec::new(),
+			_not_sync: PhantomData,
+		})
+	}
+
+	/// Parses the DRED data out of `packet` into `dred`.
+	///
+	/// `max_dred_samples` is the most audio, in samples at `sampling_rate`,
+	/// which may need to be recovered, so that no more DRED than that is
+	/// decoded. `sampling_rate` doesn't need to match the sampling rate of the
+	/// audio. If `defer_processing` is set, the expensive part of decoding the
+	/// DRED is left for [`OpusDredDecoder::dred_process`], so that it can be
+	/// skipped unless a packet is actually lost.
+	///
+	/// Returns the offset of the first recoverable sample before the start of
+	/// the packet, which is 0 if the packet carries no DRED, along with the
+	/// number of silent samples between that and the last sample of
+	/// redundancy, both at `sampling_rate`.
+	pub fn dred_parse(
+		&mut self,
+		dred: &mut OpusDred,
+		packet: &[u8],
+		max_dred_samples: usize,
+		sampling_rate: i32,
+		defer_processing: bool,
+	) -> OpusResult<(usize, usize)> {
+		let mut dred_end = 0;
+		let offset = map_error!(usize, unsafe {
+			opus_dred_parse(
+				self.decoder_state.as_mut_ptr().cast(),
+				dred.state.as_mut_ptr().cast(),
+				packet.as_ptr(),
+				packet.len() as _,
+				max_dred_samples as _,
+				sampling_rate,
+				&mut dred_end,
+				defer_processing as _,
+			)
+		})
+		.context(Operation::ParseDred)?;
+		Ok((offset, dred_end as usize))
+	}
+
+	/// Finishes decoding DRED data which was parsed with `defer_processing`
+	/// set, in-place.
+	///
+	/// This does nothing if the DRED data has already been processed.
+	pub fn dred_process(&mut self, dred: &mut OpusDred) -> OpusResult<()> {
+		let state = dred.state.as_mut_ptr();
+		map_error!((), unsafe {
+			opus_dred_process(
+				self.decoder_state.as_mut_ptr().cast(),
+				state.cast_const().cast(),
+				state.cast(),
+			)
+		})
+		.context(Operation::ParseDred)
+	}
+
+	/// Resets the decoder state to be equivalent to a freshly initialized
+	/// state.
+	pub fn reset(&mut self) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_dred_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_RESET_STATE as _,
+			)
+		})
+		.context(Operation::Ctl("reset state"))
+	}
+
+	/// Loads the neural network weights of the DRED decoder from a
+	/// weights blob, which is needed before they can be used when libopus is
+	/// built with the `weights-file` feature.
+	///
+	/// The blob is copied, and the copy is kept alive for as long as the
+	/// DRED decoder is, as libopus points into it rather than copying the
+	/// weights. Returns [`OpusErrorCode::BadArg`] if the blob is truncated or
+	/// otherwise invalid.
+	#[cfg(feature = "weights-file")]
+	pub fn set_dnn_blob(&mut self, blob: &[u8]) -> OpusResult<()> {
+		let operation = Operation::Ctl("set DNN blob");
+		let len = i32::try_from(blob.len())
+			.map_err(|_| OpusErrorCode::BadArg)
+			.context(operation)?;
+		let blob = DnnBlob::new(blob).context(operation)?;
+		// A failed load can still leave some of the models pointing into the
+		// blob, so it's kept either way.
+		self.dnn_blobs.push(Arc::clone(&blob));
+		map_error!((), unsafe {
+			opus_dred_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_SET_DNN_BLOB_REQUEST as _,
+				blob.as_ptr(),
+				len,
+			)
+		})
+		.context(operation)
+	}
+}
+
+impl Debug for OpusDredDecoder {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusDredDecoder").finish_non_exhaustive()
+	}
+}
+
+/// The DRED data parsed out of a packet by an [`OpusDredDecoder`].
+//
+// Cloning copies the state byte-for-byte, which is sound as it holds nothing
+// but the decoded features and the parsing progress.
+#[derive(Clone)]
+pub struct OpusDred {
+	state: StateBuffer,
+}
+
+impl OpusDred {
+	/// Creates a new, empty DRED buffer.
+	pub fn new() -> Self {
+		let size = unsafe { opus_dred_get_size() as usize };
+		Self {
+			state: StateBuffer::new(size),
+		}
+	}
+
+	pub(crate) fn as_ptr(&self) -> *const u8 {
+		self.state.as_ptr()
+	}
+}
+
+impl Default for OpusDred {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Debug for OpusDred {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusDred").finish_non_exhaustive()
+	}
+}
@@ -1,6 +1,22 @@
 // SPDX-License-Identifier: MPL-2.0
 
+mod abr;
+mod config;
 mod encodable;
 mod encoder;
+mod frame;
+mod preset;
+#[cfg(feature = "futures")]
+mod sink;
+#[cfg(feature = "std")]
+mod writer;
 
-pub use self::{encodable::OpusEncodable, encoder::OpusEncoder};
+pub use self::{
+	abr::AdaptiveBitrateController, config::OpusEncoderConfig, encodable::OpusEncodable,
+	encoder::OpusEncoder, frame::EncodedFrame, preset::OpusEncoderPreset,
+};
+
+#[cfg(feature = "futures")]
+pub use self::sink::{OpusEncoderSink, OpusSinkError};
+#[cfg(feature = "std")]
+pub use self::writer::OpusEncoderWriter;
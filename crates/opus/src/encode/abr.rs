@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{encode::OpusEncoder, error::OpusResult, frame_duration::FrameDuration, math};
+
+/// The packet loss ratio above which the bitrate is reduced.
+const LOSS_THRESHOLD: f64 = 0.05;
+/// The factor the bitrate is multiplied by when there is too much loss.
+const DECREASE_FACTOR: f64 = 0.85;
+/// How much the bitrate increases by for each second without too much loss,
+/// in bits per second.
+const INCREASE_PER_SECOND: f64 = 1000.0;
+
+/// Adapts the encoder bitrate to the network conditions, from the packet loss
+/// the receiver reports.
+///
+/// This is additive increase, multiplicative decrease (AIMD): whenever over 5%
+/// of the packets in a report were lost, the bitrate is cut by 15%, and
+/// otherwise it grows by 1 kbps for every second of audio sent, always staying
+/// within the configured bounds. The time passed is worked out from the
+/// number of packets sent, so the controller needs to know the
+/// [frame duration](AdaptiveBitrateController::set_frame_duration) of each
+/// packet (default: 20 ms).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveBitrateController {
+	bitrate: f64,
+	min_bitrate: i32,
+	max_bitrate: i32,
+	frame_duration: FrameDuration,
+}
+
+impl AdaptiveBitrateController {
+	/// Creates a controller which starts at `initial_bitrate`, and keeps the
+	/// bitrate between `min_bitrate` and `max_bitrate` bits per second,
+	/// inclusive.
+	///
+	/// # Panics
+	///
+	/// Panics if `min_bitrate` is greater than `max_bitrate`.
+	pub fn new(initial_bitrate: i32, min_bitrate: i32, max_bitrate: i32) -> Self {
+		assert!(
+			min_bitrate <= max_bitrate,
+			"minimum bitrate is greater than the maximum"
+		);
+		Self {
+			bitrate: initial_bitrate.clamp(min_bitrate, max_bitrate) as f64,
+			min_bitrate,
+			max_bitrate,
+			frame_duration: FrameDuration::Ms20,
+		}
+	}
+
+	/// Returns the current recommended bitrate, in bits per second.
+	pub fn bitrate(&self) -> i32 {
+		math::round(self.bitrate) as i32
+	}
+
+	/// Returns the minimum bitrate, in bits per second.
+	pub fn min_bitrate(&self) -> i32 {
+		self.min_bitrate
+	}
+
+	/// Returns the maximum bitrate, in bits per second.
+	pub fn max_bitrate(&self) -> i32 {
+		self.max_bitrate
+	}
+
+	/// Configures the duration of each packet sent, which is used to work out
+	/// how much time each report covers.
+	pub fn set_frame_duration(&mut self, duration: FrameDuration) {
+		self.frame_duration = duration;
+	}
+
+	/// Updates the bitrate from a receiver report covering `packets_sent`
+	/// packets, of which `packets_lost` were lost, and returns the new
+	/// recommended bitrate, in bits per second.
+	///
+	/// `rtt_ms` is the round trip time the report was measured with. It is
+	/// currently unused, as the bitrate is only adapted to packet loss.
+	pub fn update(&mut self, packets_sent: u32, packets_lost: u32, rtt_ms: f32) -> i32 {
+		let _ = rtt_ms;
+		if packets_sent > 0 {
+			let loss = packets_lost.min(packets_sent) as f64 / packets_sent as f64;
+			if loss > LOSS_THRESHOLD {
+				self.bitrate *= DECREASE_FACTOR;
+			} else {
+				let seconds =
+					packets_sent as f64 * self.frame_duration.samples_48k() as f64 / 48000.0;
+				self.bitrate += INCREASE_PER_SECOND * seconds;
+			}
+			self.bitrate = self
+				.bitrate
+				.clamp(self.min_bitrate as f64, self.max_bitrate as f64);
+		}
+		self.bitrate()
+	}
+
+	/// Configures the encoder to use the current recommended bitrate.
+	pub fn apply_to(&self, encoder: &mut OpusEncoder) -> OpusResult<()> {
+		encoder.set_bitrate(self.bitrate())
+	}
+}
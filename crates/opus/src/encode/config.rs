@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{bandwidth::OpusBandwidth, signal::OpusSignal};
+
+/// A snapshot of all the settable configuration of an
+/// [`OpusEncoder`](crate::OpusEncoder).
+///
+/// The forced bandpass set with
+/// [`OpusEncoder::set_bandwidth`](crate::OpusEncoder::set_bandwidth) is not
+/// included, as libopus only reports the bandpass currently in use, and
+/// restoring that would force it. Use `max_bandwidth` instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpusEncoderConfig {
+	/// The bitrate, in bits per second.
+	pub bitrate: i32,
+	/// The computational complexity, in the range of 0-10, inclusive.
+	pub complexity: i32,
+	/// Whether variable bitrate (VBR) is enabled.
+	pub vbr: bool,
+	/// Whether VBR is constrained.
+	pub vbr_constraint: bool,
+	/// Whether in-band forward error correction is enabled.
+	pub inband_fec: bool,
+	/// Whether discontinuous transmission (DTX) is enabled.
+	pub dtx: bool,
+	/// The type of signal being encoded, or `None` if detected automatically.
+	pub signal: Option<OpusSignal>,
+	/// The maximum bandpass the encoder will select automatically.
+	pub max_bandwidth: OpusBandwidth,
+	/// The expected packet loss percentage, in the range of 0-100, inclusive.
+	pub expected_packet_loss: i32,
+	/// The number of channels to force, or `None` if picked automatically.
+	pub force_channels: Option<usize>,
+	/// Whether inter-frame prediction is disabled.
+	pub prediction_disabled: bool,
+	/// Whether phase inversion for intensity stereo is disabled.
+	pub phase_inversion_disabled: bool,
+	/// The depth of the signal being encoded, in bits.
+	pub lsb_depth: i32,
+}
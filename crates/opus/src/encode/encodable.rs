@@ -1,5 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
-use crate::{encode::OpusEncoder, error::OpusErrorCode, map_error};
+use crate::{
+	encode::OpusEncoder,
+	error::{ErrorContext, Operation, OpusResult},
+	ffi, map_error,
+};
 use meowlouder_opus_sys::{opus_encode, opus_encode_float};
 
 pub trait OpusEncodable: Sized {
@@ -8,7 +12,7 @@ pub trait OpusEncodable: Sized {
 		pcm: &[Self],
 		frame_size: usize,
 		data: &mut [u8],
-	) -> Result<usize, OpusErrorCode>;
+	) -> OpusResult<usize>;
 }
 
 impl OpusEncodable for i16 {
@@ -17,16 +21,20 @@ impl OpusEncodable for i16 {
 		pcm: &[Self],
 		frame_size: usize,
 		data: &mut [u8],
-	) -> Result<usize, OpusErrorCode> {
+	) -> OpusResult<usize> {
+		encoder.check_buffers(pcm.len(), frame_size, data.len())?;
+		#[cfg(feature = "tracing")]
+		let _span = tracing::trace_span!("opus_encode_ffi").entered();
 		map_error!(usize, unsafe {
 			opus_encode(
 				encoder.encoder_state.as_mut_ptr().cast(),
 				pcm.as_ptr(),
-				frame_size as _,
+				ffi::c_len(frame_size, Operation::Encode)?,
 				data.as_mut_ptr(),
-				data.len() as _,
+				ffi::c_capacity(data.len()),
 			)
 		})
+		.context(Operation::Encode)
 	}
 }
 
@@ -36,15 +44,19 @@ impl OpusEncodable for f32 {
 		pcm: &[Self],
 		frame_size: usize,
 		data: &mut [u8],
-	) -> Result<usize, OpusErrorCode> {
+	) -> OpusResult<usize> {
+		encoder.check_buffers(pcm.len(), frame_size, data.len())?;
+		#[cfg(feature = "tracing")]
+		let _span = tracing::trace_span!("opus_encode_ffi").entered();
 		map_error!(usize, unsafe {
 			opus_encode_float(
 				encoder.encoder_state.as_mut_ptr().cast(),
 				pcm.as_ptr(),
-				frame_size as _,
+				ffi::c_len(frame_size, Operation::Encode)?,
 				data.as_mut_ptr(),
-				data.len() as _,
+				ffi::c_capacity(data.len()),
 			)
 		})
+		.context(Operation::Encode)
 	}
 }
@@ -1,73 +1,888 @@
 // SPDX-License-Identifier: MPL-2.0
-use crate::{application::OpusApplication, encode::OpusEncodable, error::OpusErrorCode, map_error};
+#[cfg(feature = "weights-file")]
+use crate::dnn_blob::DnnBlob;
+use crate::{
+	application::OpusApplication,
+	bandwidth::OpusBandwidth,
+	channels::Channels,
+	encode::{
+		preset::PresetSettings, EncodedFrame, OpusEncodable, OpusEncoderConfig, OpusEncoderPreset,
+	},
+	error::{DebugCtl, ErrorContext, Operation, OpusError, OpusErrorCode, OpusResult},
+	ffi,
+	frame_duration::FrameDuration,
+	frame_size::FrameSize,
+	map_error, packet,
+	pool::{BufferPool, PooledPacket},
+	sample_rate::SampleRate,
+	signal::OpusSignal,
+	state::StateBuffer,
+};
+#[cfg(feature = "weights-file")]
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+#[cfg(feature = "weights-file")]
+use meowlouder_opus_sys::OPUS_SET_DNN_BLOB_REQUEST;
 use meowlouder_opus_sys::{
-	opus_encoder_ctl, opus_encoder_get_size, opus_encoder_init, OPUS_GET_BANDWIDTH_REQUEST,
-	OPUS_GET_PACKET_LOSS_PERC_REQUEST, OPUS_GET_SAMPLE_RATE_REQUEST, OPUS_RESET_STATE,
-	OPUS_SET_PACKET_LOSS_PERC_REQUEST,
+	opus_encoder_ctl, opus_encoder_get_size, opus_encoder_init, OPUS_AUTO, OPUS_FRAMESIZE_ARG,
+	OPUS_GET_APPLICATION_REQUEST, OPUS_GET_BANDWIDTH_REQUEST, OPUS_GET_BITRATE_REQUEST,
+	OPUS_GET_COMPLEXITY_REQUEST, OPUS_GET_DTX_REQUEST, OPUS_GET_EXPERT_FRAME_DURATION_REQUEST,
+	OPUS_GET_FINAL_RANGE_REQUEST, OPUS_GET_FORCE_CHANNELS_REQUEST, OPUS_GET_INBAND_FEC_REQUEST,
+	OPUS_GET_IN_DTX_REQUEST, OPUS_GET_LOOKAHEAD_REQUEST, OPUS_GET_LSB_DEPTH_REQUEST,
+	OPUS_GET_MAX_BANDWIDTH_REQUEST, OPUS_GET_PACKET_LOSS_PERC_REQUEST,
+	OPUS_GET_PHASE_INVERSION_DISABLED_REQUEST, OPUS_GET_PREDICTION_DISABLED_REQUEST,
+	OPUS_GET_SAMPLE_RATE_REQUEST, OPUS_GET_SIGNAL_REQUEST, OPUS_GET_VBR_CONSTRAINT_REQUEST,
+	OPUS_GET_VBR_REQUEST, OPUS_RESET_STATE, OPUS_SET_APPLICATION_REQUEST,
+	OPUS_SET_BANDWIDTH_REQUEST, OPUS_SET_BITRATE_REQUEST, OPUS_SET_COMPLEXITY_REQUEST,
+	OPUS_SET_DTX_REQUEST, OPUS_SET_EXPERT_FRAME_DURATION_REQUEST, OPUS_SET_FORCE_CHANNELS_REQUEST,
+	OPUS_SET_INBAND_FEC_REQUEST, OPUS_SET_LSB_DEPTH_REQUEST, OPUS_SET_MAX_BANDWIDTH_REQUEST,
+	OPUS_SET_PACKET_LOSS_PERC_REQUEST, OPUS_SET_PHASE_INVERSION_DISABLED_REQUEST,
+	OPUS_SET_PREDICTION_DISABLED_REQUEST, OPUS_SET_SIGNAL_REQUEST, OPUS_SET_VBR_CONSTRAINT_REQUEST,
+	OPUS_SET_VBR_REQUEST,
 };
+#[cfg(feature = "dred")]
+use meowlouder_opus_sys::{OPUS_GET_DRED_DURATION_REQUEST, OPUS_SET_DRED_DURATION_REQUEST};
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
 
-const MAX_DATA_BYTES: usize = 1275;
+/// The largest packet the encoder can produce: 120 ms, coded as six 20 ms
+/// frames.
+const MAX_DATA_BYTES: usize = packet::max_size_of_frames(6);
 
+/// An Opus encoder.
+///
+/// Cloning the encoder mid-stream gives an independent encoder in exactly the
+/// same state, which continues encoding bit-exactly like the original would.
+///
+/// # Threading
+///
+/// This is [`Send`], so it can be moved to another thread, but not [`Sync`],
+/// as libopus does not support concurrent access to the same encoder state.
+/// Wrap it in a [`Mutex`](std::sync::Mutex) if it needs to be shared between
+/// threads.
+///
+/// The other encoders and decoders in this crate, such as
+/// [`OpusDecoder`](crate::decoder::OpusDecoder) and
+/// [`OpusMSEncoder`](crate::multistream::OpusMSEncoder), work the same way.
+//
+// Cloning the encoder copies its state byte-for-byte, which is sound because
+// libopus codec state is self-contained: sub-states are referenced by offsets
+// from the start of the state rather than by pointers, and the only pointers
+// it does hold (the static CELT mode, and the neural network weights) point
+// to data that lives outside of the state.
+// The energy mask pointer in the encoder state is only ever set by
+// multistream encoders, and is always null for standalone encoders.
 #[derive(Clone)]
 pub struct OpusEncoder {
-	pub(crate) encoder_state: Box<[u8]>,
+	pub(crate) encoder_state: StateBuffer,
+	sample_rate: SampleRate,
+	channels: Channels,
+	/// The number of samples (per channel) encoded since the encoder was
+	/// initialized or last reset.
+	timestamp: u64,
+	/// The frame size of the last frame encoded, or 0 if none have been.
+	last_frame_size: usize,
+	/// The weights blobs the encoder state may point into.
+	#[cfg(feature = "weights-file")]
+	dnn_blobs: Vec<Arc<DnnBlob>>,
+	_not_sync: PhantomData<*mut ()>,
 }
 
+// SAFETY: The encoder state is a plain, self-contained C struct with no
+// thread-local or thread-affine data, so it can be freely moved between
+// threads. libopus has no global mutable state, and the only pointers in the
+// state are to the static CELT mode and to the weights in `dnn_blobs`, which
+// are never written to and are owned by the encoder.
+//
+// `_not_sync` opts out of `Sync`, as concurrent access through shared
+// references is not something libopus supports: nothing in it is atomic, and
+// its ctl interface takes a mutable state pointer even to read a setting,
+// which is why every method calling into libopus takes `&mut self`. Sharing
+// an encoder through a `Mutex` only needs `Send`.
+unsafe impl Send for OpusEncoder {}
+
 impl OpusEncoder {
 	pub fn new(
+		sample_rate: SampleRate,
+		channels: Channels,
+		application: OpusApplication,
+	) -> OpusResult<Self> {
+		Self::init(sample_rate, channels, application)
+	}
+
+	/// Creates an encoder like [`OpusEncoder::new`], with the sampling rate in
+	/// Hz and the number of channels as an integer.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if libopus doesn't support the
+	/// sampling rate, or there aren't 1 or 2 channels.
+	pub fn new_raw(
 		sample_rate: i32,
 		channels: i32,
 		application: OpusApplication,
-	) -> Result<Self, OpusErrorCode> {
-		debug_assert!(channels <= 2, "channels cannot be over 2");
-		let encoder_size = unsafe { opus_encoder_get_size(channels) as usize };
-		let mut encoder_state = vec![0; encoder_size].into_boxed_slice();
+	) -> OpusResult<Self> {
+		let sample_rate = SampleRate::try_from(sample_rate).context(Operation::EncoderInit)?;
+		let channels = Channels::try_from(channels).context(Operation::EncoderInit)?;
+		Self::init(sample_rate, channels, application)
+	}
+
+	fn init(
+		sample_rate: SampleRate,
+		channels: Channels,
+		application: OpusApplication,
+	) -> OpusResult<Self> {
+		let encoder_size = unsafe { opus_encoder_get_size(channels.into()) as usize };
+		let mut encoder_state = StateBuffer::new(encoder_size);
 		map_error!(unsafe {
 			opus_encoder_init(
 				encoder_state.as_mut_ptr().cast(),
-				sample_rate,
-				channels,
+				sample_rate.into(),
+				channels.into(),
 				application.into(),
 			)
-		})?;
-		Ok(Self { encoder_state })
+		})
+		.context(Operation::EncoderInit)?;
+		Ok(Self {
+			encoder_state,
+			sample_rate,
+			channels,
+			timestamp: 0,
+			last_frame_size: 0,
+			#[cfg(feature = "weights-file")]
+			dnn_blobs: Vec::new(),
+			_not_sync: PhantomData,
+		})
 	}
 
 	pub fn encode_into<T: OpusEncodable>(
 		&mut self,
 		pcm: &[T],
-		frame_size: usize,
+		frame_size: impl Into<FrameSize>,
 		data: &mut [u8],
-	) -> Result<usize, OpusErrorCode> {
-		T::encode(self, pcm, frame_size, data)
+	) -> OpusResult<usize> {
+		let frame_size = FrameSize::from_samples(frame_size.into().samples(), self.sample_rate)
+			.context(Operation::Encode)?
+			.samples();
+		let result = T::encode(self, pcm, frame_size, data);
+		#[cfg(feature = "tracing")]
+		match &result {
+			Ok(len) => tracing::trace!(
+				encoded_bytes = len,
+				frame_size,
+				sample_rate = self.sample_rate.as_i32(),
+				"encoded frame"
+			),
+			Err(err) => tracing::error!(
+				code = err.code().code(),
+				context = %err.operation(),
+				"failed to encode frame: {err}"
+			),
+		}
+		let len = result?;
+		self.timestamp += frame_size as u64;
+		self.last_frame_size = frame_size;
+		Ok(len)
 	}
 
 	pub fn encode<T: OpusEncodable>(
 		&mut self,
 		pcm: &[T],
-		frame_size: usize,
-	) -> Result<Vec<u8>, OpusErrorCode> {
-		let mut data = vec![0; MAX_DATA_BYTES];
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<Vec<u8>> {
+		let frame_size = frame_size.into();
+		let max_len = packet::max_packet_size(frame_size, self.sample_rate).min(MAX_DATA_BYTES);
+		let mut data = vec![0; max_len];
 		let len = self.encode_into(pcm, frame_size, &mut data)?;
 		data.truncate(len);
 		Ok(data)
 	}
 
+	/// Checks that `pcm` holds a whole frame of `frame_size` samples per
+	/// channel, and that there's room for at least one byte in `data`, as
+	/// libopus would otherwise read past the end of `pcm`.
+	pub(crate) fn check_buffers(
+		&self,
+		pcm_len: usize,
+		frame_size: usize,
+		data_len: usize,
+	) -> OpusResult<()> {
+		let required = ffi::pcm_len(frame_size, self.channels.count(), Operation::Encode)?;
+		if pcm_len < required {
+			return Err(OpusError::buffer_too_small(
+				Operation::Encode,
+				required,
+				pcm_len,
+			));
+		}
+		if data_len == 0 {
+			return Err(OpusError::buffer_too_small(Operation::Encode, 1, 0));
+		}
+		Ok(())
+	}
+
+	/// Encodes a frame into a self-delimited packet, which can be concatenated
+	/// with other packets without storing its length separately.
+	///
+	/// See [`packet::to_self_delimited`] for the framing used.
+	pub fn encode_self_delimited<T: OpusEncodable>(
+		&mut self,
+		pcm: &[T],
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<Vec<u8>> {
+		let mut data = [0; MAX_DATA_BYTES];
+		let len = self.encode_into(pcm, frame_size, &mut data)?;
+		packet::to_self_delimited(&data[..len])
+	}
+
+	/// Encodes a frame into a [`SmallVec`], which stores packets of up to 256
+	/// bytes inline rather than on the heap.
+	///
+	/// Packets at typical bitrates fit easily: a 20 ms frame at 32 kbps is 80
+	/// bytes on average, and exactly that with a constant bitrate.
+	#[cfg(feature = "smallvec")]
+	pub fn encode_small<T: OpusEncodable>(
+		&mut self,
+		pcm: &[T],
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<SmallVec<[u8; 256]>> {
+		let mut data = [0; MAX_DATA_BYTES];
+		let len = self.encode_into(pcm, frame_size, &mut data)?;
+		Ok(SmallVec::from_slice(&data[..len]))
+	}
+
+	/// Encodes a frame into a buffer taken from `pool`, rather than allocating
+	/// a new one, as [`OpusEncoder::encode`] does.
+	///
+	/// The buffer is returned to the pool once the packet is dropped.
+	pub fn encode_pooled<'pool, T: OpusEncodable>(
+		&mut self,
+		pcm: &[T],
+		frame_size: impl Into<FrameSize>,
+		pool: &'pool BufferPool,
+	) -> OpusResult<PooledPacket<'pool>> {
+		let mut packet = pool.get();
+		let data = packet.as_mut_vec();
+		data.resize(MAX_DATA_BYTES, 0);
+		let len = self.encode_into(pcm, frame_size, data)?;
+		data.truncate(len);
+		Ok(packet)
+	}
+
+	/// Encodes consecutive frames of `frame_size` samples (per channel) from
+	/// `pcm`, returning a packet for each.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if `pcm` doesn't hold a whole number
+	/// of frames.
+	pub fn batch_encode<T: OpusEncodable>(
+		&mut self,
+		pcm: &[T],
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<Vec<Vec<u8>>> {
+		let frame_size = frame_size.into().samples();
+		let frame_len = ffi::pcm_len(frame_size, self.channels.count(), Operation::Encode)?;
+		if frame_len == 0 || pcm.len() % frame_len != 0 {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Encode));
+		}
+		let mut data = [0; MAX_DATA_BYTES];
+		pcm.chunks_exact(frame_len)
+			.map(|frame| {
+				let len = self.encode_into(frame, frame_size, &mut data)?;
+				Ok(data[..len].to_vec())
+			})
+			.collect()
+	}
+
+	/// Flushes the audio still held in the encoder's lookahead at the end of
+	/// a stream, by encoding enough frames of silence to push it out.
+	///
+	/// The frames are the same size as the last frame encoded, and should be
+	/// appended to the end of the stream. The decoded stream then ends with
+	/// up to a frame of extra silence after the pre-skip is removed, which
+	/// can be trimmed using the total number of samples encoded before
+	/// draining. Returns no packets if nothing has been encoded yet.
+	pub fn drain(&mut self) -> OpusResult<Vec<Vec<u8>>> {
+		let frame_size = self.last_frame_size;
+		if frame_size == 0 {
+			return Ok(Vec::new());
+		}
+		let lookahead = self.lookahead()? as usize;
+		let silence = vec![0i16; frame_size * self.channels.count()];
+		(0..lookahead.div_ceil(frame_size))
+			.map(|_| self.encode(&silence, frame_size))
+			.collect()
+	}
+
+	/// Encodes a frame of the given duration, computing the frame size from
+	/// the encoder's sampling rate.
+	pub fn encode_duration<T: OpusEncodable>(
+		&mut self,
+		pcm: &[T],
+		duration: FrameDuration,
+	) -> OpusResult<Vec<u8>> {
+		self.encode(pcm, FrameSize::from_duration(duration, self.sample_rate))
+	}
+
+	/// Encodes a frame like [`OpusEncoder::encode`], additionally returning
+	/// information about the encoded frame.
+	pub fn encode_frame<T: OpusEncodable>(
+		&mut self,
+		pcm: &[T],
+		frame_size: impl Into<FrameSize>,
+	) -> OpusResult<EncodedFrame> {
+		let frame_size = frame_size.into().samples();
+		let timestamp = self.timestamp;
+		let data = self.encode(pcm, frame_size)?;
+		Ok(EncodedFrame {
+			bandwidth: packet::bandwidth(&data)?,
+			dtx: self.in_dtx()?,
+			data,
+			frame_size_samples: frame_size,
+			timestamp: Some(timestamp),
+		})
+	}
+
+	/// Returns the number of samples (per channel) encoded since the encoder
+	/// was initialized or last reset.
+	pub fn timestamp(&self) -> u64 {
+		self.timestamp
+	}
+
 	/// Resets the codec state to be equivalent to a freshly initialized state.
 	/// This should be called when switching streams in order to prevent the
 	/// back to back decoding from giving different results from one at a time
 	/// decoding.
-	pub fn reset(&mut self) -> Result<(), OpusErrorCode> {
+	///
+	/// This also resets the timestamp to 0, and forgets the last frame size
+	/// used by [`OpusEncoder::drain`].
+	pub fn reset(&mut self) -> OpusResult<()> {
 		map_error!((), unsafe {
 			opus_encoder_ctl(
 				self.encoder_state.as_mut_ptr().cast(),
 				OPUS_RESET_STATE as _,
 			)
 		})
+		.context(Operation::Ctl("reset state"))?;
+		self.timestamp = 0;
+		self.last_frame_size = 0;
+		Ok(())
+	}
+
+	/// Captures the encoder's current configuration, so that it can later be
+	/// re-applied with [`OpusEncoder::restore`].
+	pub fn snapshot(&mut self) -> OpusResult<OpusEncoderConfig> {
+		Ok(OpusEncoderConfig {
+			bitrate: self.bitrate()?,
+			complexity: self.complexity()?,
+			vbr: self.vbr()?,
+			vbr_constraint: self.vbr_constraint()?,
+			inband_fec: self.inband_fec()?,
+			dtx: self.dtx()?,
+			signal: self.signal()?,
+			max_bandwidth: self.max_bandwidth()?,
+			expected_packet_loss: self.expected_packet_loss()?,
+			force_channels: self.force_channels()?,
+			prediction_disabled: self.prediction_disabled()?,
+			phase_inversion_disabled: self.phase_inversion_disabled()?,
+			lsb_depth: self.lsb_depth()?,
+		})
+	}
+
+	/// Applies a configuration previously captured with
+	/// [`OpusEncoder::snapshot`].
+	pub fn restore(&mut self, config: &OpusEncoderConfig) -> OpusResult<()> {
+		self.set_bitrate(config.bitrate)?;
+		self.set_complexity(config.complexity)?;
+		self.set_vbr(config.vbr)?;
+		self.set_vbr_constraint(config.vbr_constraint)?;
+		self.set_inband_fec(config.inband_fec)?;
+		self.set_dtx(config.dtx)?;
+		self.set_signal(config.signal)?;
+		self.set_max_bandwidth(config.max_bandwidth)?;
+		self.set_expected_packet_loss(config.expected_packet_loss)?;
+		self.set_force_channels(config.force_channels)?;
+		self.set_prediction_disabled(config.prediction_disabled)?;
+		self.set_phase_inversion_disabled(config.phase_inversion_disabled)?;
+		self.set_lsb_depth(config.lsb_depth)
+	}
+
+	/// Applies all of a preset's settings.
+	///
+	/// If any of them fails to apply, the encoder's previous configuration is
+	/// restored before returning the error. Switching to or from the
+	/// restricted low delay application fails with [`OpusErrorCode::BadArg`]
+	/// once the encoder has encoded a frame, unless it is
+	/// [reset](OpusEncoder::reset) first.
+	pub fn apply_preset(&mut self, preset: OpusEncoderPreset) -> OpusResult<()> {
+		let application = self.application()?;
+		let frame_duration = self.expert_frame_duration()?;
+		let config = self.snapshot()?;
+		let result = self.apply_preset_settings(preset.settings());
+		if result.is_err() {
+			// These were all accepted before, so restoring them can't fail.
+			let _ = self.set_application(application);
+			let _ = self.set_expert_frame_duration(frame_duration);
+			let _ = self.restore(&config);
+		}
+		result
+	}
+
+	fn apply_preset_settings(&mut self, settings: PresetSettings) -> OpusResult<()> {
+		self.set_application(settings.application)?;
+		self.set_bitrate(settings.bitrate)?;
+		self.set_vbr(settings.vbr)?;
+		self.set_complexity(settings.complexity)?;
+		self.set_inband_fec(settings.inband_fec)?;
+		self.set_expected_packet_loss(settings.expected_packet_loss)?;
+		self.set_expert_frame_duration(settings.frame_duration)?;
+		self.set_force_channels(settings.force_channels)
+	}
+
+	/// Returns the number of channels the encoder was initialized with.
+	pub fn channels(&self) -> usize {
+		self.channels.count()
+	}
+
+	/// Returns the encoder's configured application.
+	pub fn application(&mut self) -> OpusResult<OpusApplication> {
+		let mut application = 0;
+		map_error!(&application, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_APPLICATION_REQUEST as _,
+				&mut application,
+			)
+		})
+		.and_then(OpusApplication::try_from)
+		.context(Operation::Ctl("get application"))
+	}
+
+	/// Configures the encoder's application.
+	///
+	/// Once the encoder has encoded a frame, this fails with
+	/// [`OpusErrorCode::BadArg`] if the application differs, unless the
+	/// encoder is [reset](OpusEncoder::reset) first.
+	pub fn set_application(&mut self, application: OpusApplication) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_APPLICATION_REQUEST as _,
+				i32::from(application),
+			)
+		})
+		.context(Operation::Ctl("set application"))
+	}
+
+	/// Returns the encoder's configured bitrate, in bits per second.
+	pub fn bitrate(&mut self) -> OpusResult<i32> {
+		let mut bitrate = 0;
+		map_error!(&bitrate, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_BITRATE_REQUEST as _,
+				&mut bitrate,
+			)
+		})
+		.context(Operation::Ctl("get bitrate"))
+	}
+
+	/// Configures the bitrate in the encoder.
+	///
+	/// `bitrate` is in bits per second, with a range of 500 to 512000
+	/// (inclusive). [`OPUS_AUTO`](meowlouder_opus_sys::OPUS_AUTO) lets the
+	/// encoder pick a bitrate based on the sample rate and channels, and
+	/// [`OPUS_BITRATE_MAX`](meowlouder_opus_sys::OPUS_BITRATE_MAX) uses as much
+	/// bitrate as possible.
+	pub fn set_bitrate(&mut self, bitrate: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_BITRATE_REQUEST as _,
+				bitrate,
+			)
+		})
+		.context(Operation::Ctl("set bitrate"))
+	}
+
+	/// Returns the encoder's configured computational complexity, in the range
+	/// of 0-10, inclusive.
+	pub fn complexity(&mut self) -> OpusResult<i32> {
+		let mut complexity = 0;
+		map_error!(&complexity, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_COMPLEXITY_REQUEST as _,
+				&mut complexity,
+			)
+		})
+		.context(Operation::Ctl("get complexity"))
+	}
+
+	/// Configures the encoder's computational complexity.
+	///
+	/// `complexity` is in the range of 0-10, inclusive, with 10 representing
+	/// the highest complexity.
+	pub fn set_complexity(&mut self, complexity: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_COMPLEXITY_REQUEST as _,
+				complexity,
+			)
+		})
+		.context(Operation::Ctl("set complexity"))
+	}
+
+	/// Returns whether the encoder is configured to use variable bitrate (VBR)
+	/// encoding (default: true).
+	pub fn vbr(&mut self) -> OpusResult<bool> {
+		let mut vbr = 0;
+		map_error!(&vbr, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_VBR_REQUEST as _,
+				&mut vbr,
+			)
+		})
+		.map(|vbr| vbr != 0)
+		.context(Operation::Ctl("get VBR"))
+	}
+
+	/// Enables or disables variable bitrate (VBR) in the encoder.
+	/// When disabled, the encoder uses hard constant bitrate (CBR).
+	pub fn set_vbr(&mut self, vbr: bool) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_VBR_REQUEST as _,
+				vbr as i32,
+			)
+		})
+		.context(Operation::Ctl("set VBR"))
+	}
+
+	/// Returns whether the encoder is configured to use in-band forward error
+	/// correction (default: false).
+	pub fn inband_fec(&mut self) -> OpusResult<bool> {
+		let mut inband_fec = 0;
+		map_error!(&inband_fec, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_INBAND_FEC_REQUEST as _,
+				&mut inband_fec,
+			)
+		})
+		.map(|inband_fec| inband_fec != 0)
+		.context(Operation::Ctl("get inband FEC"))
+	}
+
+	/// Configures the encoder's use of in-band forward error correction (FEC).
+	/// This only has an effect when the encoder is using SILK, and when the
+	/// expected packet loss is non-zero.
+	pub fn set_inband_fec(&mut self, inband_fec: bool) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_INBAND_FEC_REQUEST as _,
+				inband_fec as i32,
+			)
+		})
+		.context(Operation::Ctl("set inband FEC"))
+	}
+
+	/// Returns whether the encoder is configured to use constrained VBR
+	/// (default: true).
+	pub fn vbr_constraint(&mut self) -> OpusResult<bool> {
+		let mut vbr_constraint = 0;
+		map_error!(&vbr_constraint, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_VBR_CONSTRAINT_REQUEST as _,
+				&mut vbr_constraint,
+			)
+		})
+		.map(|vbr_constraint| vbr_constraint != 0)
+		.context(Operation::Ctl("get VBR constraint"))
+	}
+
+	/// Enables or disables constrained VBR in the encoder.
+	/// This setting is ignored when the encoder is in CBR mode.
+	pub fn set_vbr_constraint(&mut self, vbr_constraint: bool) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_VBR_CONSTRAINT_REQUEST as _,
+				vbr_constraint as i32,
+			)
+		})
+		.context(Operation::Ctl("set VBR constraint"))
+	}
+
+	/// Returns whether the encoder is configured to use discontinuous
+	/// transmission (DTX) (default: false).
+	pub fn dtx(&mut self) -> OpusResult<bool> {
+		let mut dtx = 0;
+		map_error!(&dtx, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_DTX_REQUEST as _,
+				&mut dtx,
+			)
+		})
+		.map(|dtx| dtx != 0)
+		.context(Operation::Ctl("get DTX"))
+	}
+
+	/// Configures the encoder's use of discontinuous transmission (DTX).
+	/// This is only applicable to the LPC layer.
+	pub fn set_dtx(&mut self, dtx: bool) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_DTX_REQUEST as _,
+				dtx as i32,
+			)
+		})
+		.context(Operation::Ctl("set DTX"))
+	}
+
+	/// Returns the encoder's configured signal type, or `None` if the encoder
+	/// detects it automatically (default: `None`).
+	pub fn signal(&mut self) -> OpusResult<Option<OpusSignal>> {
+		let mut signal = 0;
+		map_error!(&signal, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_SIGNAL_REQUEST as _,
+				&mut signal,
+			)
+		})
+		.and_then(|signal| match signal {
+			OPUS_AUTO => Ok(None),
+			signal => OpusSignal::try_from(signal).map(Some),
+		})
+		.context(Operation::Ctl("get signal"))
+	}
+
+	/// Configures the type of signal being encoded.
+	/// This is a hint which helps the encoder's mode selection, with `None`
+	/// letting the encoder detect it automatically.
+	pub fn set_signal(&mut self, signal: Option<OpusSignal>) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_SIGNAL_REQUEST as _,
+				signal.map_or(OPUS_AUTO, i32::from),
+			)
+		})
+		.context(Operation::Ctl("set signal"))
+	}
+
+	/// Returns the frame duration the encoder is forced to use, or `None` if
+	/// it uses the frame size given when encoding (default: `None`).
+	pub fn expert_frame_duration(&mut self) -> OpusResult<Option<FrameDuration>> {
+		let mut duration = 0;
+		map_error!(&duration, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_EXPERT_FRAME_DURATION_REQUEST as _,
+				&mut duration,
+			)
+		})
+		.and_then(|duration| match duration as u32 {
+			OPUS_FRAMESIZE_ARG => Ok(None),
+			_ => FrameDuration::try_from(duration).map(Some),
+		})
+		.context(Operation::Ctl("get expert frame duration"))
+	}
+
+	/// Forces the encoder to use frames of the given duration, or lets it use
+	/// the frame size given when encoding if `None`.
+	///
+	/// A frame size given when encoding that is longer than `duration` is
+	/// still encoded, with the encoder only using the first `duration` of it,
+	/// so this keeps an application from accidentally producing longer frames
+	/// than it can tolerate. A shorter frame size fails with
+	/// [`OpusErrorCode::BadArg`].
+	pub fn set_expert_frame_duration(&mut self, duration: Option<FrameDuration>) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_EXPERT_FRAME_DURATION_REQUEST as _,
+				duration.map_or(OPUS_FRAMESIZE_ARG as i32, i32::from),
+			)
+		})
+		.context(Operation::Ctl("set expert frame duration"))
+	}
+
+	/// Sets the encoder's bandpass to a specific value, or lets the encoder
+	/// pick it automatically if `None` (default: `None`).
+	///
+	/// This prevents the encoder from automatically selecting the bandpass
+	/// based on the available bitrate.
+	pub fn set_bandwidth(&mut self, bandwidth: Option<OpusBandwidth>) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_BANDWIDTH_REQUEST as _,
+				bandwidth.map_or(OPUS_AUTO, i32::from),
+			)
+		})
+		.context(Operation::Ctl("set bandwidth"))
+	}
+
+	/// Returns the encoder's configured maximum bandpass (default: fullband).
+	pub fn max_bandwidth(&mut self) -> OpusResult<OpusBandwidth> {
+		let mut max_bandwidth = 0;
+		map_error!(&max_bandwidth, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_MAX_BANDWIDTH_REQUEST as _,
+				&mut max_bandwidth,
+			)
+		})
+		.and_then(OpusBandwidth::try_from)
+		.context(Operation::Ctl("get max bandwidth"))
+	}
+
+	/// Configures the maximum bandpass that the encoder will select
+	/// automatically.
+	pub fn set_max_bandwidth(&mut self, max_bandwidth: OpusBandwidth) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_MAX_BANDWIDTH_REQUEST as _,
+				i32::from(max_bandwidth),
+			)
+		})
+		.context(Operation::Ctl("set max bandwidth"))
+	}
+
+	/// Returns the number of channels the encoder is configured to force, or
+	/// `None` if it picks automatically (default: `None`).
+	pub fn force_channels(&mut self) -> OpusResult<Option<usize>> {
+		let mut force_channels = 0;
+		map_error!(&force_channels, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_FORCE_CHANNELS_REQUEST as _,
+				&mut force_channels,
+			)
+		})
+		.map(|force_channels| match force_channels {
+			OPUS_AUTO => None,
+			force_channels => Some(force_channels as usize),
+		})
+		.context(Operation::Ctl("get force channels"))
+	}
+
+	/// Configures mono/stereo forcing in the encoder.
+	/// This can force the encoder to produce packets encoded as either mono or
+	/// stereo, regardless of the format of the input audio, with `None`
+	/// letting the encoder pick automatically.
+	pub fn set_force_channels(&mut self, force_channels: Option<usize>) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_FORCE_CHANNELS_REQUEST as _,
+				force_channels.map_or(OPUS_AUTO, |force_channels| force_channels as i32),
+			)
+		})
+		.context(Operation::Ctl("set force channels"))
+	}
+
+	/// Returns whether the encoder has inter-frame prediction disabled
+	/// (default: false).
+	pub fn prediction_disabled(&mut self) -> OpusResult<bool> {
+		let mut prediction_disabled = 0;
+		map_error!(&prediction_disabled, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_PREDICTION_DISABLED_REQUEST as _,
+				&mut prediction_disabled,
+			)
+		})
+		.map(|prediction_disabled| prediction_disabled != 0)
+		.context(Operation::Ctl("get prediction disabled"))
+	}
+
+	/// Disables almost all use of prediction, making frames almost completely
+	/// independent. This reduces quality.
+	pub fn set_prediction_disabled(&mut self, prediction_disabled: bool) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_PREDICTION_DISABLED_REQUEST as _,
+				prediction_disabled as i32,
+			)
+		})
+		.context(Operation::Ctl("set prediction disabled"))
+	}
+
+	/// Returns whether the encoder has phase inversion disabled
+	/// (default: false).
+	pub fn phase_inversion_disabled(&mut self) -> OpusResult<bool> {
+		let mut phase_inversion_disabled = 0;
+		map_error!(&phase_inversion_disabled, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_PHASE_INVERSION_DISABLED_REQUEST as _,
+				&mut phase_inversion_disabled,
+			)
+		})
+		.map(|phase_inversion_disabled| phase_inversion_disabled != 0)
+		.context(Operation::Ctl("get phase inversion disabled"))
+	}
+
+	/// Disables the use of phase inversion for intensity stereo, improving the
+	/// quality of mono downmixes, but slightly reducing normal stereo quality.
+	pub fn set_phase_inversion_disabled(
+		&mut self,
+		phase_inversion_disabled: bool,
+	) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_PHASE_INVERSION_DISABLED_REQUEST as _,
+				phase_inversion_disabled as i32,
+			)
+		})
+		.context(Operation::Ctl("set phase inversion disabled"))
+	}
+
+	/// Returns the encoder's configured signal depth, in bits (default: 24).
+	pub fn lsb_depth(&mut self) -> OpusResult<i32> {
+		let mut lsb_depth = 0;
+		map_error!(&lsb_depth, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_LSB_DEPTH_REQUEST as _,
+				&mut lsb_depth,
+			)
+		})
+		.context(Operation::Ctl("get LSB depth"))
+	}
+
+	/// Configures the depth of the signal being encoded.
+	///
+	/// `lsb_depth` is the depth in bits, in the range of 8-24, inclusive.
+	/// This is a hint which helps the encoder identify silence and
+	/// near-silence.
+	pub fn set_lsb_depth(&mut self, lsb_depth: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_LSB_DEPTH_REQUEST as _,
+				lsb_depth,
+			)
+		})
+		.context(Operation::Ctl("set LSB depth"))
 	}
 
 	/// Returns the encoder's configured bandpass.
-	pub fn bandwidth(&mut self) -> Result<i32, OpusErrorCode> {
+	pub fn bandwidth(&mut self) -> OpusResult<i32> {
 		let mut bandwidth = 0;
 		map_error!(&bandwidth, unsafe {
 			opus_encoder_ctl(
@@ -76,10 +891,57 @@ impl OpusEncoder {
 				&mut bandwidth,
 			)
 		})
+		.context(Operation::Ctl("get bandwidth"))
+	}
+
+	/// Returns the total number of samples (per channel, at the encoder's
+	/// sampling rate) of delay added by the encoder, which should be skipped
+	/// at the start of the decoded audio.
+	pub fn lookahead(&mut self) -> OpusResult<i32> {
+		let mut lookahead = 0;
+		map_error!(&lookahead, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_LOOKAHEAD_REQUEST as _,
+				&mut lookahead,
+			)
+		})
+		.context(Operation::Ctl("get lookahead"))
+	}
+
+	/// Returns the final state of the entropy coder after the last frame
+	/// encoded, which matches between an encoder and a decoder that stay in
+	/// sync, and is what conformance tests compare.
+	pub fn final_range(&mut self) -> OpusResult<u32> {
+		let mut final_range = 0u32;
+		map_error!(&final_range, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_FINAL_RANGE_REQUEST as _,
+				&mut final_range,
+			)
+		})
+		.context(Operation::Ctl("get final range"))
+	}
+
+	/// Returns whether the last frame encoded was in discontinuous transmission
+	/// (DTX), meaning it was silent enough that the encoder stopped sending
+	/// audio for it.
+	pub fn in_dtx(&mut self) -> OpusResult<bool> {
+		let mut in_dtx = 0;
+		map_error!(&in_dtx, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_IN_DTX_REQUEST as _,
+				&mut in_dtx,
+			)
+		})
+		.map(|in_dtx| in_dtx != 0)
+		.context(Operation::Ctl("get DTX state"))
 	}
 
 	/// Returns the sampling rate the encoder was initialized with.
-	pub fn sample_rate(&mut self) -> Result<i32, OpusErrorCode> {
+	pub fn sample_rate(&mut self) -> OpusResult<i32> {
 		let mut sample_rate = 0;
 		map_error!(&sample_rate, unsafe {
 			opus_encoder_ctl(
@@ -88,11 +950,12 @@ impl OpusEncoder {
 				&mut sample_rate,
 			)
 		})
+		.context(Operation::Ctl("get sample rate"))
 	}
 
 	/// Returns the encoder's configured packet loss percentage
 	/// in the range of 0-100, include (default: 0).
-	pub fn expected_packet_loss(&mut self) -> Result<i32, OpusErrorCode> {
+	pub fn expected_packet_loss(&mut self) -> OpusResult<i32> {
 		let mut packet_loss_percent = 0;
 		map_error!(&packet_loss_percent, unsafe {
 			opus_encoder_ctl(
@@ -101,6 +964,7 @@ impl OpusEncoder {
 				&mut packet_loss_percent,
 			)
 		})
+		.context(Operation::Ctl("get expected packet loss"))
 	}
 
 	/// Configures the encoder's expected packet loss percentage.
@@ -110,7 +974,7 @@ impl OpusEncoder {
 	///
 	/// `percentage` is the loss percentage in range 0-100, inclusive (default:
 	/// 0).
-	pub fn set_expected_packet_loss(&mut self, percentage: i32) -> Result<(), OpusErrorCode> {
+	pub fn set_expected_packet_loss(&mut self, percentage: i32) -> OpusResult<()> {
 		map_error!((), unsafe {
 			opus_encoder_ctl(
 				self.encoder_state.as_mut_ptr().cast(),
@@ -118,5 +982,90 @@ impl OpusEncoder {
 				percentage,
 			)
 		})
+		.context(Operation::Ctl("set expected packet loss"))
+	}
+
+	/// Returns the duration of Deep REDundancy (DRED) the encoder is
+	/// configured to add to each packet, in 10 ms units (default: 0).
+	#[cfg(feature = "dred")]
+	pub fn dred_duration(&mut self) -> OpusResult<i32> {
+		let mut dred_duration = 0;
+		map_error!(&dred_duration, unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_DRED_DURATION_REQUEST as _,
+				&mut dred_duration,
+			)
+		})
+		.context(Operation::Ctl("get DRED duration"))
+	}
+
+	/// Configures how much Deep REDundancy (DRED) to add to each packet, which
+	/// lets the decoder recover audio from before the packet when packets are
+	/// lost.
+	///
+	/// `duration` is in 10 ms units, up to 104 (just over a second). DRED is
+	/// only added when the expected packet loss is non-zero, and the bitrate is
+	/// high enough to leave room for it.
+	#[cfg(feature = "dred")]
+	pub fn set_dred_duration(&mut self, duration: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_DRED_DURATION_REQUEST as _,
+				duration,
+			)
+		})
+		.context(Operation::Ctl("set DRED duration"))
+	}
+
+	/// Loads the neural network weights used by the DNN features from a
+	/// weights blob, which is needed before they can be used when libopus is
+	/// built with the `weights-file` feature.
+	///
+	/// The blob is copied, and the copy is kept alive for as long as the
+	/// encoder (or any clone of it) is, as libopus points into it rather than
+	/// copying the weights. Returns [`OpusErrorCode::BadArg`] if the blob is
+	/// truncated or otherwise invalid.
+	#[cfg(feature = "weights-file")]
+	pub fn set_dnn_blob(&mut self, blob: &[u8]) -> OpusResult<()> {
+		let operation = Operation::Ctl("set DNN blob");
+		let len = i32::try_from(blob.len())
+			.map_err(|_| OpusErrorCode::BadArg)
+			.context(operation)?;
+		let blob = DnnBlob::new(blob).context(operation)?;
+		// A failed load can still leave some of the models pointing into the
+		// blob, so it's kept either way.
+		self.dnn_blobs.push(Arc::clone(&blob));
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_DNN_BLOB_REQUEST as _,
+				blob.as_ptr(),
+				len,
+			)
+		})
+		.context(operation)
+	}
+}
+
+impl Debug for OpusEncoder {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		// The getters need a mutable reference for the FFI call, but none of
+		// them actually modify the encoder state, so query a copy of it.
+		let mut encoder = self.clone();
+		f.debug_struct("OpusEncoder")
+			.field("sample_rate", &DebugCtl(encoder.sample_rate()))
+			.field("channels", &self.channels)
+			.field("application", &DebugCtl(encoder.application()))
+			.field("bitrate", &DebugCtl(encoder.bitrate()))
+			.field("complexity", &DebugCtl(encoder.complexity()))
+			.field("vbr", &DebugCtl(encoder.vbr()))
+			.field("inband_fec", &DebugCtl(encoder.inband_fec()))
+			.field(
+				"expected_packet_loss",
+				&DebugCtl(encoder.expected_packet_loss()),
+			)
+			.finish()
 	}
 }
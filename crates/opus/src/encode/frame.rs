@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::bandwidth::OpusBandwidth;
+use alloc::vec::Vec;
+
+/// An encoded Opus packet, along with information about the frame it encodes.
+///
+/// Returned by [`OpusEncoder::encode_frame`](crate::OpusEncoder::encode_frame).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedFrame {
+	/// The encoded packet.
+	pub data: Vec<u8>,
+	/// The number of samples (per channel) the packet encodes.
+	pub frame_size_samples: usize,
+	/// The position of the first sample of the frame in the encoded stream,
+	/// in samples (per channel) at the encoder's sampling rate.
+	pub timestamp: Option<u64>,
+	/// The audio bandwidth the packet was encoded with.
+	pub bandwidth: OpusBandwidth,
+	/// Whether the encoder was in discontinuous transmission (DTX) when
+	/// encoding the frame.
+	pub dtx: bool,
+}
+
+impl EncodedFrame {
+	/// Discards the metadata, returning just the encoded packet.
+	pub fn into_data(self) -> Vec<u8> {
+		self.data
+	}
+}
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{application::OpusApplication, frame_duration::FrameDuration};
+
+/// A ready-made encoder configuration for a common use case, applied with
+/// [`OpusEncoder::apply_preset`](crate::OpusEncoder::apply_preset).
+///
+/// Settings a preset doesn't mention are left as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OpusEncoderPreset {
+	/// A voice call over a lossy network: the VoIP application at 32 kbps
+	/// CBR, complexity 8, with in-band FEC tuned for 10% packet loss.
+	VoipCall,
+	/// Music streaming: the audio application at 128 kbps VBR, complexity 10,
+	/// without FEC.
+	MusicStreaming,
+	/// The lowest latency: the restricted low delay application at 24 kbps
+	/// VBR, complexity 5, with 10 ms frames.
+	LowLatency,
+	/// Spoken word recordings: the audio application at 48 kbps VBR,
+	/// complexity 9, forced to mono.
+	Podcast,
+}
+
+/// The settings an [`OpusEncoderPreset`] applies.
+pub(crate) struct PresetSettings {
+	pub(crate) application: OpusApplication,
+	pub(crate) bitrate: i32,
+	pub(crate) vbr: bool,
+	pub(crate) complexity: i32,
+	pub(crate) inband_fec: bool,
+	pub(crate) expected_packet_loss: i32,
+	pub(crate) frame_duration: Option<FrameDuration>,
+	pub(crate) force_channels: Option<usize>,
+}
+
+impl OpusEncoderPreset {
+	pub(crate) const fn settings(self) -> PresetSettings {
+		match self {
+			Self::VoipCall => PresetSettings {
+				application: OpusApplication::Voip,
+				bitrate: 32000,
+				vbr: false,
+				complexity: 8,
+				inband_fec: true,
+				expected_packet_loss: 10,
+				frame_duration: None,
+				force_channels: None,
+			},
+			Self::MusicStreaming => PresetSettings {
+				application: OpusApplication::Audio,
+				bitrate: 128000,
+				vbr: true,
+				complexity: 10,
+				inband_fec: false,
+				expected_packet_loss: 0,
+				frame_duration: None,
+				force_channels: None,
+			},
+			Self::LowLatency => PresetSettings {
+				application: OpusApplication::RestrictedLowDelay,
+				bitrate: 24000,
+				vbr: true,
+				complexity: 5,
+				inband_fec: false,
+				expected_packet_loss: 0,
+				frame_duration: Some(FrameDuration::Ms10),
+				force_channels: None,
+			},
+			Self::Podcast => PresetSettings {
+				application: OpusApplication::Audio,
+				bitrate: 48000,
+				vbr: true,
+				complexity: 9,
+				inband_fec: false,
+				expected_packet_loss: 0,
+				frame_duration: None,
+				force_channels: Some(1),
+			},
+		}
+	}
+}
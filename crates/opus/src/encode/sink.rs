@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	encode::OpusEncoder,
+	error::{OpusError, OpusResult},
+};
+use alloc::{collections::VecDeque, vec::Vec};
+use bytes::Bytes;
+use core::{
+	pin::Pin,
+	task::{Context, Poll},
+};
+use futures::{ready, Sink};
+
+/// An error from an [`OpusEncoderSink`].
+#[derive(Debug, thiserror::Error)]
+pub enum OpusSinkError<E> {
+	/// Encoding a frame failed.
+	#[error(transparent)]
+	Encode(#[from] OpusError),
+	/// The inner sink returned an error.
+	#[error("inner sink error: {0}")]
+	Inner(E),
+}
+
+/// A [`Sink`] adapter that encodes PCM into Opus packets, and forwards them to
+/// an inner sink.
+///
+/// PCM sent to it is buffered until a full frame has accumulated, at which
+/// point it is encoded. Any partial frame is only encoded when the sink is
+/// flushed or closed.
+///
+/// As [`SinkExt::send`](futures::SinkExt::send) flushes the sink after every
+/// item, which pads out any partial frame, use
+/// [`SinkExt::feed`](futures::SinkExt::feed) when sending PCM in chunks that
+/// aren't a multiple of the frame size.
+pub struct OpusEncoderSink<S> {
+	encoder: OpusEncoder,
+	inner: S,
+	frame_size: usize,
+	pcm: Vec<i16>,
+	packets: VecDeque<Bytes>,
+}
+
+impl<S> OpusEncoderSink<S>
+where
+	S: Sink<Bytes> + Unpin,
+{
+	/// Creates a new sink, which encodes frames of `frame_size` samples (per
+	/// channel) with `encoder`, and sends the packets to `inner`.
+	pub fn new(encoder: OpusEncoder, inner: S, frame_size: usize) -> Self {
+		Self {
+			encoder,
+			inner,
+			frame_size,
+			pcm: Vec::new(),
+			packets: VecDeque::new(),
+		}
+	}
+
+	/// Returns a reference to the encoder.
+	pub fn encoder(&self) -> &OpusEncoder {
+		&self.encoder
+	}
+
+	/// Returns a mutable reference to the encoder, for changing its settings
+	/// mid-stream.
+	pub fn encoder_mut(&mut self) -> &mut OpusEncoder {
+		&mut self.encoder
+	}
+
+	/// Returns a reference to the inner sink.
+	pub fn get_ref(&self) -> &S {
+		&self.inner
+	}
+
+	/// Returns a mutable reference to the inner sink.
+	pub fn get_mut(&mut self) -> &mut S {
+		&mut self.inner
+	}
+
+	/// Returns the inner sink, discarding any audio that has not been sent
+	/// yet.
+	pub fn into_inner(self) -> S {
+		self.inner
+	}
+
+	fn frame_len(&self) -> usize {
+		self.frame_size * self.encoder.channels()
+	}
+
+	fn encode_frame(&mut self) -> OpusResult<()> {
+		let frame_len = self.frame_len();
+		let packet = self
+			.encoder
+			.encode(&self.pcm[..frame_len], self.frame_size)?;
+		self.pcm.drain(..frame_len);
+		self.packets.push_back(Bytes::from(packet));
+		Ok(())
+	}
+
+	/// Sends as many of the encoded packets to the inner sink as it will
+	/// accept.
+	fn poll_send_packets(
+		&mut self,
+		cx: &mut Context<'_>,
+	) -> Poll<Result<(), OpusSinkError<S::Error>>> {
+		while !self.packets.is_empty() {
+			ready!(Pin::new(&mut self.inner).poll_ready(cx)).map_err(OpusSinkError::Inner)?;
+			let packet = self.packets.pop_front().expect("packet queue is not empty");
+			Pin::new(&mut self.inner)
+				.start_send(packet)
+				.map_err(OpusSinkError::Inner)?;
+		}
+		Poll::Ready(Ok(()))
+	}
+}
+
+impl<S> Sink<&[i16]> for OpusEncoderSink<S>
+where
+	S: Sink<Bytes> + Unpin,
+{
+	type Error = OpusSinkError<S::Error>;
+
+	fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		let this = self.get_mut();
+		ready!(this.poll_send_packets(cx))?;
+		Pin::new(&mut this.inner)
+			.poll_ready(cx)
+			.map_err(OpusSinkError::Inner)
+	}
+
+	fn start_send(self: Pin<&mut Self>, item: &[i16]) -> Result<(), Self::Error> {
+		let this = self.get_mut();
+		this.pcm.extend_from_slice(item);
+		while this.pcm.len() >= this.frame_len() {
+			this.encode_frame()?;
+		}
+		Ok(())
+	}
+
+	/// Encodes any partial frame that has been buffered, padding it out to a
+	/// full frame with silence, and then flushes the inner sink.
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		let this = self.get_mut();
+		if !this.pcm.is_empty() {
+			let frame_len = this.frame_len();
+			this.pcm.resize(frame_len, 0);
+			this.encode_frame()?;
+		}
+		ready!(this.poll_send_packets(cx))?;
+		Pin::new(&mut this.inner)
+			.poll_flush(cx)
+			.map_err(OpusSinkError::Inner)
+	}
+
+	fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		ready!(self.as_mut().poll_flush(cx))?;
+		Pin::new(&mut self.get_mut().inner)
+			.poll_close(cx)
+			.map_err(OpusSinkError::Inner)
+	}
+}
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::encode::OpusEncoder;
+use alloc::vec::Vec;
+use std::io::{Error as IoError, ErrorKind as IoErrorKind, Write};
+
+/// A [`Write`] adapter that encodes raw PCM into a stream of Opus packets.
+///
+/// Bytes written to it are interpreted as interleaved, little-endian `i16`
+/// samples. Whenever a full frame has accumulated, it is encoded, and the
+/// resulting packet is written to the inner writer, prefixed by its length as
+/// a 4-byte big-endian integer.
+///
+/// Any partial frame is only encoded when [`flush`](Write::flush) is called,
+/// so make sure to flush the writer once the input has ended, or the tail of
+/// the audio will be lost.
+///
+/// If encoding a frame or writing its packet fails partway through a
+/// [`write`](Write::write), the frame is dropped, the bytes taken so far are
+/// reported as written, and the error is returned by the next call instead.
+pub struct OpusEncoderWriter<W: Write> {
+	encoder: OpusEncoder,
+	inner: W,
+	frame_size: usize,
+	pcm: Vec<i16>,
+	partial_sample: Option<u8>,
+	error: Option<IoError>,
+}
+
+impl<W: Write> OpusEncoderWriter<W> {
+	/// Creates a new writer, which encodes frames of `frame_size` samples (per
+	/// channel) with `encoder`, and writes the packets to `inner`.
+	pub fn new(encoder: OpusEncoder, inner: W, frame_size: usize) -> Self {
+		let frame_len = frame_size * encoder.channels();
+		Self {
+			encoder,
+			inner,
+			frame_size,
+			pcm: Vec::with_capacity(frame_len),
+			partial_sample: None,
+			error: None,
+		}
+	}
+
+	/// Returns a reference to the encoder.
+	pub fn encoder(&self) -> &OpusEncoder {
+		&self.encoder
+	}
+
+	/// Returns a mutable reference to the encoder, for changing its settings
+	/// mid-stream.
+	pub fn encoder_mut(&mut self) -> &mut OpusEncoder {
+		&mut self.encoder
+	}
+
+	/// Returns a reference to the inner writer.
+	pub fn get_ref(&self) -> &W {
+		&self.inner
+	}
+
+	/// Returns a mutable reference to the inner writer.
+	///
+	/// Writing to it directly will corrupt the packet stream.
+	pub fn get_mut(&mut self) -> &mut W {
+		&mut self.inner
+	}
+
+	/// Encodes any buffered audio, and returns the inner writer.
+	pub fn finish(mut self) -> Result<W, IoError> {
+		self.flush()?;
+		Ok(self.inner)
+	}
+
+	fn frame_len(&self) -> usize {
+		self.frame_size * self.encoder.channels()
+	}
+
+	fn push_sample(&mut self, bytes: [u8; 2]) -> Result<(), IoError> {
+		self.pcm.push(i16::from_le_bytes(bytes));
+		if self.pcm.len() == self.frame_len() {
+			self.write_frame()?;
+		}
+		Ok(())
+	}
+
+	fn write_frame(&mut self) -> Result<(), IoError> {
+		let packet = self.encoder.encode(&self.pcm, self.frame_size);
+		self.pcm.clear();
+		let packet = packet?;
+		self.inner.write_all(&(packet.len() as u32).to_be_bytes())?;
+		self.inner.write_all(&packet)
+	}
+}
+
+impl<W: Write> Write for OpusEncoderWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> Result<usize, IoError> {
+		if let Some(err) = self.error.take() {
+			return Err(err);
+		}
+		let mut bytes = buf;
+		if let Some(low) = self.partial_sample.take() {
+			match bytes.split_first() {
+				Some((&high, rest)) => {
+					bytes = rest;
+					if let Err(err) = self.push_sample([low, high]) {
+						self.error = Some(err);
+						return Ok(1);
+					}
+				}
+				None => {
+					self.partial_sample = Some(low);
+					return Ok(0);
+				}
+			}
+		}
+		let mut samples = bytes.chunks_exact(2);
+		for (index, sample) in (&mut samples).enumerate() {
+			// The sample has been buffered even if encoding fails, so it counts
+			// as written, and the error is kept for the next call.
+			if let Err(err) = self.push_sample([sample[0], sample[1]]) {
+				self.error = Some(err);
+				return Ok(buf.len() - bytes.len() + (index + 1) * 2);
+			}
+		}
+		self.partial_sample = samples.remainder().first().copied();
+		Ok(buf.len())
+	}
+
+	/// Encodes any partial frame that has been buffered, padding it out to a
+	/// full frame with silence, and then flushes the inner writer.
+	///
+	/// Returns [`IoErrorKind::InvalidData`] if an odd number of bytes has been
+	/// written, as the last sample is then incomplete.
+	fn flush(&mut self) -> Result<(), IoError> {
+		if let Some(err) = self.error.take() {
+			return Err(err);
+		}
+		if self.partial_sample.is_some() {
+			return Err(IoError::new(
+				IoErrorKind::InvalidData,
+				"incomplete sample at end of PCM data",
+			));
+		}
+		if !self.pcm.is_empty() {
+			let frame_len = self.frame_len();
+			self.pcm.resize(frame_len, 0);
+			self.write_frame()?;
+		}
+		self.inner.flush()
+	}
+}
@@ -1,37 +1,61 @@
 // SPDX-License-Identifier: MPL-2.0
+use alloc::{borrow::ToOwned, string::String};
+use core::{
+	ffi::CStr,
+	fmt::{Debug, Display, Error as FmtError, Formatter},
+};
 use meowlouder_opus_sys::{
 	opus_strerror, OPUS_ALLOC_FAIL, OPUS_BAD_ARG, OPUS_BUFFER_TOO_SMALL, OPUS_INTERNAL_ERROR,
 	OPUS_INVALID_PACKET, OPUS_INVALID_STATE, OPUS_UNIMPLEMENTED,
 };
-use std::{
-	ffi::CStr,
-	fmt::{Display, Error as FmtError, Formatter},
-};
+#[cfg(feature = "std")]
+use std::io::{Error as IoError, ErrorKind as IoErrorKind};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum OpusErrorCode {
-	BadArg = OPUS_BAD_ARG,
-	BufferTooSmall = OPUS_BUFFER_TOO_SMALL,
-	InternalError = OPUS_INTERNAL_ERROR,
-	InvalidPacket = OPUS_INVALID_PACKET,
-	Unimplemented = OPUS_UNIMPLEMENTED,
-	InvalidState = OPUS_INVALID_STATE,
-	AllocFail = OPUS_ALLOC_FAIL,
+	BadArg,
+	BufferTooSmall,
+	InternalError,
+	InvalidPacket,
+	Unimplemented,
+	InvalidState,
+	AllocFail,
+	/// An error code unknown to these bindings, such as one added by a newer
+	/// version of libopus.
+	Unknown(i32),
 }
 
 impl OpusErrorCode {
 	pub fn description(self) -> &'static str {
-		// SAFETY: All possible values for [OpusErrorCode] are valid inputs for
-		// [opus_strerror]
+		if let Self::Unknown(_) = self {
+			return "unknown error";
+		}
+		// SAFETY: All known error codes are valid inputs for [opus_strerror]
 		unsafe {
-			CStr::from_ptr(opus_strerror(self as i32))
+			CStr::from_ptr(opus_strerror(self.code()))
 				.to_str()
 				.unwrap_unchecked()
 		}
 	}
 
-	pub(crate) fn from_errno(errno: i32) -> Self {
+	/// Returns the libopus error code.
+	pub fn code(self) -> i32 {
+		match self {
+			Self::BadArg => OPUS_BAD_ARG,
+			Self::BufferTooSmall => OPUS_BUFFER_TOO_SMALL,
+			Self::InternalError => OPUS_INTERNAL_ERROR,
+			Self::InvalidPacket => OPUS_INVALID_PACKET,
+			Self::Unimplemented => OPUS_UNIMPLEMENTED,
+			Self::InvalidState => OPUS_INVALID_STATE,
+			Self::AllocFail => OPUS_ALLOC_FAIL,
+			Self::Unknown(errno) => errno,
+		}
+	}
+
+	/// Converts a libopus error code, falling back to
+	/// [`OpusErrorCode::Unknown`] for any code that isn't known.
+	pub fn from_errno(errno: i32) -> Self {
 		match errno {
 			OPUS_BAD_ARG => Self::BadArg,
 			OPUS_BUFFER_TOO_SMALL => Self::BufferTooSmall,
@@ -40,19 +64,243 @@ impl OpusErrorCode {
 			OPUS_UNIMPLEMENTED => Self::Unimplemented,
 			OPUS_INVALID_STATE => Self::InvalidState,
 			OPUS_ALLOC_FAIL => Self::AllocFail,
-			_ => unreachable!("invalid libopus error code ({errno})"),
+			_ => Self::Unknown(errno),
 		}
 	}
 }
 
 impl Display for OpusErrorCode {
 	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
-		write!(f, "{}", self.description())
+		match self {
+			Self::Unknown(errno) => write!(f, "{} ({errno})", self.description()),
+			_ => write!(f, "{}", self.description()),
+		}
 	}
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for OpusErrorCode {}
 
+#[cfg(feature = "std")]
+impl From<OpusErrorCode> for IoError {
+	fn from(value: OpusErrorCode) -> Self {
+		let kind = match value {
+			OpusErrorCode::BadArg => IoErrorKind::InvalidInput,
+			OpusErrorCode::BufferTooSmall => IoErrorKind::WriteZero,
+			OpusErrorCode::InvalidPacket => IoErrorKind::InvalidData,
+			_ => IoErrorKind::Other,
+		};
+		IoError::new(kind, value)
+	}
+}
+
+/// A libopus error, along with the operation that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OpusError {
+	code: OpusErrorCode,
+	operation: Operation,
+	/// The required and provided lengths of the buffer, for a
+	/// [`OpusErrorCode::BufferTooSmall`] error found before calling libopus.
+	buffer_lengths: Option<(usize, usize)>,
+}
+
+impl OpusError {
+	pub fn new(code: OpusErrorCode, operation: Operation) -> Self {
+		Self {
+			code,
+			operation,
+			buffer_lengths: None,
+		}
+	}
+
+	/// Creates a [`OpusErrorCode::BufferTooSmall`] error for a buffer with
+	/// room for `provided` elements, when it needed room for `required`.
+	pub fn buffer_too_small(operation: Operation, required: usize, provided: usize) -> Self {
+		Self {
+			code: OpusErrorCode::BufferTooSmall,
+			operation,
+			buffer_lengths: Some((required, provided)),
+		}
+	}
+
+	/// Returns the error code libopus failed with.
+	pub fn code(self) -> OpusErrorCode {
+		self.code
+	}
+
+	/// Returns the operation that failed.
+	pub fn operation(self) -> Operation {
+		self.operation
+	}
+
+	/// Returns the length the buffer needed to be, if this is a buffer too
+	/// small error with known lengths.
+	pub fn required(self) -> Option<usize> {
+		self.buffer_lengths.map(|(required, _)| required)
+	}
+
+	/// Returns the length of the buffer that was too small, if this is a buffer
+	/// too small error with known lengths.
+	pub fn provided(self) -> Option<usize> {
+		self.buffer_lengths.map(|(_, provided)| provided)
+	}
+}
+
+impl Display for OpusError {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		write!(f, "failed to {}: {}", self.operation, self.code)?;
+		if let Some((required, provided)) = self.buffer_lengths {
+			write!(f, " (required {required}, provided {provided})")?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OpusError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		Some(&self.code)
+	}
+}
+
+impl PartialEq<OpusErrorCode> for OpusError {
+	fn eq(&self, other: &OpusErrorCode) -> bool {
+		self.code == *other
+	}
+}
+
+#[cfg(feature = "std")]
+impl From<OpusError> for IoError {
+	fn from(value: OpusError) -> Self {
+		IoError::new(IoError::from(value.code).kind(), value)
+	}
+}
+
+/// The operation an [`OpusError`] occurred during.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+	/// Initializing an encoder.
+	EncoderInit,
+	/// Encoding a frame.
+	Encode,
+	/// Initializing a decoder.
+	DecoderInit,
+	/// Decoding or concealing a packet.
+	Decode,
+	/// Getting or setting a codec parameter, described by the string (such as
+	/// `"set bitrate"`).
+	Ctl(&'static str),
+	/// Creating an Opus Custom mode.
+	ModeInit,
+	/// Parsing a packet, or reading information from it.
+	ParsePacket,
+	/// Padding a packet.
+	PadPacket,
+	/// Removing the padding from a packet.
+	UnpadPacket,
+	/// Merging, splitting, or re-framing packets.
+	Repacketize,
+	/// Parsing the Deep REDundancy (DRED) data in a packet.
+	ParseDred,
+	/// Soft clipping audio.
+	SoftClip,
+}
+
+impl Display for Operation {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.write_str(match self {
+			Self::EncoderInit => "initialize encoder",
+			Self::Encode => "encode",
+			Self::DecoderInit => "initialize decoder",
+			Self::Decode => "decode",
+			Self::Ctl(name) => name,
+			Self::ModeInit => "create custom mode",
+			Self::ParsePacket => "parse packet",
+			Self::PadPacket => "pad packet",
+			Self::UnpadPacket => "unpad packet",
+			Self::Repacketize => "repacketize",
+			Self::ParseDred => "parse DRED data",
+			Self::SoftClip => "soft clip",
+		})
+	}
+}
+
+pub type OpusResult<T> = Result<T, OpusError>;
+
+/// An error from parsing one of the public enums by its lowercase name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseNameError {
+	kind: &'static str,
+	input: String,
+	expected: &'static [&'static str],
+}
+
+impl ParseNameError {
+	pub(crate) fn new(kind: &'static str, input: &str, expected: &'static [&'static str]) -> Self {
+		Self {
+			kind,
+			input: input.to_owned(),
+			expected,
+		}
+	}
+
+	/// Returns the string that failed to parse.
+	pub fn input(&self) -> &str {
+		&self.input
+	}
+
+	/// Returns the names that would have been accepted.
+	pub fn expected(&self) -> &'static [&'static str] {
+		self.expected
+	}
+}
+
+impl Display for ParseNameError {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		write!(
+			f,
+			"unknown {} {:?}, expected one of: {}",
+			self.kind,
+			self.input,
+			self.expected.join(", ")
+		)
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseNameError {}
+
+#[cfg(feature = "std")]
+impl From<ParseNameError> for IoError {
+	fn from(value: ParseNameError) -> Self {
+		IoError::new(IoErrorKind::InvalidInput, value)
+	}
+}
+
+/// Attaches the failed operation to a bare [`OpusErrorCode`].
+pub(crate) trait ErrorContext<T> {
+	fn context(self, operation: Operation) -> OpusResult<T>;
+}
+
+impl<T> ErrorContext<T> for Result<T, OpusErrorCode> {
+	fn context(self, operation: Operation) -> OpusResult<T> {
+		self.map_err(|code| OpusError::new(code, operation))
+	}
+}
+
+/// Formats the result of a CTL call, falling back to `<error>` if it failed.
+pub(crate) struct DebugCtl<T>(pub(crate) OpusResult<T>);
+
+impl<T: Debug> Debug for DebugCtl<T> {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		match &self.0 {
+			Ok(value) => value.fmt(f),
+			Err(_) => f.write_str("<error>"),
+		}
+	}
+}
+
 #[macro_export]
 macro_rules! map_error {
 	($x:expr) => {{
@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Checked conversions for the lengths passed to libopus, which takes them as
+//! `int`s.
+use crate::error::{Operation, OpusError, OpusErrorCode, OpusResult};
+
+/// Converts a length to the `int` libopus takes, returning
+/// [`OpusErrorCode::BadArg`] if it doesn't fit.
+pub(crate) fn c_len(len: usize, operation: Operation) -> OpusResult<i32> {
+	i32::try_from(len).map_err(|_| OpusError::new(OpusErrorCode::BadArg, operation))
+}
+
+/// Converts the length of an output buffer to the `int` libopus takes,
+/// capping it at the largest `int`, as a buffer with more room than that is
+/// still large enough.
+pub(crate) fn c_capacity(len: usize) -> i32 {
+	len.min(i32::MAX as usize) as i32
+}
+
+/// Returns the number of interleaved samples in `frame_size` samples for each
+/// of `channels` channels, or [`OpusErrorCode::BadArg`] if it overflows.
+pub(crate) fn pcm_len(
+	frame_size: usize,
+	channels: usize,
+	operation: Operation,
+) -> OpusResult<usize> {
+	frame_size
+		.checked_mul(channels)
+		.ok_or(OpusError::new(OpusErrorCode::BadArg, operation))
+}
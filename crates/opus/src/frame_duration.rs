@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::OpusErrorCode;
+use meowlouder_opus_sys::{
+	OPUS_FRAMESIZE_100_MS, OPUS_FRAMESIZE_10_MS, OPUS_FRAMESIZE_120_MS, OPUS_FRAMESIZE_20_MS,
+	OPUS_FRAMESIZE_2_5_MS, OPUS_FRAMESIZE_40_MS, OPUS_FRAMESIZE_5_MS, OPUS_FRAMESIZE_60_MS,
+	OPUS_FRAMESIZE_80_MS,
+};
+
+/// The duration of a single Opus frame, or of a packet made up of several.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FrameDuration {
+	/// 2.5 ms.
+	Ms2_5,
+	/// 5 ms.
+	Ms5,
+	/// 10 ms.
+	Ms10,
+	/// 20 ms.
+	Ms20,
+	/// 40 ms.
+	Ms40,
+	/// 60 ms.
+	Ms60,
+	/// 80 ms.
+	Ms80,
+	/// 100 ms.
+	Ms100,
+	/// 120 ms, the longest duration a single packet can hold.
+	Ms120,
+}
+
+/// The number of samples (per channel) in each [`FrameDuration`] at 48 kHz,
+/// indexed by variant.
+const SAMPLES_48K: [usize; 9] = [120, 240, 480, 960, 1920, 2880, 3840, 4800, 5760];
+
+impl FrameDuration {
+	/// Returns the number of samples (per channel) in this duration, at 48 kHz.
+	pub(crate) const fn samples_48k(self) -> usize {
+		SAMPLES_48K[self as usize]
+	}
+
+	/// Returns the number of samples (per channel) in this duration, at the
+	/// given sampling rate.
+	///
+	/// This is exact for all of the sampling rates Opus supports. Any other
+	/// sampling rate is rounded down to a whole number of samples.
+	pub const fn samples_at(self, sample_rate: u32) -> usize {
+		self.samples_48k() * sample_rate as usize / 48000
+	}
+}
+
+impl From<FrameDuration> for i32 {
+	fn from(value: FrameDuration) -> Self {
+		(OPUS_FRAMESIZE_2_5_MS + value as u32) as i32
+	}
+}
+
+impl TryFrom<i32> for FrameDuration {
+	type Error = OpusErrorCode;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value as u32 {
+			OPUS_FRAMESIZE_2_5_MS => Ok(Self::Ms2_5),
+			OPUS_FRAMESIZE_5_MS => Ok(Self::Ms5),
+			OPUS_FRAMESIZE_10_MS => Ok(Self::Ms10),
+			OPUS_FRAMESIZE_20_MS => Ok(Self::Ms20),
+			OPUS_FRAMESIZE_40_MS => Ok(Self::Ms40),
+			OPUS_FRAMESIZE_60_MS => Ok(Self::Ms60),
+			OPUS_FRAMESIZE_80_MS => Ok(Self::Ms80),
+			OPUS_FRAMESIZE_100_MS => Ok(Self::Ms100),
+			OPUS_FRAMESIZE_120_MS => Ok(Self::Ms120),
+			_ => Err(OpusErrorCode::BadArg),
+		}
+	}
+}
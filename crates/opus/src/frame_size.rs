@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{error::OpusErrorCode, frame_duration::FrameDuration, sample_rate::SampleRate};
+
+/// A frame size, in samples per channel.
+///
+/// The encoding and decoding functions take anything convertible into this,
+/// so a bare `usize` works as before. Converting from a `usize` doesn't
+/// validate it, but the encoding and decoding functions return
+/// [`OpusErrorCode::BadArg`] for a size that isn't one of the legal frame
+/// sizes at their sampling rate; use [`FrameSize::from_samples`] to check a
+/// size up front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FrameSize(usize);
+
+impl FrameSize {
+	/// Returns the frame size of a frame of `duration`, at the given sampling
+	/// rate.
+	pub const fn from_duration(duration: FrameDuration, sample_rate: SampleRate) -> Self {
+		Self(duration.samples_at(sample_rate.as_i32() as u32))
+	}
+
+	/// Returns the frame size of `samples` samples per channel, if that is the
+	/// size of a frame Opus can encode at the given sampling rate, or
+	/// [`OpusErrorCode::BadArg`] otherwise.
+	pub fn from_samples(samples: usize, sample_rate: SampleRate) -> Result<Self, OpusErrorCode> {
+		let frame_size = Self(samples);
+		match frame_size.duration(sample_rate) {
+			Some(_) => Ok(frame_size),
+			None => Err(OpusErrorCode::BadArg),
+		}
+	}
+
+	/// Returns the largest frame size at the given sampling rate, which is
+	/// 120 ms long.
+	pub const fn max_at(sample_rate: SampleRate) -> Self {
+		Self::from_duration(FrameDuration::Ms120, sample_rate)
+	}
+
+	/// Returns the number of samples per channel.
+	pub const fn samples(self) -> usize {
+		self.0
+	}
+
+	/// Returns the duration of a frame of this size at the given sampling
+	/// rate, or `None` if it isn't a size Opus can encode.
+	pub fn duration(self, sample_rate: SampleRate) -> Option<FrameDuration> {
+		[
+			FrameDuration::Ms2_5,
+			FrameDuration::Ms5,
+			FrameDuration::Ms10,
+			FrameDuration::Ms20,
+			FrameDuration::Ms40,
+			FrameDuration::Ms60,
+			FrameDuration::Ms80,
+			FrameDuration::Ms100,
+			FrameDuration::Ms120,
+		]
+		.into_iter()
+		.find(|&duration| Self::from_duration(duration, sample_rate) == self)
+	}
+}
+
+impl From<usize> for FrameSize {
+	fn from(value: usize) -> Self {
+		Self(value)
+	}
+}
+
+impl From<FrameSize> for usize {
+	fn from(value: FrameSize) -> Self {
+		value.0
+	}
+}
@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
+#![no_std]
 #![warn(
 	clippy::correctness,
 	clippy::suspicious,
@@ -6,31 +7,75 @@
 	clippy::perf,
 	clippy::style
 )]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 pub mod application;
+pub mod bandwidth;
+pub mod capabilities;
+pub mod channel_order;
+pub mod channels;
+#[cfg(feature = "custom")]
+pub mod custom;
 pub mod decoder;
+#[cfg(feature = "weights-file")]
+mod dnn_blob;
+#[cfg(feature = "dred")]
+pub mod dred;
 pub mod encode;
+mod ffi;
 #[macro_use]
 pub mod error;
+pub mod frame_duration;
+pub mod frame_size;
+mod math;
+pub mod multistream;
+pub mod packet;
+pub mod padder;
+pub mod pool;
+pub mod projection;
+pub mod repacketizer;
+pub mod sample_rate;
+#[cfg(feature = "serde")]
+mod serde_impls;
+pub mod signal;
+pub mod soft_clip;
+mod state;
+pub mod toc;
+pub mod util;
+pub mod version;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
 
 pub use crate::{
 	application::OpusApplication,
-	decoder::OpusDecoder,
-	encode::{OpusEncodable, OpusEncoder},
+	bandwidth::OpusBandwidth,
+	capabilities::{capabilities, has_deep_plc, has_dred, Capabilities},
+	channels::Channels,
+	decoder::{DecodeOutcome, DecodedFrame, OpusDecodable, OpusDecoder},
+	encode::{
+		AdaptiveBitrateController, EncodedFrame, OpusEncodable, OpusEncoder, OpusEncoderConfig,
+		OpusEncoderPreset,
+	},
+	frame_duration::FrameDuration,
+	frame_size::FrameSize,
+	multistream::{
+		ChannelLayout, ChannelMapping, OpusMSDecoder, OpusMSEncoder, OpusMSStreamEncoder,
+	},
+	packet::{OpusPacket, OpusPacketRef},
+	padder::PacketPadder,
+	pool::{BufferPool, PooledPacket},
+	projection::{OpusProjectionDecoder, OpusProjectionEncoder},
+	repacketizer::OpusRepacketizer,
+	sample_rate::SampleRate,
+	signal::OpusSignal,
+	soft_clip::OpusSoftClip,
+	toc::{OpusMode, OpusToc},
+	version::{libopus_version, libopus_version_info, LibopusVersion},
 };
 
-/// Returns the libopus version string.
-///
-/// Applications may look for the substring "-fixed" in the version string to
-/// determine whether they have a fixed-point or floating-point build at
-/// runtime.
-pub fn libopus_version() -> &'static str {
-	use meowlouder_opus_sys::opus_get_version_string;
-	use std::ffi::CStr;
-
-	// SAFETY: the libopus version string is guaranteed to be a valid string.
-	unsafe {
-		CStr::from_ptr(opus_get_version_string())
-			.to_str()
-			.unwrap_unchecked()
-	}
-}
+#[cfg(feature = "dred")]
+pub use crate::dred::{OpusDred, OpusDredDecoder};
+#[cfg(feature = "std")]
+pub use crate::{decoder::OpusDecoderReader, encode::OpusEncoderWriter};
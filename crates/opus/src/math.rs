@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: MPL-2.0
+//! The float functions that `core` doesn't provide, which come from `libm`
+//! without `std`.
+
+#[cfg(feature = "std")]
+pub(crate) fn roundf(x: f32) -> f32 {
+	x.round()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn roundf(x: f32) -> f32 {
+	libm::roundf(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn round(x: f64) -> f64 {
+	x.round()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn round(x: f64) -> f64 {
+	libm::round(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn log10f(x: f32) -> f32 {
+	x.log10()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn log10f(x: f32) -> f32 {
+	libm::log10f(x)
+}
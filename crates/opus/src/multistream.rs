@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Multistream encoding and decoding, for audio with more than two channels.
+//!
+//! A multistream packet is made up of several Opus streams, each of which is
+//! either mono or a coupled stereo pair. A [`ChannelMapping`] assigns each
+//! channel of the audio to one of the channels of the streams.
+
+mod decoder;
+mod encoder;
+mod mapping;
+mod stream_encoder;
+
+pub use self::{
+	decoder::OpusMSDecoder,
+	encoder::OpusMSEncoder,
+	mapping::{channel_mapping_for_count, ChannelLayout, ChannelMapping, MappingError},
+	stream_encoder::OpusMSStreamEncoder,
+};
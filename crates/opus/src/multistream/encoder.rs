@@ -0,0 +1,382 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	application::OpusApplication,
+	error::{ErrorContext, Operation, OpusError, OpusErrorCode, OpusResult},
+	ffi,
+	multistream::{ChannelMapping, OpusMSStreamEncoder},
+	packet,
+	sample_rate::SampleRate,
+	state::StateBuffer,
+};
+use alloc::{vec, vec::Vec};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+use meowlouder_opus_sys::{
+	opus_multistream_encode, opus_multistream_encode_float, opus_multistream_encoder_ctl,
+	opus_multistream_encoder_get_size, opus_multistream_encoder_init,
+	opus_multistream_surround_encoder_get_size, opus_multistream_surround_encoder_init,
+	OpusEncoder as RawOpusEncoder, OPUS_GET_BITRATE_REQUEST, OPUS_GET_COMPLEXITY_REQUEST,
+	OPUS_MULTISTREAM_GET_ENCODER_STATE_REQUEST, OPUS_RESET_STATE, OPUS_SET_BITRATE_REQUEST,
+	OPUS_SET_COMPLEXITY_REQUEST,
+};
+
+/// The largest packet a single stream can produce (120 ms, coded as six 20 ms
+/// frames), plus the 2 bytes its self-delimiting length may take.
+const MAX_STREAM_DATA_BYTES: usize = packet::max_size_of_frames(6) + 2;
+
+/// An Opus multistream encoder, which encodes interleaved audio with any
+/// number of channels into multistream packets.
+///
+/// Surround audio for mapping family 1 must be in Vorbis channel order, which
+/// audio in the more common SMPTE order can be converted to with
+/// [`channel_order::smpte_to_vorbis`](crate::channel_order::smpte_to_vorbis).
+///
+/// This is [`Send`] but not [`Sync`], like
+/// [`OpusEncoder`](crate::encode::OpusEncoder#threading).
+pub struct OpusMSEncoder {
+	encoder_state: StateBuffer,
+	channels: usize,
+	streams: usize,
+	coupled_streams: usize,
+	_not_sync: PhantomData<*mut ()>,
+}
+
+// SAFETY: The multistream state embeds the state of each stream's encoder
+// inline, after its own header, so it is as self-contained as an
+// `OpusEncoder`'s. The energy mask that surround encoding sets on each stream
+// points to a buffer on the stack of `opus_multistream_encode`, but it is set
+// again before each stream is encoded, so it is never read after that call.
+unsafe impl Send for OpusMSEncoder {}
+
+impl OpusMSEncoder {
+	/// Creates a new multistream encoder, encoding audio with the channels
+	/// and streams described by `mapping`.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if a coupled stream has one of its
+	/// channels unused by the mapping, which libopus doesn't allow when
+	/// encoding.
+	pub fn new(
+		sample_rate: i32,
+		mapping: &ChannelMapping,
+		application: OpusApplication,
+	) -> OpusResult<Self> {
+		SampleRate::try_from(sample_rate).context(Operation::EncoderInit)?;
+		let streams = mapping.streams() as i32;
+		let coupled_streams = mapping.coupled_streams() as i32;
+		let encoder_size =
+			unsafe { opus_multistream_encoder_get_size(streams, coupled_streams) as usize };
+		let mut encoder_state = StateBuffer::new(encoder_size);
+		map_error!(unsafe {
+			opus_multistream_encoder_init(
+				encoder_state.as_mut_ptr().cast(),
+				sample_rate,
+				mapping.channels() as _,
+				streams,
+				coupled_streams,
+				mapping.table().as_ptr(),
+				application.into(),
+			)
+		})
+		.context(Operation::EncoderInit)?;
+		Ok(Self {
+			encoder_state,
+			channels: mapping.channels(),
+			streams: streams as usize,
+			coupled_streams: coupled_streams as usize,
+			_not_sync: PhantomData,
+		})
+	}
+
+	/// Creates a new multistream encoder for a standard channel layout, with
+	/// the streams and mapping picked by libopus.
+	///
+	/// Mapping family 0 supports mono and stereo, and mapping family 1
+	/// supports 1 to 8 channels in Vorbis channel order, up to 7.1 surround
+	/// (see [`channel_order`](crate::channel_order)).
+	/// The encoder is returned along with the mapping it uses, which needs to
+	/// be given to the decoder.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if the mapping family doesn't support
+	/// the number of channels.
+	pub fn new_surround(
+		sample_rate: i32,
+		channels: u8,
+		mapping_family: u8,
+		application: OpusApplication,
+	) -> OpusResult<(Self, ChannelMapping)> {
+		SampleRate::try_from(sample_rate).context(Operation::EncoderInit)?;
+		if channels == 0 {
+			return Err(OpusError::new(
+				OpusErrorCode::BadArg,
+				Operation::EncoderInit,
+			));
+		}
+		let encoder_size = unsafe {
+			opus_multistream_surround_encoder_get_size(channels as _, mapping_family as _) as usize
+		};
+		// libopus returns a size of 0 for unsupported layouts.
+		if encoder_size == 0 {
+			return Err(OpusError::new(
+				OpusErrorCode::BadArg,
+				Operation::EncoderInit,
+			));
+		}
+		let mut encoder_state = StateBuffer::new(encoder_size);
+		let mut streams = 0;
+		let mut coupled_streams = 0;
+		let mut mapping = vec![0; channels as usize];
+		map_error!(unsafe {
+			opus_multistream_surround_encoder_init(
+				encoder_state.as_mut_ptr().cast(),
+				sample_rate,
+				channels as _,
+				mapping_family as _,
+				&mut streams,
+				&mut coupled_streams,
+				mapping.as_mut_ptr(),
+				application.into(),
+			)
+		})
+		.context(Operation::EncoderInit)?;
+		let encoder = Self {
+			encoder_state,
+			channels: channels as usize,
+			streams: streams as usize,
+			coupled_streams: coupled_streams as usize,
+			_not_sync: PhantomData,
+		};
+		let mapping = ChannelMapping::new(
+			mapping_family,
+			streams as u8,
+			coupled_streams as u8,
+			mapping,
+		);
+		Ok((encoder, mapping))
+	}
+
+	/// Creates a new multistream encoder for 5.1 surround in Vorbis channel
+	/// order (front left, center, front right, rear left, rear right, LFE),
+	/// with mapping family 1.
+	///
+	/// This encodes 4 streams, 2 of them coupled, and libopus tunes the
+	/// encoder for surround. The encoder is returned along with the mapping it
+	/// uses, which needs to be given to the decoder.
+	pub fn new_surround_5_1(
+		sample_rate: SampleRate,
+		application: OpusApplication,
+	) -> OpusResult<(Self, ChannelMapping)> {
+		Self::new_surround(sample_rate.as_i32(), 6, 1, application)
+	}
+
+	/// Creates a new multistream encoder for 7.1 surround in Vorbis channel
+	/// order (front left, center, front right, side left, side right, rear
+	/// left, rear right, LFE), with mapping family 1.
+	///
+	/// This encodes 5 streams, 3 of them coupled, and libopus tunes the
+	/// encoder for surround. The encoder is returned along with the mapping it
+	/// uses, which needs to be given to the decoder.
+	pub fn new_surround_7_1(
+		sample_rate: SampleRate,
+		application: OpusApplication,
+	) -> OpusResult<(Self, ChannelMapping)> {
+		Self::new_surround(sample_rate.as_i32(), 8, 1, application)
+	}
+
+	/// Encodes a frame of interleaved audio into `data`, returning the length
+	/// of the packet.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if `pcm` holds less than
+	/// `frame_size` samples for every channel.
+	pub fn encode_into(
+		&mut self,
+		pcm: &[i16],
+		frame_size: usize,
+		data: &mut [u8],
+	) -> OpusResult<usize> {
+		if pcm.len() < ffi::pcm_len(frame_size, self.channels, Operation::Encode)? {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Encode));
+		}
+		map_error!(usize, unsafe {
+			opus_multistream_encode(
+				self.encoder_state.as_mut_ptr().cast(),
+				pcm.as_ptr(),
+				ffi::c_len(frame_size, Operation::Encode)?,
+				data.as_mut_ptr(),
+				ffi::c_capacity(data.len()),
+			)
+		})
+		.context(Operation::Encode)
+	}
+
+	/// Encodes a frame of interleaved audio into a new packet.
+	pub fn encode(&mut self, pcm: &[i16], frame_size: usize) -> OpusResult<Vec<u8>> {
+		let mut data = vec![0; MAX_STREAM_DATA_BYTES * self.streams];
+		let len = self.encode_into(pcm, frame_size, &mut data)?;
+		data.truncate(len);
+		Ok(data)
+	}
+
+	/// Encodes a frame of interleaved float audio into `data`, returning the
+	/// length of the packet.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if `pcm` holds less than
+	/// `frame_size` samples for every channel.
+	pub fn encode_float_into(
+		&mut self,
+		pcm: &[f32],
+		frame_size: usize,
+		data: &mut [u8],
+	) -> OpusResult<usize> {
+		if pcm.len() < ffi::pcm_len(frame_size, self.channels, Operation::Encode)? {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Encode));
+		}
+		map_error!(usize, unsafe {
+			opus_multistream_encode_float(
+				self.encoder_state.as_mut_ptr().cast(),
+				pcm.as_ptr(),
+				ffi::c_len(frame_size, Operation::Encode)?,
+				data.as_mut_ptr(),
+				ffi::c_capacity(data.len()),
+			)
+		})
+		.context(Operation::Encode)
+	}
+
+	/// Encodes a frame of interleaved float audio into a new packet.
+	pub fn encode_float(&mut self, pcm: &[f32], frame_size: usize) -> OpusResult<Vec<u8>> {
+		let mut data = vec![0; MAX_STREAM_DATA_BYTES * self.streams];
+		let len = self.encode_float_into(pcm, frame_size, &mut data)?;
+		data.truncate(len);
+		Ok(data)
+	}
+
+	/// Resets the codec state to be equivalent to a freshly initialized state.
+	pub fn reset(&mut self) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_multistream_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_RESET_STATE as _,
+			)
+		})
+		.context(Operation::Ctl("reset state"))
+	}
+
+	/// Returns the number of input channels the encoder was initialized with.
+	pub fn channels(&self) -> usize {
+		self.channels
+	}
+
+	/// Returns the total number of streams the encoder encodes.
+	pub fn streams(&self) -> usize {
+		self.streams
+	}
+
+	/// Returns the number of coupled (stereo) streams the encoder encodes.
+	pub fn coupled_streams(&self) -> usize {
+		self.coupled_streams
+	}
+
+	/// Returns the encoder of one of the streams, to configure it separately
+	/// from the others.
+	///
+	/// The coupled streams come first, followed by the mono streams. Returns
+	/// [`OpusErrorCode::BadArg`] if there is no stream `index`.
+	pub fn stream_encoder(&mut self, index: usize) -> OpusResult<OpusMSStreamEncoder<'_>> {
+		if index >= self.streams {
+			return Err(OpusError::new(
+				OpusErrorCode::BadArg,
+				Operation::Ctl("get stream encoder"),
+			));
+		}
+		let mut state: *mut RawOpusEncoder = core::ptr::null_mut();
+		map_error!(unsafe {
+			opus_multistream_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_MULTISTREAM_GET_ENCODER_STATE_REQUEST as _,
+				index as i32,
+				&mut state,
+			)
+		})
+		.context(Operation::Ctl("get stream encoder"))?;
+		// SAFETY: libopus returned the state of stream `index`, which lives
+		// within our state, and we're mutably borrowed for the lifetime of the
+		// handle.
+		Ok(unsafe { OpusMSStreamEncoder::new(state, index) })
+	}
+
+	/// Returns the total bitrate of all of the streams, in bits per second.
+	///
+	/// libopus only splits the bitrate set with
+	/// [`OpusMSEncoder::set_bitrate`] between the streams when encoding, so
+	/// this reflects the new bitrate after the next frame is encoded.
+	pub fn bitrate(&mut self) -> OpusResult<i32> {
+		let mut bitrate = 0;
+		map_error!(&bitrate, unsafe {
+			opus_multistream_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_BITRATE_REQUEST as _,
+				&mut bitrate,
+			)
+		})
+		.context(Operation::Ctl("get bitrate"))
+	}
+
+	/// Configures the total bitrate of the encoder, which is split between the
+	/// streams.
+	///
+	/// `bitrate` is in bits per second, with
+	/// [`OPUS_AUTO`](meowlouder_opus_sys::OPUS_AUTO) and
+	/// [`OPUS_BITRATE_MAX`](meowlouder_opus_sys::OPUS_BITRATE_MAX) behaving as
+	/// with [`OpusEncoder::set_bitrate`](crate::OpusEncoder::set_bitrate).
+	pub fn set_bitrate(&mut self, bitrate: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_multistream_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_BITRATE_REQUEST as _,
+				bitrate,
+			)
+		})
+		.context(Operation::Ctl("set bitrate"))
+	}
+
+	/// Returns the encoder's configured computational complexity, in the range
+	/// of 0-10, inclusive.
+	pub fn complexity(&mut self) -> OpusResult<i32> {
+		let mut complexity = 0;
+		map_error!(&complexity, unsafe {
+			opus_multistream_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_COMPLEXITY_REQUEST as _,
+				&mut complexity,
+			)
+		})
+		.context(Operation::Ctl("get complexity"))
+	}
+
+	/// Configures the computational complexity of every stream's encoder.
+	///
+	/// `complexity` is in the range of 0-10, inclusive, with 10 representing
+	/// the highest complexity.
+	pub fn set_complexity(&mut self, complexity: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_multistream_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_COMPLEXITY_REQUEST as _,
+				complexity,
+			)
+		})
+		.context(Operation::Ctl("set complexity"))
+	}
+}
+
+impl Debug for OpusMSEncoder {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusMSEncoder")
+			.field("channels", &self.channels)
+			.field("streams", &self.streams)
+			.field("coupled_streams", &self.coupled_streams)
+			.finish_non_exhaustive()
+	}
+}
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::OpusErrorCode;
+use alloc::{vec, vec::Vec};
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+/// A coded channel index which leaves the channel unused: silent when
+/// decoding, and ignored when encoding.
+const UNUSED_CHANNEL: u8 = 255;
+
+/// A standard channel layout supported by mapping family 1, with channels in
+/// Vorbis order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelLayout {
+	/// Mono.
+	Mono,
+	/// Left, right.
+	Stereo,
+	/// Left, center, right.
+	Linear,
+	/// Front left, front right, rear left, rear right.
+	Quadraphonic,
+	/// Front left, center, front right, rear left, rear right.
+	Surround5_0,
+	/// Front left, center, front right, rear left, rear right, LFE.
+	Surround5_1,
+	/// Front left, center, front right, side left, side right, rear center,
+	/// LFE.
+	Surround6_1,
+	/// Front left, center, front right, side left, side right, rear left,
+	/// rear right, LFE.
+	Surround7_1,
+}
+
+impl ChannelLayout {
+	/// Returns the number of channels in the layout.
+	pub const fn channels(self) -> u8 {
+		self as u8 + 1
+	}
+}
+
+/// How the channels of multistream audio are assigned to its streams.
+///
+/// The coded channels are numbered with the coupled (stereo) streams first,
+/// taking two channels each, followed by the mono streams. So coded channel
+/// `i` belongs to stream `i / 2` if `i < 2 * coupled_streams`, or to stream
+/// `i - coupled_streams` otherwise. The table then gives the coded channel
+/// for each channel of the audio, or 255 if the channel is unused.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChannelMapping {
+	family: u8,
+	streams: u8,
+	coupled_streams: u8,
+	table: Vec<u8>,
+}
+
+impl ChannelMapping {
+	/// Creates a mapping family 0 mapping, for mono or stereo audio in a
+	/// single stream.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if `channels` isn't 1 or 2.
+	pub fn family0(channels: u8) -> Result<Self, OpusErrorCode> {
+		match channels {
+			1 => Ok(Self::new(0, 1, 0, vec![0])),
+			2 => Ok(Self::new(0, 1, 1, vec![0, 1])),
+			_ => Err(OpusErrorCode::BadArg),
+		}
+	}
+
+	/// Creates a mapping family 1 mapping for a standard layout, as specified
+	/// by [RFC 7845](https://www.rfc-editor.org/rfc/rfc7845#section-5.1.1.2).
+	pub fn family1(layout: ChannelLayout) -> Self {
+		let (streams, coupled_streams, table): (u8, u8, &[u8]) = match layout {
+			ChannelLayout::Mono => (1, 0, &[0]),
+			ChannelLayout::Stereo => (1, 1, &[0, 1]),
+			ChannelLayout::Linear => (2, 1, &[0, 2, 1]),
+			ChannelLayout::Quadraphonic => (2, 2, &[0, 1, 2, 3]),
+			ChannelLayout::Surround5_0 => (3, 2, &[0, 4, 1, 2, 3]),
+			ChannelLayout::Surround5_1 => (4, 2, &[0, 4, 1, 2, 3, 5]),
+			ChannelLayout::Surround6_1 => (4, 3, &[0, 4, 1, 2, 3, 5, 6]),
+			ChannelLayout::Surround7_1 => (5, 3, &[0, 6, 1, 2, 3, 4, 5, 7]),
+		};
+		Self::new(1, streams, coupled_streams, table.to_vec())
+	}
+
+	/// Creates a custom (mapping family 255) mapping.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if there are no streams, if there
+	/// are more coupled streams than streams, if there are more than 255
+	/// coded channels, if the table is empty or has more than 255 channels,
+	/// or if the table references a coded channel that doesn't exist.
+	pub fn custom(streams: u8, coupled_streams: u8, table: &[u8]) -> Result<Self, OpusErrorCode> {
+		let coded_channels = streams as usize + coupled_streams as usize;
+		if streams == 0
+			|| coupled_streams > streams
+			|| coded_channels > 255
+			|| table.is_empty()
+			|| table.len() > 255
+			|| table
+				.iter()
+				.any(|&channel| channel != UNUSED_CHANNEL && channel as usize >= coded_channels)
+		{
+			return Err(OpusErrorCode::BadArg);
+		}
+		Ok(Self::new(255, streams, coupled_streams, table.to_vec()))
+	}
+
+	pub(crate) fn new(family: u8, streams: u8, coupled_streams: u8, table: Vec<u8>) -> Self {
+		Self {
+			family,
+			streams,
+			coupled_streams,
+			table,
+		}
+	}
+
+	/// Returns the mapping family, as written in the header of an Ogg Opus
+	/// stream.
+	pub fn family(&self) -> u8 {
+		self.family
+	}
+
+	/// Returns the number of channels of audio.
+	pub fn channels(&self) -> usize {
+		self.table.len()
+	}
+
+	/// Returns the total number of streams.
+	pub fn streams(&self) -> u8 {
+		self.streams
+	}
+
+	/// Returns the number of coupled (stereo) streams.
+	pub fn coupled_streams(&self) -> u8 {
+		self.coupled_streams
+	}
+
+	/// Returns the coded channel for each channel of audio.
+	pub fn table(&self) -> &[u8] {
+		&self.table
+	}
+}
+
+/// Returns the recommended mapping for audio with `channels` channels in
+/// Vorbis order: the mapping family 1 mapping for the standard layout with
+/// that many channels, as in [`ChannelMapping::family1`].
+///
+/// Returns [`MappingError::UnsupportedChannelCount`] for anything but 1 to 8
+/// channels.
+pub fn channel_mapping_for_count(channels: u8) -> Result<ChannelMapping, MappingError> {
+	let layout = match channels {
+		1 => ChannelLayout::Mono,
+		2 => ChannelLayout::Stereo,
+		3 => ChannelLayout::Linear,
+		4 => ChannelLayout::Quadraphonic,
+		5 => ChannelLayout::Surround5_0,
+		6 => ChannelLayout::Surround5_1,
+		7 => ChannelLayout::Surround6_1,
+		8 => ChannelLayout::Surround7_1,
+		_ => return Err(MappingError::UnsupportedChannelCount(channels)),
+	};
+	Ok(ChannelMapping::family1(layout))
+}
+
+/// An error from picking a [`ChannelMapping`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MappingError {
+	/// There is no standard layout with this many channels.
+	UnsupportedChannelCount(u8),
+}
+
+impl Display for MappingError {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		match self {
+			Self::UnsupportedChannelCount(channels) => write!(
+				f,
+				"no standard channel mapping for {channels} channels, expected 1 to 8"
+			),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MappingError {}
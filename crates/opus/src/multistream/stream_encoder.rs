@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	bandwidth::OpusBandwidth,
+	error::{ErrorContext, Operation, OpusResult},
+	multistream::OpusMSEncoder,
+	signal::OpusSignal,
+};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+use meowlouder_opus_sys::{
+	opus_encoder_ctl, OpusEncoder as RawOpusEncoder, OPUS_AUTO, OPUS_GET_BANDWIDTH_REQUEST,
+	OPUS_GET_BITRATE_REQUEST, OPUS_GET_SIGNAL_REQUEST, OPUS_SET_BANDWIDTH_REQUEST,
+	OPUS_SET_BITRATE_REQUEST, OPUS_SET_SIGNAL_REQUEST,
+};
+
+/// The encoder of a single stream within an [`OpusMSEncoder`], from
+/// [`OpusMSEncoder::stream_encoder`].
+///
+/// This mutably borrows the multistream encoder, so it can't be used to encode
+/// while the stream's encoder is being configured.
+///
+/// Note that the multistream encoder overrides some settings of its streams
+/// on every frame it encodes: the bitrate of every stream is recomputed from
+/// the total bitrate, and with a surround mapping, so is the bandwidth.
+pub struct OpusMSStreamEncoder<'a> {
+	state: *mut RawOpusEncoder,
+	stream: usize,
+	_parent: PhantomData<&'a mut OpusMSEncoder>,
+}
+
+impl<'a> OpusMSStreamEncoder<'a> {
+	/// Creates a handle to a stream's encoder.
+	///
+	/// # Safety
+	///
+	/// `state` must point to the encoder of stream `stream` within the state
+	/// of an encoder which is mutably borrowed for `'a`.
+	pub(crate) unsafe fn new(state: *mut RawOpusEncoder, stream: usize) -> Self {
+		Self {
+			state,
+			stream,
+			_parent: PhantomData,
+		}
+	}
+
+	/// Returns the index of the stream within the multistream encoder.
+	pub fn stream(&self) -> usize {
+		self.stream
+	}
+
+	/// Returns the stream's bitrate, in bits per second.
+	pub fn bitrate(&mut self) -> OpusResult<i32> {
+		let mut bitrate = 0;
+		map_error!(&bitrate, unsafe {
+			opus_encoder_ctl(self.state, OPUS_GET_BITRATE_REQUEST as _, &mut bitrate)
+		})
+		.context(Operation::Ctl("get bitrate"))
+	}
+
+	/// Configures the stream's bitrate, as with
+	/// [`OpusEncoder::set_bitrate`](crate::OpusEncoder::set_bitrate).
+	///
+	/// This only lasts until the next frame is encoded, when the multistream
+	/// encoder splits its total bitrate between the streams again.
+	pub fn set_bitrate(&mut self, bitrate: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(self.state, OPUS_SET_BITRATE_REQUEST as _, bitrate)
+		})
+		.context(Operation::Ctl("set bitrate"))
+	}
+
+	/// Returns the stream's configured bandpass.
+	pub fn bandwidth(&mut self) -> OpusResult<i32> {
+		let mut bandwidth = 0;
+		map_error!(&bandwidth, unsafe {
+			opus_encoder_ctl(self.state, OPUS_GET_BANDWIDTH_REQUEST as _, &mut bandwidth)
+		})
+		.context(Operation::Ctl("get bandwidth"))
+	}
+
+	/// Sets the stream's bandpass to a specific value, or lets the encoder
+	/// pick it automatically if `None`.
+	///
+	/// With a surround mapping, this only lasts until the next frame is
+	/// encoded, when the multistream encoder picks the bandwidth itself.
+	pub fn set_bandwidth(&mut self, bandwidth: Option<OpusBandwidth>) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.state,
+				OPUS_SET_BANDWIDTH_REQUEST as _,
+				bandwidth.map_or(OPUS_AUTO, i32::from),
+			)
+		})
+		.context(Operation::Ctl("set bandwidth"))
+	}
+
+	/// Returns the stream's configured signal type, or `None` if the encoder
+	/// detects it automatically (default: `None`).
+	pub fn signal(&mut self) -> OpusResult<Option<OpusSignal>> {
+		let mut signal = 0;
+		map_error!(&signal, unsafe {
+			opus_encoder_ctl(self.state, OPUS_GET_SIGNAL_REQUEST as _, &mut signal)
+		})
+		.and_then(|signal| match signal {
+			OPUS_AUTO => Ok(None),
+			signal => OpusSignal::try_from(signal).map(Some),
+		})
+		.context(Operation::Ctl("get signal"))
+	}
+
+	/// Configures the type of signal in the stream.
+	/// This is a hint which helps the encoder's mode selection, with `None`
+	/// letting the encoder detect it automatically.
+	pub fn set_signal(&mut self, signal: Option<OpusSignal>) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_encoder_ctl(
+				self.state,
+				OPUS_SET_SIGNAL_REQUEST as _,
+				signal.map_or(OPUS_AUTO, i32::from),
+			)
+		})
+		.context(Operation::Ctl("set signal"))
+	}
+}
+
+impl Debug for OpusMSStreamEncoder<'_> {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusMSStreamEncoder")
+			.field("stream", &self.stream)
+			.finish_non_exhaustive()
+	}
+}
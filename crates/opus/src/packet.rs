@@ -0,0 +1,686 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Functions for inspecting and manipulating Opus packets without decoding
+//! them.
+use crate::{
+	bandwidth::OpusBandwidth,
+	error::{ErrorContext, Operation, OpusError, OpusErrorCode, OpusResult},
+	ffi,
+	frame_duration::FrameDuration,
+	frame_size::FrameSize,
+	repacketizer::OpusRepacketizer,
+	sample_rate::SampleRate,
+	toc::{OpusMode, OpusToc},
+};
+use alloc::{vec, vec::Vec};
+use core::{ops::Deref, ptr};
+use meowlouder_opus_sys::{
+	opus_multistream_packet_pad, opus_multistream_packet_unpad, opus_packet_get_bandwidth,
+	opus_packet_get_nb_channels, opus_packet_get_nb_frames, opus_packet_get_nb_samples,
+	opus_packet_get_samples_per_frame, opus_packet_pad, opus_packet_parse, opus_packet_unpad,
+};
+
+/// Returns the audio bandwidth the packet was encoded with.
+pub fn bandwidth(packet: &[u8]) -> OpusResult<OpusBandwidth> {
+	ensure_not_empty(packet)?;
+	map_error!(unsafe { opus_packet_get_bandwidth(packet.as_ptr()) })
+		.and_then(|bandwidth| {
+			OpusBandwidth::try_from(bandwidth).map_err(|_| OpusErrorCode::InvalidPacket)
+		})
+		.context(Operation::ParsePacket)
+}
+
+/// Returns the number of channels encoded in the packet.
+pub fn nb_channels(packet: &[u8]) -> OpusResult<usize> {
+	ensure_not_empty(packet)?;
+	map_error!(usize, unsafe {
+		opus_packet_get_nb_channels(packet.as_ptr())
+	})
+	.context(Operation::ParsePacket)
+}
+
+/// Returns the number of frames in the packet.
+pub fn nb_frames(packet: &[u8]) -> OpusResult<usize> {
+	ensure_not_empty(packet)?;
+	map_error!(usize, unsafe {
+		opus_packet_get_nb_frames(
+			packet.as_ptr(),
+			ffi::c_len(packet.len(), Operation::ParsePacket)?,
+		)
+	})
+	.context(Operation::ParsePacket)
+}
+
+/// Returns the number of samples (per channel) in each frame of the packet,
+/// at the given sampling rate.
+pub fn samples_per_frame(packet: &[u8], sample_rate: i32) -> OpusResult<usize> {
+	ensure_not_empty(packet)?;
+	map_error!(usize, unsafe {
+		opus_packet_get_samples_per_frame(packet.as_ptr(), sample_rate)
+	})
+	.context(Operation::ParsePacket)
+}
+
+/// Returns the total number of samples (per channel) in the packet, at the
+/// given sampling rate.
+///
+/// The sampling rate must be a multiple of 400, or inaccurate results will be
+/// returned.
+pub fn nb_samples(packet: &[u8], sample_rate: i32) -> OpusResult<usize> {
+	ensure_not_empty(packet)?;
+	map_error!(usize, unsafe {
+		opus_packet_get_nb_samples(
+			packet.as_ptr(),
+			ffi::c_len(packet.len(), Operation::ParsePacket)?,
+			sample_rate,
+		)
+	})
+	.context(Operation::ParsePacket)
+}
+
+/// Returns whether the packet is a discontinuous transmission (DTX) packet,
+/// which carries no audio, and only tells the decoder to keep producing
+/// comfort noise.
+///
+/// This covers empty packets, packets of up to 2 bytes, and code 3 packets
+/// declaring zero frames, which only carry padding.
+pub fn is_dtx(packet: &[u8]) -> bool {
+	match *packet {
+		[] | [_] | [_, _] => true,
+		[toc, count, ..] => OpusToc::parse(toc).code() == 3 && count & 0x3F == 0,
+	}
+}
+
+/// Returns whether the packet is likely to be silence or comfort noise, i.e.
+/// it is a [DTX](is_dtx) packet, or each of its frames is too small to carry
+/// anything more.
+///
+/// This is only a heuristic based on the TOC byte and the frame sizes, as
+/// telling for certain would require decoding the packet.
+pub fn is_silence_candidate(packet: &[u8]) -> bool {
+	/// The largest frame considered too small to carry real audio.
+	const MAX_SILENT_FRAME_LEN: usize = 2;
+
+	is_dtx(packet)
+		|| parse(packet).is_ok_and(|parsed| {
+			parsed
+				.frames
+				.iter()
+				.all(|frame| frame.len() <= MAX_SILENT_FRAME_LEN)
+		})
+}
+
+/// The maximum number of frames that can be contained in a single packet.
+pub const MAX_FRAMES: usize = 48;
+
+/// An Opus packet split into its individual frames, as returned by [`parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedPacket<'a> {
+	/// The TOC byte of the packet.
+	pub toc: u8,
+	/// The compressed data of each frame in the packet.
+	pub frames: Vec<&'a [u8]>,
+	/// The offset of the first frame's data within the packet, in bytes.
+	pub payload_offset: usize,
+}
+
+/// Parses an Opus packet into its individual frames, without copying them.
+pub fn parse(packet: &[u8]) -> OpusResult<ParsedPacket<'_>> {
+	ensure_not_empty(packet)?;
+	let mut toc = 0;
+	let mut frame_ptrs = [ptr::null(); MAX_FRAMES];
+	let mut frame_sizes = [0; MAX_FRAMES];
+	let mut payload_offset = 0;
+	let nb_frames = map_error!(usize, unsafe {
+		opus_packet_parse(
+			packet.as_ptr(),
+			ffi::c_len(packet.len(), Operation::ParsePacket)?,
+			&mut toc,
+			frame_ptrs.as_mut_ptr(),
+			frame_sizes.as_mut_ptr(),
+			&mut payload_offset,
+		)
+	})
+	.context(Operation::ParsePacket)?;
+	let frames = frame_ptrs
+		.iter()
+		.zip(frame_sizes)
+		.take(nb_frames)
+		.map(|(&frame_ptr, frame_size)| {
+			// SAFETY: libopus returns pointers into the packet we gave it, rather
+			// than copying the frames anywhere.
+			let start = unsafe { frame_ptr.offset_from(packet.as_ptr()) } as usize;
+			&packet[start..start + frame_size as usize]
+		})
+		.collect();
+	Ok(ParsedPacket {
+		toc,
+		frames,
+		payload_offset: payload_offset as usize,
+	})
+}
+
+/// Checks that the packet is structurally valid, without decoding it.
+///
+/// This checks that the packet isn't empty, that its frame count is
+/// consistent with its length and with the TOC byte's frame count code, as
+/// specified by [RFC 6716 section 3](https://www.rfc-editor.org/rfc/rfc6716#section-3),
+/// and that it's no longer than 120 ms. Returns
+/// [`OpusErrorCode::InvalidPacket`] if any of these checks fail.
+///
+/// A valid packet may still fail to decode if its frames are corrupt, as
+/// their contents can't be checked without decoding them.
+pub fn validate_packet(packet: &[u8]) -> OpusResult<()> {
+	let invalid = |_| invalid_packet();
+	nb_frames(packet).map_err(invalid)?;
+	nb_samples(packet, 48000).map_err(invalid)?;
+	parse(packet).map_err(invalid)?;
+	Ok(())
+}
+
+/// Pads the packet in-place to `new_len` bytes, without changing the audio it
+/// decodes to.
+///
+/// Padding to the current length of the packet does nothing. Returns
+/// [`OpusErrorCode::BadArg`] if `new_len` is smaller than the current length
+/// of the packet. On error, the packet is left unchanged.
+pub fn pad(packet: &mut Vec<u8>, new_len: usize) -> OpusResult<()> {
+	let len = packet.len();
+	if new_len < len {
+		return Err(OpusError::new(OpusErrorCode::BadArg, Operation::PadPacket));
+	}
+	let c_len = ffi::c_len(len, Operation::PadPacket)?;
+	let c_new_len = ffi::c_len(new_len, Operation::PadPacket)?;
+	packet.resize(new_len, 0);
+	let result = map_error!((), unsafe {
+		opus_packet_pad(packet.as_mut_ptr(), c_len, c_new_len)
+	})
+	.context(Operation::PadPacket);
+	if result.is_err() {
+		packet.truncate(len);
+	}
+	result
+}
+
+/// Removes all padding from the packet in-place, shrinking it to its minimal
+/// size.
+///
+/// This can't be done by just slicing the packet, as removing the padding
+/// also rewrites the packet's framing, such as the frame count byte.
+pub fn unpad(packet: &mut Vec<u8>) -> OpusResult<()> {
+	let new_len = map_error!(usize, unsafe {
+		opus_packet_unpad(
+			packet.as_mut_ptr(),
+			ffi::c_len(packet.len(), Operation::UnpadPacket)?,
+		)
+	})
+	.context(Operation::UnpadPacket)?;
+	packet.truncate(new_len);
+	Ok(())
+}
+
+/// Pads the multistream packet in-place to `new_len` bytes, without changing
+/// the audio it decodes to. The padding is added to the last stream.
+///
+/// Returns [`OpusErrorCode::BadArg`] if `new_len` is smaller than the current
+/// length of the packet. On error, the packet is left unchanged.
+pub fn pad_multistream(packet: &mut Vec<u8>, new_len: usize, nb_streams: i32) -> OpusResult<()> {
+	let len = packet.len();
+	if new_len < len {
+		return Err(OpusError::new(OpusErrorCode::BadArg, Operation::PadPacket));
+	}
+	let c_len = ffi::c_len(len, Operation::PadPacket)?;
+	let c_new_len = ffi::c_len(new_len, Operation::PadPacket)?;
+	packet.resize(new_len, 0);
+	let result = map_error!((), unsafe {
+		opus_multistream_packet_pad(packet.as_mut_ptr(), c_len, c_new_len, nb_streams)
+	})
+	.context(Operation::PadPacket);
+	if result.is_err() {
+		packet.truncate(len);
+	}
+	result
+}
+
+/// Removes all padding from each stream of the multistream packet in-place,
+/// shrinking it to its minimal size.
+pub fn unpad_multistream(packet: &mut Vec<u8>, nb_streams: i32) -> OpusResult<()> {
+	let new_len = map_error!(usize, unsafe {
+		opus_multistream_packet_unpad(
+			packet.as_mut_ptr(),
+			ffi::c_len(packet.len(), Operation::UnpadPacket)?,
+			nb_streams,
+		)
+	})
+	.context(Operation::UnpadPacket)?;
+	packet.truncate(new_len);
+	Ok(())
+}
+
+/// The maximum size of a single compressed frame, in bytes.
+const MAX_FRAME_BYTES: usize = 1275;
+
+/// The longest frame the encoder codes as a single frame: 20 ms at 48 kHz.
+const MAX_CODED_FRAME_48K: usize = 960;
+
+/// Returns the largest size a packet of `nb_frames` frames can take, in
+/// bytes: each frame may take up to 2 bytes for its length, plus the TOC byte
+/// and frame count byte of the packet.
+pub(crate) const fn max_size_of_frames(nb_frames: usize) -> usize {
+	nb_frames * (MAX_FRAME_BYTES + 2) + 2
+}
+
+/// Returns the largest size an encoded packet of `frame_size` samples (per
+/// channel) at the given sampling rate can take, in bytes.
+///
+/// Frames longer than 20 ms are coded as several frames of up to 20 ms each,
+/// which may each take up to 1275 bytes, so a 120 ms packet can be several
+/// times larger than a single frame.
+pub fn max_packet_size(frame_size: impl Into<FrameSize>, sample_rate: SampleRate) -> usize {
+	let samples_48k =
+		frame_size.into().samples().saturating_mul(48000) / sample_rate.as_i32() as usize;
+	max_size_of_frames(samples_48k.div_ceil(MAX_CODED_FRAME_48K).max(1))
+}
+
+/// Merges consecutive packets into as few packets as possible, without any
+/// packet exceeding `max_duration`.
+///
+/// Packets are merged greedily in order, so a new packet is started whenever
+/// the next one wouldn't fit in the current one. All of the packets must share
+/// the same TOC configuration and channel count, or
+/// [`OpusErrorCode::InvalidPacket`] is returned.
+pub fn combine_packets(packets: &[&[u8]], max_duration: FrameDuration) -> OpusResult<Vec<Vec<u8>>> {
+	let max_samples = max_duration.samples_48k();
+	let mut combined = Vec::new();
+	let mut repacketizer = OpusRepacketizer::new();
+	let mut duration = 0;
+	let mut first_toc = None;
+	for &packet in packets {
+		check_same_config(&mut first_toc, packet)?;
+		let packet_duration = nb_samples(packet, 48000)?;
+		if duration > 0 && duration + packet_duration > max_samples {
+			combined.push(flush_repacketizer(&mut repacketizer)?);
+			repacketizer = OpusRepacketizer::new();
+			duration = 0;
+		}
+		repacketizer.cat(packet)?;
+		duration += packet_duration;
+	}
+	if duration > 0 {
+		combined.push(flush_repacketizer(&mut repacketizer)?);
+	}
+	Ok(combined)
+}
+
+/// Splits a multi-frame packet into one standalone packet per frame.
+///
+/// Single-frame packets are returned unchanged.
+pub fn split_packet(packet: &[u8]) -> OpusResult<Vec<Vec<u8>>> {
+	let mut repacketizer = OpusRepacketizer::new();
+	repacketizer.cat(packet)?;
+	let nb_frames = repacketizer.nb_frames();
+	if nb_frames == 1 {
+		return Ok(vec![packet.to_vec()]);
+	}
+	(0..nb_frames)
+		.map(|frame| repacketizer.out_range(frame, frame + 1, MAX_FRAME_BYTES + 1))
+		.collect()
+}
+
+/// Checks that `packet` shares the TOC configuration and channel count of the
+/// first packet checked with the same `first_toc`, as the repacketizer
+/// requires.
+pub(crate) fn check_same_config(first_toc: &mut Option<OpusToc>, packet: &[u8]) -> OpusResult<()> {
+	let toc = OpusToc::of_packet(packet)?;
+	let first_toc = *first_toc.get_or_insert(toc);
+	if first_toc.config() != toc.config() || first_toc.is_stereo() != toc.is_stereo() {
+		return Err(OpusError::new(
+			OpusErrorCode::InvalidPacket,
+			Operation::Repacketize,
+		));
+	}
+	Ok(())
+}
+
+pub(crate) fn flush_repacketizer(repacketizer: &mut OpusRepacketizer) -> OpusResult<Vec<u8>> {
+	let nb_frames = repacketizer.nb_frames();
+	repacketizer.out(max_size_of_frames(nb_frames))
+}
+
+/// Converts a packet to the self-delimiting framing described in
+/// [RFC 6716, Appendix B](https://www.rfc-editor.org/rfc/rfc6716#appendix-B),
+/// where the length of the last frame is stored in the packet, so that it can
+/// be concatenated with other packets.
+pub fn to_self_delimited(packet: &[u8]) -> OpusResult<Vec<u8>> {
+	let parsed = parse(packet)?;
+	let last_len = parsed.frames.last().map_or(0, |frame| frame.len());
+	let mut delimited = Vec::with_capacity(packet.len() + 2);
+	delimited.extend_from_slice(&packet[..parsed.payload_offset]);
+	write_frame_len(&mut delimited, last_len);
+	delimited.extend_from_slice(&packet[parsed.payload_offset..]);
+	Ok(delimited)
+}
+
+/// Reads a single self-delimited packet from the start of `data`, returning
+/// the packet in the regular framing, along with the number of bytes of
+/// `data` it took up.
+pub fn from_self_delimited(data: &[u8]) -> OpusResult<(Vec<u8>, usize)> {
+	let toc = OpusToc::parse(*data.first().ok_or_else(invalid_packet)?);
+	let mut offset = 1;
+	let mut padding = 0;
+	let mut frames_len = 0;
+	let last_frame_count = match toc.code() {
+		0 => 1,
+		1 => 2,
+		2 => {
+			let (len, size) = read_frame_len(&data[offset..])?;
+			offset += size;
+			frames_len += len;
+			1
+		}
+		_ => {
+			let count = *data.get(offset).ok_or_else(invalid_packet)?;
+			offset += 1;
+			let nb_frames = (count & 0x3F) as usize;
+			if nb_frames == 0 {
+				return Err(invalid_packet());
+			}
+			if count & 0x40 != 0 {
+				loop {
+					let byte = *data.get(offset).ok_or_else(invalid_packet)?;
+					offset += 1;
+					padding += if byte == 255 { 254 } else { byte as usize };
+					if byte != 255 {
+						break;
+					}
+				}
+			}
+			if count & 0x80 != 0 {
+				for _ in 1..nb_frames {
+					let (len, size) = read_frame_len(&data[offset..])?;
+					offset += size;
+					frames_len += len;
+				}
+				1
+			} else {
+				nb_frames
+			}
+		}
+	};
+	let (last_len, size) = read_frame_len(&data[offset..])?;
+	let end = offset + size + frames_len + last_len * last_frame_count + padding;
+	if end > data.len() {
+		return Err(invalid_packet());
+	}
+	let mut packet = Vec::with_capacity(end - size);
+	packet.extend_from_slice(&data[..offset]);
+	packet.extend_from_slice(&data[offset + size..end]);
+	parse(&packet)?;
+	Ok((packet, end))
+}
+
+/// Returns an iterator over the packets in a buffer of concatenated
+/// self-delimited packets.
+pub fn self_delimited_packets(data: &[u8]) -> SelfDelimitedPackets<'_> {
+	SelfDelimitedPackets { data }
+}
+
+/// An iterator over a buffer of concatenated self-delimited packets, as
+/// returned by [`self_delimited_packets`].
+///
+/// Iteration stops after the first invalid packet.
+#[derive(Debug, Clone)]
+pub struct SelfDelimitedPackets<'a> {
+	data: &'a [u8],
+}
+
+impl Iterator for SelfDelimitedPackets<'_> {
+	type Item = OpusResult<Vec<u8>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.data.is_empty() {
+			return None;
+		}
+		match from_self_delimited(self.data) {
+			Ok((packet, len)) => {
+				self.data = &self.data[len..];
+				Some(Ok(packet))
+			}
+			Err(err) => {
+				self.data = &[];
+				Some(Err(err))
+			}
+		}
+	}
+}
+
+/// Writes a frame length, using the one or two byte encoding from RFC 6716.
+fn write_frame_len(out: &mut Vec<u8>, len: usize) {
+	if len < 252 {
+		out.push(len as u8);
+	} else {
+		let first = 252 + (len & 3);
+		out.push(first as u8);
+		out.push(((len - first) >> 2) as u8);
+	}
+}
+
+/// Reads a frame length, returning the length along with how many bytes it
+/// was encoded in.
+fn read_frame_len(data: &[u8]) -> OpusResult<(usize, usize)> {
+	match *data {
+		[first, ..] if first < 252 => Ok((first as usize, 1)),
+		[first, second, ..] => Ok((second as usize * 4 + first as usize, 2)),
+		_ => Err(invalid_packet()),
+	}
+}
+
+/// An owned Opus packet, which is guaranteed to be structurally valid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OpusPacket(Vec<u8>);
+
+impl OpusPacket {
+	/// Returns a borrowed view of the packet.
+	pub fn as_packet_ref(&self) -> OpusPacketRef<'_> {
+		OpusPacketRef(&self.0)
+	}
+
+	/// Returns the raw bytes of the packet.
+	pub fn into_inner(self) -> Vec<u8> {
+		self.0
+	}
+
+	/// Returns the TOC byte of the packet.
+	pub fn toc(&self) -> OpusToc {
+		self.as_packet_ref().toc()
+	}
+
+	/// Returns the raw TOC byte of the packet.
+	pub fn toc_byte(&self) -> u8 {
+		self.as_packet_ref().toc_byte()
+	}
+
+	/// Returns the configuration number (0-31) of the packet.
+	pub fn config_index(&self) -> u8 {
+		self.as_packet_ref().config_index()
+	}
+
+	/// Returns whether the packet is coded as stereo.
+	pub fn stereo(&self) -> bool {
+		self.as_packet_ref().stereo()
+	}
+
+	/// Returns the frame count code (0-3) of the packet.
+	pub fn frame_count_code(&self) -> u8 {
+		self.as_packet_ref().frame_count_code()
+	}
+
+	/// Returns the coding mode of the packet.
+	pub fn mode(&self) -> OpusMode {
+		self.as_packet_ref().mode()
+	}
+
+	/// Returns the number of channels encoded in the packet.
+	pub fn channels(&self) -> usize {
+		self.as_packet_ref().channels()
+	}
+
+	/// Returns the number of frames in the packet.
+	pub fn frames(&self) -> usize {
+		self.as_packet_ref().frames()
+	}
+
+	/// Returns the duration of the packet, in milliseconds.
+	pub fn duration_ms(&self) -> f32 {
+		self.as_packet_ref().duration_ms()
+	}
+
+	/// Returns the duration of the packet, in samples (per channel) at the
+	/// given sampling rate.
+	pub fn duration_samples(&self, sample_rate: SampleRate) -> usize {
+		self.as_packet_ref().duration_samples(sample_rate)
+	}
+}
+
+impl TryFrom<Vec<u8>> for OpusPacket {
+	type Error = OpusError;
+
+	fn try_from(packet: Vec<u8>) -> Result<Self, Self::Error> {
+		parse(&packet)?;
+		Ok(Self(packet))
+	}
+}
+
+impl From<OpusPacket> for Vec<u8> {
+	fn from(packet: OpusPacket) -> Self {
+		packet.0
+	}
+}
+
+impl AsRef<[u8]> for OpusPacket {
+	fn as_ref(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl Deref for OpusPacket {
+	type Target = [u8];
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+/// A borrowed Opus packet, which is guaranteed to be structurally valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OpusPacketRef<'a>(&'a [u8]);
+
+impl<'a> OpusPacketRef<'a> {
+	/// Returns the raw bytes of the packet.
+	pub fn as_bytes(self) -> &'a [u8] {
+		self.0
+	}
+
+	/// Copies the packet into an owned [`OpusPacket`].
+	pub fn to_packet(self) -> OpusPacket {
+		OpusPacket(self.0.to_vec())
+	}
+
+	/// Returns the TOC byte of the packet.
+	pub fn toc(self) -> OpusToc {
+		OpusToc::parse(self.toc_byte())
+	}
+
+	/// Returns the raw TOC byte of the packet.
+	pub fn toc_byte(self) -> u8 {
+		self.0[0]
+	}
+
+	/// Returns the configuration number (0-31) of the packet, which
+	/// determines its mode, bandwidth, and frame duration.
+	pub fn config_index(self) -> u8 {
+		self.toc().config()
+	}
+
+	/// Returns whether the packet is coded as stereo.
+	pub fn stereo(self) -> bool {
+		self.toc().is_stereo()
+	}
+
+	/// Returns the frame count code (0-3) of the packet. See
+	/// [`OpusToc::code`] for what each code means.
+	pub fn frame_count_code(self) -> u8 {
+		self.toc().code()
+	}
+
+	/// Returns the coding mode of the packet.
+	pub fn mode(self) -> OpusMode {
+		self.toc().mode()
+	}
+
+	/// Returns the number of channels encoded in the packet.
+	pub fn channels(self) -> usize {
+		if self.stereo() {
+			2
+		} else {
+			1
+		}
+	}
+
+	/// Returns the number of frames in the packet.
+	pub fn frames(self) -> usize {
+		match self.toc().code() {
+			0 => 1,
+			1 | 2 => 2,
+			// Validation guarantees that code 3 packets have a frame count byte.
+			_ => (self.0[1] & 0x3F) as usize,
+		}
+	}
+
+	/// Returns the duration of the packet, in milliseconds.
+	pub fn duration_ms(self) -> f32 {
+		self.duration_samples(SampleRate::Hz48000) as f32 / 48.0
+	}
+
+	/// Returns the duration of the packet, in samples (per channel) at the
+	/// given sampling rate.
+	pub fn duration_samples(self, sample_rate: SampleRate) -> usize {
+		self.frames() * self.toc().samples_per_frame(sample_rate)
+	}
+}
+
+impl<'a> TryFrom<&'a [u8]> for OpusPacketRef<'a> {
+	type Error = OpusError;
+
+	fn try_from(packet: &'a [u8]) -> Result<Self, Self::Error> {
+		parse(packet)?;
+		Ok(Self(packet))
+	}
+}
+
+impl AsRef<[u8]> for OpusPacketRef<'_> {
+	fn as_ref(&self) -> &[u8] {
+		self.0
+	}
+}
+
+impl Deref for OpusPacketRef<'_> {
+	type Target = [u8];
+
+	fn deref(&self) -> &Self::Target {
+		self.0
+	}
+}
+
+/// libopus reads the TOC byte of the packet unconditionally, so an empty
+/// packet must never make it across the FFI boundary.
+fn ensure_not_empty(packet: &[u8]) -> OpusResult<()> {
+	if packet.is_empty() {
+		Err(invalid_packet())
+	} else {
+		Ok(())
+	}
+}
+
+fn invalid_packet() -> OpusError {
+	OpusError::new(OpusErrorCode::InvalidPacket, Operation::ParsePacket)
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{error::OpusResult, packet, repacketizer::OpusRepacketizer};
+use alloc::vec::Vec;
+
+/// Pads packets to a constant size, so that the size of each packet doesn't
+/// leak anything about the audio it contains.
+///
+/// Padding doesn't change the audio a packet decodes to. Packets which are
+/// already larger than the target size can't be padded, so the target should
+/// be picked with some headroom above the largest packet the encoder emits,
+/// using [`PacketPadder::padding_added`] to measure the cost of doing so.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketPadder {
+	target_len: usize,
+	packets_padded: u64,
+	padding_added: u64,
+}
+
+impl PacketPadder {
+	/// Creates a new padder, which pads packets to exactly `target_len` bytes.
+	pub fn new(target_len: usize) -> Self {
+		Self {
+			target_len,
+			packets_padded: 0,
+			padding_added: 0,
+		}
+	}
+
+	/// The size every packet is padded to, in bytes.
+	pub fn target_len(&self) -> usize {
+		self.target_len
+	}
+
+	/// The number of packets padded so far.
+	pub fn packets_padded(&self) -> u64 {
+		self.packets_padded
+	}
+
+	/// The total number of bytes of padding added so far.
+	pub fn padding_added(&self) -> u64 {
+		self.padding_added
+	}
+
+	/// Pads the packet in-place to the target size.
+	///
+	/// Returns [`OpusErrorCode::BadArg`](crate::error::OpusErrorCode::BadArg)
+	/// if the packet is already larger than the target size. On error, the
+	/// packet is left unchanged.
+	pub fn pad(&mut self, packet: &mut Vec<u8>) -> OpusResult<()> {
+		let len = packet.len();
+		packet::pad(packet, self.target_len)?;
+		self.packets_padded += 1;
+		self.padding_added += (self.target_len - len) as u64;
+		Ok(())
+	}
+
+	/// Merges the packets into a single packet with the repacketizer, and pads
+	/// it to the target size.
+	///
+	/// The packets must be able to be merged, as with
+	/// [`OpusRepacketizer::cat`]. Returns
+	/// [`OpusErrorCode::BufferTooSmall`](crate::error::OpusErrorCode::BufferTooSmall)
+	/// if the merged packet is larger than the target size.
+	pub fn repacketize(&mut self, packets: &[&[u8]]) -> OpusResult<Vec<u8>> {
+		let mut repacketizer = OpusRepacketizer::new();
+		for packet in packets {
+			repacketizer.cat(packet)?;
+		}
+		let mut packet = repacketizer.out(self.target_len)?;
+		self.pad(&mut packet)?;
+		Ok(packet)
+	}
+}
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MPL-2.0
+use alloc::vec::Vec;
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	ops::{Deref, DerefMut},
+};
+use crossbeam_queue::ArrayQueue;
+
+/// The number of buffers a [`BufferPool`] holds by default.
+const DEFAULT_CAPACITY: usize = 64;
+
+/// A bounded pool of packet buffers, so that encoding doesn't need to
+/// allocate a new buffer for every packet.
+///
+/// Buffers are taken from the pool with [`BufferPool::get`], and returned to
+/// it when the [`PooledPacket`] holding them is dropped. If the pool is
+/// empty, a new buffer is allocated instead, and if it is full, returned
+/// buffers are freed, so the pool never holds more than its capacity.
+///
+/// The pool can be shared between threads, such as between several encoders
+/// and the thread sending their packets.
+pub struct BufferPool {
+	buffers: ArrayQueue<Vec<u8>>,
+}
+
+impl BufferPool {
+	/// Creates a new, empty pool, which holds up to `capacity` buffers.
+	///
+	/// # Panics
+	///
+	/// Panics if `capacity` is 0.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			buffers: ArrayQueue::new(capacity),
+		}
+	}
+
+	/// The maximum number of buffers the pool holds.
+	pub fn capacity(&self) -> usize {
+		self.buffers.capacity()
+	}
+
+	/// The number of buffers currently in the pool.
+	pub fn len(&self) -> usize {
+		self.buffers.len()
+	}
+
+	/// Returns `true` if there are no buffers in the pool.
+	pub fn is_empty(&self) -> bool {
+		self.buffers.is_empty()
+	}
+
+	/// Takes an empty buffer from the pool, allocating a new one if the pool is
+	/// empty.
+	pub fn get(&self) -> PooledPacket<'_> {
+		PooledPacket {
+			data: self.buffers.pop().unwrap_or_default(),
+			pool: self,
+		}
+	}
+
+	/// Returns a buffer to the pool, freeing it if the pool is full.
+	fn put(&self, mut buffer: Vec<u8>) {
+		buffer.clear();
+		// If the pool is full, the buffer is just dropped.
+		let _ = self.buffers.push(buffer);
+	}
+}
+
+impl Default for BufferPool {
+	/// Creates a new, empty pool, which holds up to 64 buffers.
+	fn default() -> Self {
+		Self::new(DEFAULT_CAPACITY)
+	}
+}
+
+impl Debug for BufferPool {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("BufferPool")
+			.field("capacity", &self.capacity())
+			.field("len", &self.len())
+			.finish()
+	}
+}
+
+/// A packet buffer taken from a [`BufferPool`], which is returned to the pool
+/// when dropped.
+pub struct PooledPacket<'pool> {
+	data: Vec<u8>,
+	pool: &'pool BufferPool,
+}
+
+impl PooledPacket<'_> {
+	/// Takes the buffer out of the pool for good, so that it isn't returned
+	/// to it when dropped.
+	pub fn into_vec(mut self) -> Vec<u8> {
+		core::mem::take(&mut self.data)
+	}
+
+	/// The underlying buffer, which can be resized as needed.
+	pub fn as_mut_vec(&mut self) -> &mut Vec<u8> {
+		&mut self.data
+	}
+}
+
+impl Deref for PooledPacket<'_> {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		&self.data
+	}
+}
+
+impl DerefMut for PooledPacket<'_> {
+	fn deref_mut(&mut self) -> &mut [u8] {
+		&mut self.data
+	}
+}
+
+impl AsRef<[u8]> for PooledPacket<'_> {
+	fn as_ref(&self) -> &[u8] {
+		&self.data
+	}
+}
+
+impl Debug for PooledPacket<'_> {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_tuple("PooledPacket").field(&self.data).finish()
+	}
+}
+
+impl Drop for PooledPacket<'_> {
+	fn drop(&mut self) {
+		// Buffers without an allocation, such as those left behind by
+		// `into_vec`, aren't worth returning.
+		if self.data.capacity() != 0 {
+			self.pool.put(core::mem::take(&mut self.data));
+		}
+	}
+}
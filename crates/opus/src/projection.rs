@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Ambisonics encoding and decoding with mapping family 3.
+//!
+//! The encoder mixes the ambisonic channels into a set of multistream
+//! streams with a mixing matrix, and the decoder undoes this with the
+//! matching demixing matrix, which has to be passed from the encoder to the
+//! decoder, such as in the header of an Ogg Opus stream.
+
+mod decoder;
+mod encoder;
+
+pub use self::{decoder::OpusProjectionDecoder, encoder::OpusProjectionEncoder};
@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	error::{ErrorContext, Operation, OpusError, OpusErrorCode, OpusResult},
+	ffi,
+	frame_size::FrameSize,
+	sample_rate::SampleRate,
+	state::StateBuffer,
+};
+use alloc::{vec, vec::Vec};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+use meowlouder_opus_sys::{
+	opus_projection_decode, opus_projection_decode_float, opus_projection_decoder_ctl,
+	opus_projection_decoder_get_size, opus_projection_decoder_init, OPUS_RESET_STATE,
+};
+
+/// An Opus projection decoder, which decodes the packets of an
+/// [`OpusProjectionEncoder`](crate::OpusProjectionEncoder) back into
+/// interleaved ambisonic audio.
+///
+/// Lost packets are concealed by passing `None` as the packet to decode.
+///
+/// This is [`Send`] but not [`Sync`], like
+/// [`OpusEncoder`](crate::encode::OpusEncoder#threading).
+pub struct OpusProjectionDecoder {
+	decoder_state: StateBuffer,
+	channels: usize,
+	streams: usize,
+	coupled_streams: usize,
+	_not_sync: PhantomData<*mut ()>,
+}
+
+// SAFETY: The projection state holds a copy of the demixing matrix, followed
+// by a multistream decoder, both inline, so it is as self-contained as an
+// `OpusMSDecoder`'s.
+unsafe impl Send for OpusProjectionDecoder {}
+
+impl OpusProjectionDecoder {
+	/// Creates a new projection decoder.
+	///
+	/// `streams`, `coupled_streams` and `demixing_matrix` must be those of the
+	/// encoder, from [`OpusProjectionEncoder::streams`],
+	/// [`OpusProjectionEncoder::coupled_streams`] and
+	/// [`OpusProjectionEncoder::demixing_matrix`], or from the header of the
+	/// stream being decoded. Returns [`OpusErrorCode::BadArg`] if the stream
+	/// counts aren't valid, or the size of the demixing matrix doesn't match
+	/// them.
+	///
+	/// [`OpusProjectionEncoder::streams`]: crate::OpusProjectionEncoder::streams
+	/// [`OpusProjectionEncoder::coupled_streams`]: crate::OpusProjectionEncoder::coupled_streams
+	/// [`OpusProjectionEncoder::demixing_matrix`]: crate::OpusProjectionEncoder::demixing_matrix
+	pub fn new(
+		sample_rate: i32,
+		channels: u8,
+		streams: u8,
+		coupled_streams: u8,
+		demixing_matrix: &[u8],
+	) -> OpusResult<Self> {
+		SampleRate::try_from(sample_rate).context(Operation::DecoderInit)?;
+		let decoder_size = unsafe {
+			opus_projection_decoder_get_size(channels as _, streams as _, coupled_streams as _)
+				as usize
+		};
+		// libopus returns a size of 0 for invalid stream counts.
+		if decoder_size == 0 {
+			return Err(OpusError::new(
+				OpusErrorCode::BadArg,
+				Operation::DecoderInit,
+			));
+		}
+		let mut decoder_state = StateBuffer::new(decoder_size);
+		// libopus only reads the demixing matrix, despite taking it mutably.
+		map_error!(unsafe {
+			opus_projection_decoder_init(
+				decoder_state.as_mut_ptr().cast(),
+				sample_rate,
+				channels as _,
+				streams as _,
+				coupled_streams as _,
+				demixing_matrix.as_ptr().cast_mut(),
+				demixing_matrix.len() as _,
+			)
+		})
+		.context(Operation::DecoderInit)?;
+		Ok(Self {
+			decoder_state,
+			channels: channels as usize,
+			streams: streams as usize,
+			coupled_streams: coupled_streams as usize,
+			_not_sync: PhantomData,
+		})
+	}
+
+	pub fn decode_into<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[i16]>,
+	{
+		let pcm = pcm.as_mut();
+		let required = ffi::pcm_len(frame_size, self.channels, Operation::Decode)?;
+		if pcm.len() < required {
+			return Err(OpusError::buffer_too_small(
+				Operation::Decode,
+				required,
+				pcm.len(),
+			));
+		}
+		// SAFETY: `pcm` has room for `frame_size` samples for each channel, which
+		// is the most libopus ever decodes.
+		unsafe { self.decode_into_unchecked(data, pcm, frame_size, decode_fec) }
+	}
+
+	/// Decodes like [`OpusProjectionDecoder::decode_into`], without checking
+	/// that `pcm` is large enough.
+	///
+	/// # Safety
+	///
+	/// `pcm` must have room for the decoded audio for each channel, which is
+	/// the duration of the packet when decoding one normally, and `frame_size`
+	/// samples otherwise.
+	pub unsafe fn decode_into_unchecked<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[i16]>,
+	{
+		let pcm = pcm.as_mut();
+
+		let (data_ptr, data_len) = match &data {
+			Some(data) => {
+				let data = data.as_ref();
+				(data.as_ptr(), ffi::c_len(data.len(), Operation::Decode)?)
+			}
+			None => (core::ptr::null(), 0),
+		};
+		let frame_size = ffi::c_len(frame_size, Operation::Decode)?;
+
+		map_error!(usize, unsafe {
+			opus_projection_decode(
+				self.decoder_state.as_mut_ptr().cast(),
+				data_ptr,
+				data_len,
+				pcm.as_mut_ptr(),
+				frame_size,
+				decode_fec as _,
+			)
+		})
+		.context(Operation::Decode)
+	}
+
+	pub fn decode<Data>(
+		&mut self,
+		data: Option<Data>,
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<Vec<i16>>
+	where
+		Data: AsRef<[u8]>,
+	{
+		// libopus never decodes more than 120 ms at once, so there's no need to
+		// allocate room for more.
+		let frame_size = frame_size.min(FrameSize::max_at(SampleRate::Hz48000).samples());
+		let mut pcm = vec![0; ffi::pcm_len(frame_size, self.channels, Operation::Decode)?];
+		let len = self.decode_into(data, &mut pcm, frame_size, decode_fec)?;
+		pcm.truncate(len * self.channels);
+		Ok(pcm)
+	}
+
+	pub fn decode_float_into<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[f32]>,
+	{
+		let pcm = pcm.as_mut();
+		let required = ffi::pcm_len(frame_size, self.channels, Operation::Decode)?;
+		if pcm.len() < required {
+			return Err(OpusError::buffer_too_small(
+				Operation::Decode,
+				required,
+				pcm.len(),
+			));
+		}
+		// SAFETY: `pcm` has room for `frame_size` samples for each channel, which
+		// is the most libopus ever decodes.
+		unsafe { self.decode_float_into_unchecked(data, pcm, frame_size, decode_fec) }
+	}
+
+	/// Decodes like [`OpusProjectionDecoder::decode_float_into`], without
+	/// checking that `pcm` is large enough.
+	///
+	/// # Safety
+	///
+	/// `pcm` must have room for the decoded audio for each channel, which is
+	/// the duration of the packet when decoding one normally, and `frame_size`
+	/// samples otherwise.
+	pub unsafe fn decode_float_into_unchecked<Data, Pcm>(
+		&mut self,
+		data: Option<Data>,
+		mut pcm: Pcm,
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<usize>
+	where
+		Data: AsRef<[u8]>,
+		Pcm: AsMut<[f32]>,
+	{
+		let pcm = pcm.as_mut();
+
+		let (data_ptr, data_len) = match &data {
+			Some(data) => {
+				let data = data.as_ref();
+				(data.as_ptr(), ffi::c_len(data.len(), Operation::Decode)?)
+			}
+			None => (core::ptr::null(), 0),
+		};
+		let frame_size = ffi::c_len(frame_size, Operation::Decode)?;
+
+		map_error!(usize, unsafe {
+			opus_projection_decode_float(
+				self.decoder_state.as_mut_ptr().cast(),
+				data_ptr,
+				data_len,
+				pcm.as_mut_ptr(),
+				frame_size,
+				decode_fec as _,
+			)
+		})
+		.context(Operation::Decode)
+	}
+
+	pub fn decode_float<Data>(
+		&mut self,
+		data: Option<Data>,
+		frame_size: usize,
+		decode_fec: bool,
+	) -> OpusResult<Vec<f32>>
+	where
+		Data: AsRef<[u8]>,
+	{
+		// libopus never decodes more than 120 ms at once, so there's no need to
+		// allocate room for more.
+		let frame_size = frame_size.min(FrameSize::max_at(SampleRate::Hz48000).samples());
+		let mut pcm = vec![0.0; ffi::pcm_len(frame_size, self.channels, Operation::Decode)?];
+		let len = self.decode_float_into(data, &mut pcm, frame_size, decode_fec)?;
+		pcm.truncate(len * self.channels);
+		Ok(pcm)
+	}
+
+	/// Resets the codec state to be equivalent to a freshly initialized state.
+	/// This should be called when switching streams in order to prevent the
+	/// back to back decoding from giving different results from one at a time
+	/// decoding.
+	pub fn reset(&mut self) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_projection_decoder_ctl(
+				self.decoder_state.as_mut_ptr().cast(),
+				OPUS_RESET_STATE as _,
+			)
+		})
+		.context(Operation::Ctl("reset state"))
+	}
+
+	/// Returns the number of output channels the decoder was initialized with.
+	pub fn channels(&self) -> usize {
+		self.channels
+	}
+
+	/// Returns the total number of streams the decoder decodes.
+	pub fn streams(&self) -> usize {
+		self.streams
+	}
+
+	/// Returns the number of coupled (stereo) streams the decoder decodes.
+	pub fn coupled_streams(&self) -> usize {
+		self.coupled_streams
+	}
+}
+
+impl Debug for OpusProjectionDecoder {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusProjectionDecoder")
+			.field("channels", &self.channels)
+			.field("streams", &self.streams)
+			.field("coupled_streams", &self.coupled_streams)
+			.finish_non_exhaustive()
+	}
+}
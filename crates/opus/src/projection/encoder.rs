@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	application::OpusApplication,
+	error::{ErrorContext, Operation, OpusError, OpusErrorCode, OpusResult},
+	ffi, packet,
+	sample_rate::SampleRate,
+	state::StateBuffer,
+};
+use alloc::{vec, vec::Vec};
+use core::{
+	fmt::{Debug, Error as FmtError, Formatter},
+	marker::PhantomData,
+};
+use meowlouder_opus_sys::{
+	opus_projection_ambisonics_encoder_get_size, opus_projection_ambisonics_encoder_init,
+	opus_projection_encode, opus_projection_encode_float, opus_projection_encoder_ctl,
+	OPUS_GET_BITRATE_REQUEST, OPUS_PROJECTION_GET_DEMIXING_MATRIX_GAIN_REQUEST,
+	OPUS_PROJECTION_GET_DEMIXING_MATRIX_REQUEST, OPUS_PROJECTION_GET_DEMIXING_MATRIX_SIZE_REQUEST,
+	OPUS_RESET_STATE, OPUS_SET_BITRATE_REQUEST,
+};
+
+/// The mapping family for ambisonics with a mixing matrix.
+const MAPPING_FAMILY_PROJECTION: i32 = 3;
+
+/// The largest packet a single stream can produce (120 ms, coded as six 20 ms
+/// frames), plus the 2 bytes its self-delimiting length may take.
+const MAX_STREAM_DATA_BYTES: usize = packet::max_size_of_frames(6) + 2;
+
+/// An Opus projection encoder, which encodes interleaved ambisonic audio
+/// (ACN channel order, SN3D normalization) into multistream packets.
+///
+/// This is [`Send`] but not [`Sync`], like
+/// [`OpusEncoder`](crate::encode::OpusEncoder#threading).
+pub struct OpusProjectionEncoder {
+	encoder_state: StateBuffer,
+	channels: usize,
+	streams: usize,
+	coupled_streams: usize,
+	_not_sync: PhantomData<*mut ()>,
+}
+
+// SAFETY: The projection state holds copies of the mixing and demixing
+// matrices, followed by a multistream encoder, all inline, so it is as
+// self-contained as an `OpusMSEncoder`'s.
+unsafe impl Send for OpusProjectionEncoder {}
+
+impl OpusProjectionEncoder {
+	/// Creates a new projection encoder.
+	///
+	/// `channels` must be a full ambisonic order, `(order + 1)²`, optionally
+	/// followed by a non-diegetic stereo pair, so 4 or 6 channels for first
+	/// order ambisonics. Returns [`OpusErrorCode::BadArg`] for any other
+	/// number of channels.
+	pub fn new(sample_rate: i32, channels: u8, application: OpusApplication) -> OpusResult<Self> {
+		SampleRate::try_from(sample_rate).context(Operation::EncoderInit)?;
+		let encoder_size = unsafe {
+			opus_projection_ambisonics_encoder_get_size(channels as _, MAPPING_FAMILY_PROJECTION)
+				as usize
+		};
+		// libopus returns a size of 0 for unsupported channel counts.
+		if encoder_size == 0 {
+			return Err(OpusError::new(
+				OpusErrorCode::BadArg,
+				Operation::EncoderInit,
+			));
+		}
+		let mut encoder_state = StateBuffer::new(encoder_size);
+		let mut streams = 0;
+		let mut coupled_streams = 0;
+		map_error!(unsafe {
+			opus_projection_ambisonics_encoder_init(
+				encoder_state.as_mut_ptr().cast(),
+				sample_rate,
+				channels as _,
+				MAPPING_FAMILY_PROJECTION,
+				&mut streams,
+				&mut coupled_streams,
+				application.into(),
+			)
+		})
+		.context(Operation::EncoderInit)?;
+		Ok(Self {
+			encoder_state,
+			channels: channels as usize,
+			streams: streams as usize,
+			coupled_streams: coupled_streams as usize,
+			_not_sync: PhantomData,
+		})
+	}
+
+	/// Encodes a frame of interleaved audio into `data`, returning the length
+	/// of the packet.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if `pcm` holds less than
+	/// `frame_size` samples for every channel.
+	pub fn encode_into(
+		&mut self,
+		pcm: &[i16],
+		frame_size: usize,
+		data: &mut [u8],
+	) -> OpusResult<usize> {
+		if pcm.len() < ffi::pcm_len(frame_size, self.channels, Operation::Encode)? {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Encode));
+		}
+		map_error!(usize, unsafe {
+			opus_projection_encode(
+				self.encoder_state.as_mut_ptr().cast(),
+				pcm.as_ptr(),
+				ffi::c_len(frame_size, Operation::Encode)?,
+				data.as_mut_ptr(),
+				ffi::c_capacity(data.len()),
+			)
+		})
+		.context(Operation::Encode)
+	}
+
+	/// Encodes a frame of interleaved audio into a new packet.
+	pub fn encode(&mut self, pcm: &[i16], frame_size: usize) -> OpusResult<Vec<u8>> {
+		let mut data = vec![0; MAX_STREAM_DATA_BYTES * self.streams];
+		let len = self.encode_into(pcm, frame_size, &mut data)?;
+		data.truncate(len);
+		Ok(data)
+	}
+
+	/// Encodes a frame of interleaved float audio into `data`, returning the
+	/// length of the packet.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if `pcm` holds less than
+	/// `frame_size` samples for every channel.
+	pub fn encode_float_into(
+		&mut self,
+		pcm: &[f32],
+		frame_size: usize,
+		data: &mut [u8],
+	) -> OpusResult<usize> {
+		if pcm.len() < ffi::pcm_len(frame_size, self.channels, Operation::Encode)? {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::Encode));
+		}
+		map_error!(usize, unsafe {
+			opus_projection_encode_float(
+				self.encoder_state.as_mut_ptr().cast(),
+				pcm.as_ptr(),
+				ffi::c_len(frame_size, Operation::Encode)?,
+				data.as_mut_ptr(),
+				ffi::c_capacity(data.len()),
+			)
+		})
+		.context(Operation::Encode)
+	}
+
+	/// Encodes a frame of interleaved float audio into a new packet.
+	pub fn encode_float(&mut self, pcm: &[f32], frame_size: usize) -> OpusResult<Vec<u8>> {
+		let mut data = vec![0; MAX_STREAM_DATA_BYTES * self.streams];
+		let len = self.encode_float_into(pcm, frame_size, &mut data)?;
+		data.truncate(len);
+		Ok(data)
+	}
+
+	/// Resets the codec state to be equivalent to a freshly initialized state.
+	pub fn reset(&mut self) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_projection_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_RESET_STATE as _,
+			)
+		})
+		.context(Operation::Ctl("reset state"))
+	}
+
+	/// Returns the number of input channels the encoder was initialized with.
+	pub fn channels(&self) -> usize {
+		self.channels
+	}
+
+	/// Returns the total number of streams the encoder encodes.
+	pub fn streams(&self) -> usize {
+		self.streams
+	}
+
+	/// Returns the number of coupled (stereo) streams the encoder encodes.
+	pub fn coupled_streams(&self) -> usize {
+		self.coupled_streams
+	}
+
+	/// Returns the total bitrate of all of the streams, in bits per second.
+	pub fn bitrate(&mut self) -> OpusResult<i32> {
+		let mut bitrate = 0;
+		map_error!(&bitrate, unsafe {
+			opus_projection_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_GET_BITRATE_REQUEST as _,
+				&mut bitrate,
+			)
+		})
+		.context(Operation::Ctl("get bitrate"))
+	}
+
+	/// Configures the total bitrate of the encoder, which is split between the
+	/// streams, as with
+	/// [`OpusMSEncoder::set_bitrate`](crate::OpusMSEncoder::set_bitrate).
+	pub fn set_bitrate(&mut self, bitrate: i32) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_projection_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_SET_BITRATE_REQUEST as _,
+				bitrate,
+			)
+		})
+		.context(Operation::Ctl("set bitrate"))
+	}
+
+	/// Returns the gain of the demixing matrix, in Q7.8 dB, which should be
+	/// applied as the output gain when decoding.
+	pub fn demixing_matrix_gain(&mut self) -> OpusResult<i32> {
+		let mut gain = 0;
+		map_error!(&gain, unsafe {
+			opus_projection_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_PROJECTION_GET_DEMIXING_MATRIX_GAIN_REQUEST as _,
+				&mut gain,
+			)
+		})
+		.context(Operation::Ctl("get demixing matrix gain"))
+	}
+
+	/// Returns the size of the demixing matrix, in bytes.
+	///
+	/// The matrix holds a 16-bit coefficient for every pair of output and
+	/// coded channels, so this is `2 * channels * (streams +
+	/// coupled_streams)`.
+	pub fn demixing_matrix_size(&mut self) -> OpusResult<usize> {
+		let mut size = 0;
+		map_error!(&size, unsafe {
+			opus_projection_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_PROJECTION_GET_DEMIXING_MATRIX_SIZE_REQUEST as _,
+				&mut size,
+			)
+		})
+		.map(|size| size as usize)
+		.context(Operation::Ctl("get demixing matrix size"))
+	}
+
+	/// Returns the demixing matrix, which the decoder needs to undo the
+	/// encoder's mixing, as little-endian 16-bit coefficients in column-major
+	/// order.
+	pub fn demixing_matrix(&mut self) -> OpusResult<Vec<u8>> {
+		let mut matrix = vec![0; self.demixing_matrix_size()?];
+		map_error!((), unsafe {
+			opus_projection_encoder_ctl(
+				self.encoder_state.as_mut_ptr().cast(),
+				OPUS_PROJECTION_GET_DEMIXING_MATRIX_REQUEST as _,
+				matrix.as_mut_ptr(),
+				matrix.len() as i32,
+			)
+		})
+		.context(Operation::Ctl("get demixing matrix"))?;
+		Ok(matrix)
+	}
+}
+
+impl Debug for OpusProjectionEncoder {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.debug_struct("OpusProjectionEncoder")
+			.field("channels", &self.channels)
+			.field("streams", &self.streams)
+			.field("coupled_streams", &self.coupled_streams)
+			.finish_non_exhaustive()
+	}
+}
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	error::{ErrorContext, Operation, OpusResult},
+	ffi,
+	packet::{self, flush_repacketizer},
+	state::StateBuffer,
+};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use meowlouder_opus_sys::{
+	opus_repacketizer_cat, opus_repacketizer_get_nb_frames, opus_repacketizer_get_size,
+	opus_repacketizer_init, opus_repacketizer_out, opus_repacketizer_out_range,
+};
+
+/// A repacketizer, which merges multiple Opus packets into one, or splits a
+/// packet into several, without decoding them.
+///
+/// libopus doesn't copy the packets added to the repacketizer, and instead
+/// keeps pointers to them, so they are borrowed for as long as the
+/// repacketizer lives.
+///
+/// All packets added to a repacketizer must share the same coding mode,
+/// bandwidth, frame size, and channel count, and the total duration must not
+/// exceed 120 ms.
+pub struct OpusRepacketizer<'a> {
+	repacketizer_state: StateBuffer,
+	_packets: PhantomData<&'a [u8]>,
+	_not_sync: PhantomData<*mut ()>,
+}
+
+// SAFETY: The repacketizer state only holds pointers to the packets it
+// borrows, which are immutable byte slices, so it can be freely moved between
+// threads.
+unsafe impl Send for OpusRepacketizer<'_> {}
+
+impl<'a> OpusRepacketizer<'a> {
+	pub fn new() -> Self {
+		let repacketizer_size = unsafe { opus_repacketizer_get_size() as usize };
+		let mut repacketizer_state = StateBuffer::new(repacketizer_size);
+		unsafe { opus_repacketizer_init(repacketizer_state.as_mut_ptr().cast()) };
+		Self {
+			repacketizer_state,
+			_packets: PhantomData,
+			_not_sync: PhantomData,
+		}
+	}
+
+	/// Merges `packets` into a single packet holding all of their frames, such
+	/// as to turn three 20 ms packets into one 60 ms packet.
+	///
+	/// Returns
+	/// [`OpusErrorCode::InvalidPacket`](crate::error::OpusErrorCode::InvalidPacket)
+	/// if the packets don't all share the same TOC configuration and channel
+	/// count, if any of them is malformed, or if they add up to more than
+	/// 120 ms of audio, and
+	/// [`OpusErrorCode::BadArg`](crate::error::OpusErrorCode::BadArg) if there
+	/// are no packets.
+	pub fn combine_frames(packets: &[&[u8]]) -> OpusResult<Vec<u8>> {
+		let mut repacketizer = OpusRepacketizer::new();
+		let mut first_toc = None;
+		for &packet in packets {
+			packet::check_same_config(&mut first_toc, packet)?;
+			repacketizer.cat(packet)?;
+		}
+		flush_repacketizer(&mut repacketizer)
+	}
+
+	/// Adds a packet to the repacketizer.
+	///
+	/// Returns
+	/// [`OpusErrorCode::InvalidPacket`](crate::error::OpusErrorCode::InvalidPacket)
+	/// if the packet is malformed, if it doesn't match the configuration of
+	/// the packets already added, or if adding it would exceed 120 ms of
+	/// audio. The repacketizer is left unchanged on error.
+	pub fn cat(&mut self, packet: &'a [u8]) -> OpusResult<()> {
+		map_error!((), unsafe {
+			opus_repacketizer_cat(
+				self.repacketizer_state.as_mut_ptr().cast(),
+				packet.as_ptr(),
+				ffi::c_len(packet.len(), Operation::Repacketize)?,
+			)
+		})
+		.context(Operation::Repacketize)
+	}
+
+	/// Returns the total number of frames in the packets added so far.
+	pub fn nb_frames(&mut self) -> usize {
+		unsafe {
+			opus_repacketizer_get_nb_frames(self.repacketizer_state.as_mut_ptr().cast()) as usize
+		}
+	}
+
+	/// Constructs a packet from all of the frames added so far, which may be up
+	/// to `max_len` bytes long.
+	pub fn out(&mut self, max_len: usize) -> OpusResult<Vec<u8>> {
+		let max_len = ffi::c_capacity(max_len);
+		let mut data = vec![0; max_len as usize];
+		let len = map_error!(usize, unsafe {
+			opus_repacketizer_out(
+				self.repacketizer_state.as_mut_ptr().cast(),
+				data.as_mut_ptr(),
+				max_len,
+			)
+		})
+		.context(Operation::Repacketize)?;
+		data.truncate(len);
+		Ok(data)
+	}
+
+	/// Constructs a packet from the frames in the range `begin..end`, which may
+	/// be up to `max_len` bytes long.
+	///
+	/// Returns [`OpusErrorCode::BadArg`](crate::error::OpusErrorCode::BadArg)
+	/// if the range is out of bounds.
+	pub fn out_range(&mut self, begin: usize, end: usize, max_len: usize) -> OpusResult<Vec<u8>> {
+		let begin = ffi::c_len(begin, Operation::Repacketize)?;
+		let end = ffi::c_len(end, Operation::Repacketize)?;
+		let max_len = ffi::c_capacity(max_len);
+		let mut data = vec![0; max_len as usize];
+		let len = map_error!(usize, unsafe {
+			opus_repacketizer_out_range(
+				self.repacketizer_state.as_mut_ptr().cast(),
+				begin,
+				end,
+				data.as_mut_ptr(),
+				max_len,
+			)
+		})
+		.context(Operation::Repacketize)?;
+		data.truncate(len);
+		Ok(data)
+	}
+}
+
+impl Default for OpusRepacketizer<'_> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::OpusErrorCode;
+
+/// A sampling rate supported by the Opus encoder and decoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(i32)]
+pub enum SampleRate {
+	/// 8 kHz.
+	Hz8000 = 8000,
+	/// 12 kHz.
+	Hz12000 = 12000,
+	/// 16 kHz.
+	Hz16000 = 16000,
+	/// 24 kHz.
+	Hz24000 = 24000,
+	/// 48 kHz.
+	Hz48000 = 48000,
+}
+
+impl SampleRate {
+	/// Returns the sampling rate in Hz.
+	pub const fn as_i32(self) -> i32 {
+		self as i32
+	}
+}
+
+impl From<SampleRate> for i32 {
+	fn from(value: SampleRate) -> Self {
+		value.as_i32()
+	}
+}
+
+impl From<SampleRate> for u32 {
+	fn from(value: SampleRate) -> Self {
+		value.as_i32() as u32
+	}
+}
+
+impl TryFrom<i32> for SampleRate {
+	type Error = OpusErrorCode;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value {
+			8000 => Ok(Self::Hz8000),
+			12000 => Ok(Self::Hz12000),
+			16000 => Ok(Self::Hz16000),
+			24000 => Ok(Self::Hz24000),
+			48000 => Ok(Self::Hz48000),
+			_ => Err(OpusErrorCode::BadArg),
+		}
+	}
+}
+
+impl TryFrom<u32> for SampleRate {
+	type Error = OpusErrorCode;
+
+	fn try_from(value: u32) -> Result<Self, Self::Error> {
+		i32::try_from(value)
+			.map_err(|_| OpusErrorCode::BadArg)
+			.and_then(Self::try_from)
+	}
+}
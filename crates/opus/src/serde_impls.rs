@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Serde support for the public enums, which are (de)serialized by their
+//! lowercase names.
+use crate::{OpusApplication, OpusBandwidth, OpusSignal};
+use alloc::string::String;
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! impl_serde_by_name {
+	($($ty:ty),+ $(,)?) => {$(
+		impl Serialize for $ty {
+			fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_str(self.name())
+			}
+		}
+
+		impl<'de> Deserialize<'de> for $ty {
+			fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				let name = String::deserialize(deserializer)?;
+				<$ty>::try_from(name.as_str())
+					.map_err(|_| D::Error::unknown_variant(&name, <$ty>::NAMES))
+			}
+		}
+	)+};
+}
+
+impl_serde_by_name!(OpusApplication, OpusBandwidth, OpusSignal);
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::{OpusErrorCode, ParseNameError};
+use core::{
+	fmt::{Display, Error as FmtError, Formatter},
+	str::FromStr,
+};
+use meowlouder_opus_sys::{OPUS_SIGNAL_MUSIC, OPUS_SIGNAL_VOICE};
+
+/// A hint to the encoder about the type of signal being encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum OpusSignal {
+	/// Bias thresholds towards choosing LPC or Hybrid modes.
+	Voice = OPUS_SIGNAL_VOICE,
+	/// Bias thresholds towards choosing MDCT modes.
+	Music = OPUS_SIGNAL_MUSIC,
+}
+
+impl OpusSignal {
+	pub(crate) const NAMES: &'static [&'static str] = &["voice", "music"];
+
+	/// Returns the lowercase name of the signal type, as used in configuration
+	/// files.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Voice => "voice",
+			Self::Music => "music",
+		}
+	}
+}
+
+impl Display for OpusSignal {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.write_str(self.name())
+	}
+}
+
+impl From<OpusSignal> for u32 {
+	fn from(value: OpusSignal) -> Self {
+		value as u32
+	}
+}
+
+impl From<OpusSignal> for i32 {
+	fn from(value: OpusSignal) -> Self {
+		value as i32
+	}
+}
+
+impl TryFrom<i32> for OpusSignal {
+	type Error = OpusErrorCode;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value as u32 {
+			OPUS_SIGNAL_VOICE => Ok(Self::Voice),
+			OPUS_SIGNAL_MUSIC => Ok(Self::Music),
+			_ => Err(OpusErrorCode::BadArg),
+		}
+	}
+}
+
+impl FromStr for OpusSignal {
+	type Err = ParseNameError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"voice" => Ok(Self::Voice),
+			"music" => Ok(Self::Music),
+			_ => Err(ParseNameError::new("signal", s, Self::NAMES)),
+		}
+	}
+}
+
+impl TryFrom<&str> for OpusSignal {
+	type Error = ParseNameError;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		value.parse()
+	}
+}
@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+	error::{Operation, OpusError, OpusErrorCode, OpusResult},
+	util,
+};
+use alloc::{vec, vec::Vec};
+
+/// Soft clipping, for bringing float PCM back within the `[-1, 1]` range
+/// without the harsh distortion of hard clipping.
+///
+/// The clipping carries state between calls, so a single instance should be
+/// used for the whole of a stream, in order to avoid discontinuities at the
+/// boundaries between blocks. The number of channels is passed with each
+/// block, rather than fixed up front.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpusSoftClip {
+	memory: Vec<f32>,
+}
+
+impl OpusSoftClip {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Soft clips the interleaved `pcm` in-place. If all samples are already
+	/// within the `[-1, 1]` range, then nothing is changed.
+	///
+	/// Returns [`OpusErrorCode::BadArg`] if `channels` is zero, or doesn't
+	/// evenly divide the length of `pcm`. Changing the number of channels
+	/// between calls resets the clipping state.
+	pub fn process(&mut self, pcm: &mut [f32], channels: usize) -> OpusResult<()> {
+		if channels == 0 || pcm.len() % channels != 0 {
+			return Err(OpusError::new(OpusErrorCode::BadArg, Operation::SoftClip));
+		}
+		if self.memory.len() != channels {
+			self.memory = vec![0.0; channels];
+		}
+		util::soft_clip(pcm, channels, &mut self.memory)
+	}
+
+	/// Resets the clipping state, for use when starting a new stream.
+	pub fn reset(&mut self) {
+		self.memory.fill(0.0);
+	}
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MPL-2.0
+use alloc::alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout};
+use core::ptr::{self, NonNull};
+
+/// The alignment of the libopus state structs.
+///
+/// libopus allocates its states with `malloc`, which is aligned for any
+/// fundamental type, so the structs may rely on that; 16 bytes covers it on
+/// all supported platforms, including the vector types of the SIMD builds.
+const STATE_ALIGN: usize = 16;
+
+/// A zeroed heap allocation holding a libopus state struct, aligned like the
+/// ones libopus allocates itself.
+///
+/// A plain `Box<[u8]>` is only guaranteed to be byte-aligned, which isn't
+/// enough for the pointers and floats inside the states.
+pub(crate) struct StateBuffer {
+	ptr: NonNull<u8>,
+	layout: Layout,
+}
+
+// SAFETY: The buffer uniquely owns its allocation, just like a `Box<[u8]>`.
+unsafe impl Send for StateBuffer {}
+// SAFETY: Shared references only allow reading the bytes through `as_ptr`.
+unsafe impl Sync for StateBuffer {}
+
+impl StateBuffer {
+	/// Allocates a zeroed buffer of `size` bytes.
+	pub(crate) fn new(size: usize) -> Self {
+		// Zero-sized allocations aren't allowed, and never happen in practice.
+		let layout = Layout::from_size_align(size.max(1), STATE_ALIGN)
+			.expect("libopus state size overflows isize");
+		// SAFETY: The layout has a non-zero size.
+		let ptr = unsafe { alloc_zeroed(layout) };
+		let ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+		Self { ptr, layout }
+	}
+
+	pub(crate) fn as_ptr(&self) -> *const u8 {
+		self.ptr.as_ptr()
+	}
+
+	pub(crate) fn as_mut_ptr(&mut self) -> *mut u8 {
+		self.ptr.as_ptr()
+	}
+}
+
+impl Clone for StateBuffer {
+	fn clone(&self) -> Self {
+		let mut clone = Self::new(self.layout.size());
+		// SAFETY: Both buffers are `layout.size()` bytes long, and they are
+		// separate allocations.
+		unsafe {
+			ptr::copy_nonoverlapping(self.as_ptr(), clone.as_mut_ptr(), self.layout.size());
+		}
+		clone
+	}
+}
+
+impl Drop for StateBuffer {
+	fn drop(&mut self) {
+		// SAFETY: The pointer was allocated in `new` with this layout.
+		unsafe { dealloc(self.ptr.as_ptr(), self.layout) };
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use meowlouder_opus_sys::{
+		opus_decoder_get_size, opus_encoder_get_size, opus_multistream_decoder_get_size,
+		opus_multistream_encoder_get_size, opus_repacketizer_get_size,
+	};
+
+	fn assert_aligned(buffer: &StateBuffer) {
+		assert_eq!(buffer.as_ptr() as usize % STATE_ALIGN, 0);
+	}
+
+	#[test]
+	fn allocates_zeroed_and_aligned() {
+		for size in [0, 1, 3, 15, 16, 17, 255, 4097] {
+			let mut buffer = StateBuffer::new(size);
+			assert_aligned(&buffer);
+			// SAFETY: The buffer is at least `size` bytes long.
+			let bytes = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr(), size) };
+			assert!(bytes.iter().all(|&byte| byte == 0));
+			bytes.fill(0xAA);
+
+			let clone = buffer.clone();
+			assert_aligned(&clone);
+			assert_ne!(clone.as_ptr(), buffer.as_ptr());
+			// SAFETY: The clone is as long as the original.
+			let cloned = unsafe { core::slice::from_raw_parts(clone.as_ptr(), size) };
+			assert!(cloned.iter().all(|&byte| byte == 0xAA));
+		}
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore = "calls into libopus")]
+	fn aligns_libopus_states() {
+		// SAFETY: The size functions only do arithmetic on their arguments.
+		let sizes = unsafe {
+			[
+				opus_encoder_get_size(2),
+				opus_decoder_get_size(2),
+				opus_multistream_encoder_get_size(4, 2),
+				opus_multistream_decoder_get_size(4, 2),
+				opus_repacketizer_get_size(),
+			]
+		};
+		for size in sizes {
+			assert!(size > 0);
+			let buffer = StateBuffer::new(size as usize);
+			assert_aligned(&buffer);
+			assert_aligned(&buffer.clone());
+		}
+	}
+}
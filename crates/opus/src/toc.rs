@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MPL-2.0
+//! A pure-Rust parser for the TOC (table-of-contents) byte at the start of
+//! every Opus packet, as described in [RFC 6716 section 3.1](https://datatracker.ietf.org/doc/html/rfc6716#section-3.1).
+use crate::{
+	bandwidth::OpusBandwidth,
+	error::{Operation, OpusError, OpusErrorCode, OpusResult},
+	sample_rate::SampleRate,
+};
+use core::{
+	fmt::{Display, Error as FmtError, Formatter},
+	time::Duration,
+};
+
+/// The coding mode used for a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpusMode {
+	/// Linear prediction, used for speech at lower bandwidths.
+	Silk,
+	/// SILK for the low frequencies and CELT for the high frequencies.
+	Hybrid,
+	/// MDCT-based, used for music and low-latency audio.
+	Celt,
+}
+
+impl Display for OpusMode {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		f.write_str(match self {
+			Self::Silk => "SILK",
+			Self::Hybrid => "Hybrid",
+			Self::Celt => "CELT",
+		})
+	}
+}
+
+/// A parsed TOC byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OpusToc(u8);
+
+impl OpusToc {
+	/// Parses a TOC byte. Every possible byte is a valid TOC.
+	pub const fn parse(byte: u8) -> Self {
+		Self(byte)
+	}
+
+	/// Parses the TOC byte at the start of the packet.
+	pub fn of_packet(packet: &[u8]) -> OpusResult<Self> {
+		packet
+			.first()
+			.copied()
+			.map(Self::parse)
+			.ok_or(OpusError::new(
+				OpusErrorCode::InvalidPacket,
+				Operation::ParsePacket,
+			))
+	}
+
+	/// Returns the raw TOC byte.
+	pub const fn byte(self) -> u8 {
+		self.0
+	}
+
+	/// Returns the configuration number (0-31) of the packet, which
+	/// determines its mode, bandwidth, and frame duration.
+	pub const fn config(self) -> u8 {
+		self.0 >> 3
+	}
+
+	/// Returns the coding mode of the packet.
+	pub const fn mode(self) -> OpusMode {
+		match self.config() {
+			0..=11 => OpusMode::Silk,
+			12..=15 => OpusMode::Hybrid,
+			_ => OpusMode::Celt,
+		}
+	}
+
+	/// Returns the audio bandwidth of the packet.
+	pub const fn bandwidth(self) -> OpusBandwidth {
+		match self.config() {
+			0..=3 | 16..=19 => OpusBandwidth::Narrowband,
+			4..=7 => OpusBandwidth::Mediumband,
+			8..=11 | 20..=23 => OpusBandwidth::Wideband,
+			12..=13 | 24..=27 => OpusBandwidth::Superwideband,
+			_ => OpusBandwidth::Fullband,
+		}
+	}
+
+	/// Returns the duration of each frame in the packet.
+	pub const fn frame_duration(self) -> Duration {
+		Duration::from_micros(self.samples_per_frame_48k() as u64 * 1_000_000 / 48_000)
+	}
+
+	/// Returns the number of samples (per channel) in each frame of the
+	/// packet, at the given sampling rate.
+	pub const fn samples_per_frame(self, sample_rate: SampleRate) -> usize {
+		self.samples_per_frame_48k() * sample_rate.as_i32() as usize / 48_000
+	}
+
+	/// Returns whether the packet is coded as stereo.
+	pub const fn is_stereo(self) -> bool {
+		self.0 & 0b100 != 0
+	}
+
+	/// Returns the frame count code (0-3) of the packet:
+	///
+	/// - `0`: 1 frame.
+	/// - `1`: 2 frames, of equal compressed size.
+	/// - `2`: 2 frames, of different compressed sizes.
+	/// - `3`: an arbitrary number of frames.
+	pub const fn code(self) -> u8 {
+		self.0 & 0b11
+	}
+
+	const fn samples_per_frame_48k(self) -> usize {
+		let config = self.config() as usize;
+		match self.mode() {
+			OpusMode::Silk => [480, 960, 1920, 2880][config & 0b11],
+			OpusMode::Hybrid => [480, 960][config & 0b1],
+			OpusMode::Celt => [120, 240, 480, 960][config & 0b11],
+		}
+	}
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Utilities for processing PCM audio around encoding and decoding.
+
+mod channel;
+mod convert;
+mod gain;
+mod interleave;
+mod soft_clip;
+
+pub use self::{
+	channel::{
+		downmix_stereo_to_mono, downmix_stereo_to_mono_f32, upmix_mono_to_stereo,
+		upmix_mono_to_stereo_f32,
+	},
+	convert::{pcm_f32_to_i16, pcm_f32_to_i16_saturating, pcm_i16_to_f32},
+	gain::{db_to_q8, linear_to_q8, q8_to_db, GainRamp, GainRampIter},
+	interleave::{deinterleave, deinterleave_into, interleave},
+	soft_clip::soft_clip,
+};
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MPL-2.0
+use alloc::vec::Vec;
+
+/// Downmixes interleaved stereo 16-bit PCM to mono, by averaging the left
+/// and right channels.
+///
+/// # Panics
+///
+/// Panics if `stereo` has an odd number of samples.
+pub fn downmix_stereo_to_mono(stereo: &[i16]) -> Vec<i16> {
+	assert!(
+		stereo.len() % 2 == 0,
+		"stereo PCM has an odd number of samples"
+	);
+	stereo
+		.chunks_exact(2)
+		.map(|frame| ((frame[0] as i32 + frame[1] as i32) / 2) as i16)
+		.collect()
+}
+
+/// Upmixes mono 16-bit PCM to interleaved stereo, by copying each sample
+/// into both channels.
+pub fn upmix_mono_to_stereo(mono: &[i16]) -> Vec<i16> {
+	mono.iter().flat_map(|&sample| [sample, sample]).collect()
+}
+
+/// Downmixes interleaved stereo float PCM to mono, by averaging the left
+/// and right channels.
+///
+/// # Panics
+///
+/// Panics if `stereo` has an odd number of samples.
+pub fn downmix_stereo_to_mono_f32(stereo: &[f32]) -> Vec<f32> {
+	assert!(
+		stereo.len() % 2 == 0,
+		"stereo PCM has an odd number of samples"
+	);
+	stereo
+		.chunks_exact(2)
+		.map(|frame| (frame[0] + frame[1]) * 0.5)
+		.collect()
+}
+
+/// Upmixes mono float PCM to interleaved stereo, by copying each sample into
+/// both channels.
+pub fn upmix_mono_to_stereo_f32(mono: &[f32]) -> Vec<f32> {
+	mono.iter().flat_map(|&sample| [sample, sample]).collect()
+}
@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::math;
+use alloc::{vec, vec::Vec};
+
+/// Converts 16-bit PCM to float PCM in the `[-1, 1)` range.
+///
+/// # Panics
+///
+/// Panics if `src` and `dst` have different lengths.
+pub fn pcm_i16_to_f32(src: &[i16], dst: &mut [f32]) {
+	assert_eq!(
+		src.len(),
+		dst.len(),
+		"source and destination lengths differ"
+	);
+	for (dst, &src) in dst.iter_mut().zip(src) {
+		*dst = src as f32 / 32768.0;
+	}
+}
+
+/// Converts float PCM to 16-bit PCM, saturating samples outside of the
+/// `[-1, 1]` range.
+///
+/// # Panics
+///
+/// Panics if `src` and `dst` have different lengths.
+pub fn pcm_f32_to_i16(src: &[f32], dst: &mut [i16]) {
+	assert_eq!(
+		src.len(),
+		dst.len(),
+		"source and destination lengths differ"
+	);
+	for (dst, &src) in dst.iter_mut().zip(src) {
+		// Float to integer casts saturate, and turn NaN into 0.
+		*dst = math::roundf(src * 32767.0) as i16;
+	}
+}
+
+/// Converts float PCM to new 16-bit PCM, saturating samples outside of the
+/// `[-1, 1]` range.
+pub fn pcm_f32_to_i16_saturating(src: &[f32]) -> Vec<i16> {
+	let mut dst = vec![0; src.len()];
+	pcm_f32_to_i16(src, &mut dst);
+	dst
+}
@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{decoder::OpusDecoder, error::OpusResult, math};
+
+const Q8_MIN: f32 = -32768.0;
+const Q8_MAX: f32 = 32767.0;
+
+/// Converts a gain in Q7.8 dB (1/256 dB steps, as libopus takes the decoder
+/// output gain in) to decibels.
+pub fn q8_to_db(q8: i32) -> f32 {
+	q8 as f32 / 256.0
+}
+
+/// Converts a gain in decibels to Q7.8, saturating to the range libopus
+/// accepts. NaN converts to 0 dB.
+pub fn db_to_q8(db: f32) -> i32 {
+	// Float to integer casts turn NaN into 0.
+	math::roundf(db * 256.0).clamp(Q8_MIN, Q8_MAX) as i32
+}
+
+/// Converts a linear amplitude gain to Q7.8, saturating to the range libopus
+/// accepts.
+pub fn linear_to_q8(linear: f32) -> i32 {
+	db_to_q8(20.0 * math::log10f(linear))
+}
+
+/// Linearly interpolated decoder gains, in Q7.8 dB, for ramping the output
+/// gain over several frames rather than clicking from a step change.
+///
+/// Yields one gain per frame, starting at the start gain and ending at the end
+/// gain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GainRamp {
+	start: i32,
+	end: i32,
+	frames: usize,
+	next: usize,
+}
+
+impl GainRamp {
+	pub fn new(start_db: f32, end_db: f32, frames: usize) -> Self {
+		Self {
+			start: db_to_q8(start_db),
+			end: db_to_q8(end_db),
+			frames,
+			next: 0,
+		}
+	}
+
+	/// Ramps the decoder's output gain from its current gain to `target_db`
+	/// over `ramp_frames` frames.
+	///
+	/// Nothing is changed until the returned iterator is advanced, which sets
+	/// the gain for the next frame, so call [`next`](Iterator::next) before
+	/// decoding each frame.
+	pub fn apply_to_decoder(
+		decoder: &mut OpusDecoder,
+		target_db: f32,
+		ramp_frames: usize,
+	) -> OpusResult<GainRampIter<'_>> {
+		let start = decoder.gain()?;
+		Ok(GainRampIter {
+			decoder,
+			ramp: Self {
+				start,
+				end: db_to_q8(target_db),
+				frames: ramp_frames,
+				next: 0,
+			},
+		})
+	}
+}
+
+impl Iterator for GainRamp {
+	type Item = i32;
+
+	fn next(&mut self) -> Option<i32> {
+		if self.next >= self.frames {
+			return None;
+		}
+		let frame = self.next;
+		self.next += 1;
+		if self.frames == 1 {
+			return Some(self.end);
+		}
+		let step = (self.end as i64 - self.start as i64) * frame as i64 / (self.frames - 1) as i64;
+		Some((self.start as i64 + step) as i32)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let remaining = self.frames - self.next;
+		(remaining, Some(remaining))
+	}
+}
+
+impl ExactSizeIterator for GainRamp {}
+
+/// A [`GainRamp`] being applied to a decoder, from
+/// [`GainRamp::apply_to_decoder`].
+///
+/// Each call to [`next`](Iterator::next) sets the decoder's gain for the next
+/// frame, and yields the gain that was set.
+#[derive(Debug)]
+pub struct GainRampIter<'a> {
+	decoder: &'a mut OpusDecoder,
+	ramp: GainRamp,
+}
+
+impl GainRampIter<'_> {
+	/// Returns the decoder the ramp is being applied to, to decode each frame
+	/// with.
+	pub fn decoder(&mut self) -> &mut OpusDecoder {
+		self.decoder
+	}
+}
+
+impl Iterator for GainRampIter<'_> {
+	type Item = OpusResult<i32>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let gain = self.ramp.next()?;
+		Some(self.decoder.set_gain(gain).map(|()| gain))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.ramp.size_hint()
+	}
+}
+
+impl ExactSizeIterator for GainRampIter<'_> {}
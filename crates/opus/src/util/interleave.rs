@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MPL-2.0
+use alloc::{vec, vec::Vec};
+
+/// Splits interleaved PCM into a separate plane for each channel.
+///
+/// # Panics
+///
+/// Panics if `channels` is zero, or doesn't evenly divide the length of
+/// `interleaved`.
+pub fn deinterleave(interleaved: &[f32], channels: usize) -> Vec<Vec<f32>> {
+	let frame_size = frame_size(interleaved, channels);
+	let mut planes = vec![vec![0.0; frame_size]; channels];
+	let mut plane_refs: Vec<&mut [f32]> = planes.iter_mut().map(Vec::as_mut_slice).collect();
+	deinterleave_into(interleaved, channels, &mut plane_refs);
+	planes
+}
+
+/// Splits interleaved PCM into the existing `planes`, one for each channel.
+///
+/// # Panics
+///
+/// Panics if `channels` is zero, doesn't evenly divide the length of
+/// `interleaved`, or isn't the number of planes, or if any plane isn't
+/// exactly long enough to hold its channel.
+pub fn deinterleave_into(interleaved: &[f32], channels: usize, planes: &mut [&mut [f32]]) {
+	let frame_size = frame_size(interleaved, channels);
+	assert_eq!(planes.len(), channels, "wrong number of planes");
+	for (channel, plane) in planes.iter_mut().enumerate() {
+		assert_eq!(plane.len(), frame_size, "plane has the wrong length");
+		for (dst, frame) in plane.iter_mut().zip(interleaved.chunks_exact(channels)) {
+			*dst = frame[channel];
+		}
+	}
+}
+
+/// Merges a plane for each channel into interleaved PCM.
+///
+/// # Panics
+///
+/// Panics if the planes have different lengths.
+pub fn interleave(planes: &[&[f32]]) -> Vec<f32> {
+	let frame_size = planes.first().map_or(0, |plane| plane.len());
+	assert!(
+		planes.iter().all(|plane| plane.len() == frame_size),
+		"planes have different lengths"
+	);
+	(0..frame_size)
+		.flat_map(|sample| planes.iter().map(move |plane| plane[sample]))
+		.collect()
+}
+
+/// Returns the number of samples per channel in `interleaved`.
+fn frame_size(interleaved: &[f32], channels: usize) -> usize {
+	assert!(channels > 0, "no channels");
+	assert!(
+		interleaved.len() % channels == 0,
+		"length isn't a multiple of the channel count"
+	);
+	interleaved.len() / channels
+}
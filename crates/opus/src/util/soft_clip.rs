@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::error::{Operation, OpusError, OpusErrorCode, OpusResult};
+use meowlouder_opus_sys::opus_pcm_soft_clip;
+
+/// Soft clips the interleaved `pcm` in-place, bringing it back within the
+/// `[-1, 1]` range without the harsh distortion of hard clipping. If all
+/// samples are already within that range, then nothing is changed.
+///
+/// `memory` holds the clipping state for each channel, and should be kept
+/// between calls for the same stream, starting out as zeroes, in order to
+/// avoid discontinuities at the boundaries between blocks.
+/// [`OpusSoftClip`](crate::OpusSoftClip) keeps it for you.
+///
+/// Returns [`OpusErrorCode::BadArg`] if `channels` is zero, doesn't evenly
+/// divide the length of `pcm`, or isn't the length of `memory`.
+pub fn soft_clip(pcm: &mut [f32], channels: usize, memory: &mut [f32]) -> OpusResult<()> {
+	if channels == 0 || pcm.len() % channels != 0 || memory.len() != channels {
+		return Err(OpusError::new(OpusErrorCode::BadArg, Operation::SoftClip));
+	}
+	let frame_size = pcm.len() / channels;
+	if frame_size > 0 {
+		unsafe {
+			opus_pcm_soft_clip(
+				pcm.as_mut_ptr(),
+				frame_size as _,
+				channels as _,
+				memory.as_mut_ptr(),
+			)
+		};
+	}
+	Ok(())
+}
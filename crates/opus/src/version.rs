@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MPL-2.0
+use alloc::string::{String, ToString};
+use core::ffi::CStr;
+use meowlouder_opus_sys::opus_get_version_string;
+
+/// Returns the libopus version string, such as "libopus 1.5.2".
+///
+/// Fixed-point builds have "-fixed" in the version string, which
+/// [`libopus_version_info`] checks for, along with parsing the version
+/// numbers.
+pub fn libopus_version() -> &'static str {
+	let version = version_cstr().to_bytes();
+	// The string is ASCII in any libopus release, but a vendored build could
+	// put anything in it, so only the valid UTF-8 at its start is returned.
+	match core::str::from_utf8(version) {
+		Ok(version) => version,
+		Err(err) => core::str::from_utf8(&version[..err.valid_up_to()]).unwrap_or_default(),
+	}
+}
+
+/// The version of the linked libopus, as returned by [`libopus_version_info`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LibopusVersion {
+	/// The major version, or 0 if it couldn't be parsed.
+	pub major: u32,
+	/// The minor version, or 0 if it couldn't be parsed.
+	pub minor: u32,
+	/// The patch version, which is 0 for releases such as "1.5" without one,
+	/// or if it couldn't be parsed.
+	pub patch: u32,
+	/// The full version string, such as "libopus 1.5.2".
+	pub version: String,
+	/// Whether libopus was built with fixed-point rather than floating-point
+	/// arithmetic, which is marked by "-fixed" in the version string.
+	pub is_fixed_point: bool,
+}
+
+impl LibopusVersion {
+	/// Parses a libopus version string, such as "libopus 1.5.2-fixed".
+	///
+	/// Version strings from builds of a git checkout, such as
+	/// "libopus 1.5.2-12-gabcdef0", are parsed as the release they're based on.
+	/// Any numbers which can't be parsed are 0.
+	pub fn parse(version: &str) -> Self {
+		let number = version.strip_prefix("libopus ").unwrap_or(version);
+		let number = number.strip_prefix('v').unwrap_or(number);
+		let number = number
+			.split(|c: char| !c.is_ascii_digit() && c != '.')
+			.next()
+			.unwrap_or_default();
+		let mut parts = number.split('.').map(|part| part.parse().unwrap_or(0));
+		Self {
+			major: parts.next().unwrap_or(0),
+			minor: parts.next().unwrap_or(0),
+			patch: parts.next().unwrap_or(0),
+			version: version.to_string(),
+			is_fixed_point: version.contains("-fixed"),
+		}
+	}
+
+	/// Returns whether this is at least the given version.
+	pub fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+		(self.major, self.minor, self.patch) >= (major, minor, patch)
+	}
+}
+
+/// Returns the version of the linked libopus, parsed from its version
+/// string.
+///
+/// Unlike [`libopus_version`], any invalid UTF-8 in the version string is
+/// replaced rather than cut off.
+pub fn libopus_version_info() -> LibopusVersion {
+	LibopusVersion::parse(&version_cstr().to_string_lossy())
+}
+
+fn version_cstr() -> &'static CStr {
+	// SAFETY: libopus returns a pointer to a static, nul-terminated string.
+	unsafe { CStr::from_ptr(opus_get_version_string()) }
+}
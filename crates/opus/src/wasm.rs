@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MPL-2.0
+//! JavaScript bindings for encoding and decoding, with the `wasm-bindgen`
+//! feature.
+//!
+//! PCM is passed as interleaved `Float32Array`s, as used by the Web Audio
+//! API, and packets as `Uint8Array`s.
+use crate::{application::OpusApplication, decoder::OpusDecoder, encode::OpusEncoder};
+use js_sys::{Float32Array, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+/// An Opus encoder, exported to JavaScript as `OpusEncoder`.
+#[wasm_bindgen(js_name = OpusEncoder)]
+pub struct WasmOpusEncoder(OpusEncoder);
+
+#[wasm_bindgen(js_class = OpusEncoder)]
+impl WasmOpusEncoder {
+	/// Creates a new encoder, for the application given by its name, such as
+	/// "audio" or "voip".
+	#[wasm_bindgen(constructor)]
+	pub fn new(
+		sample_rate: i32,
+		channels: i32,
+		application: &str,
+	) -> Result<WasmOpusEncoder, JsError> {
+		let application = OpusApplication::try_from(application)?;
+		Ok(Self(OpusEncoder::new_raw(
+			sample_rate,
+			channels,
+			application,
+		)?))
+	}
+
+	/// Encodes a frame of `frame_size` samples (per channel) of interleaved
+	/// PCM into a packet.
+	pub fn encode(&mut self, pcm: &Float32Array, frame_size: usize) -> Result<Uint8Array, JsError> {
+		let packet = self.0.encode(&pcm.to_vec(), frame_size)?;
+		Ok(Uint8Array::from(packet.as_slice()))
+	}
+}
+
+/// An Opus decoder, exported to JavaScript as `OpusDecoder`.
+#[wasm_bindgen(js_name = OpusDecoder)]
+pub struct WasmOpusDecoder(OpusDecoder);
+
+#[wasm_bindgen(js_class = OpusDecoder)]
+impl WasmOpusDecoder {
+	/// Creates a new decoder.
+	#[wasm_bindgen(constructor)]
+	pub fn new(sample_rate: i32, channels: i32) -> Result<WasmOpusDecoder, JsError> {
+		Ok(Self(OpusDecoder::new_raw(sample_rate, channels)?))
+	}
+
+	/// Decodes a packet into interleaved PCM, or conceals a lost one if
+	/// `packet` is null or undefined, decoding up to `frame_size` samples (per
+	/// channel).
+	pub fn decode(
+		&mut self,
+		packet: Option<Uint8Array>,
+		frame_size: usize,
+	) -> Result<Float32Array, JsError> {
+		let packet = packet.map(|packet| packet.to_vec());
+		let pcm = self.0.decode_float(packet, frame_size, false)?;
+		Ok(Float32Array::from(pcm.as_slice()))
+	}
+}
@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::encoder;
+use meowlouder_opus::{AdaptiveBitrateController, Channels, FrameDuration};
+
+#[test]
+fn backs_off_under_sustained_loss() {
+	let mut controller = AdaptiveBitrateController::new(64000, 16000, 128000);
+	let mut encoder = encoder(Channels::Stereo);
+	controller
+		.apply_to(&mut encoder)
+		.expect("failed to set bitrate");
+	assert_eq!(encoder.bitrate(), Ok(64000));
+
+	// Five seconds of 20 ms packets, with one in ten lost, reported every
+	// 500 ms.
+	let mut bitrates = Vec::new();
+	for _ in 0..10 {
+		bitrates.push(controller.update(25, 3, 80.0));
+		controller
+			.apply_to(&mut encoder)
+			.expect("failed to set bitrate");
+	}
+	assert!(
+		bitrates.windows(2).all(|pair| pair[1] <= pair[0]),
+		"{bitrates:?}"
+	);
+	assert_eq!(bitrates[0], 54400);
+	assert_eq!(bitrates[9], 16000);
+	assert_eq!(encoder.bitrate(), Ok(16000));
+}
+
+#[test]
+fn recovers_without_loss() {
+	let mut controller = AdaptiveBitrateController::new(32000, 16000, 40000);
+	// 1 kbps for every second without loss, and 5% still counts as stable.
+	assert_eq!(controller.update(50, 0, 50.0), 33000);
+	assert_eq!(controller.update(100, 5, 50.0), 35000);
+	controller.set_frame_duration(FrameDuration::Ms60);
+	assert_eq!(controller.update(50, 0, 50.0), 38000);
+	assert_eq!(controller.update(500, 0, 50.0), 40000);
+	// A report without any packets changes nothing.
+	assert_eq!(controller.update(0, 0, 50.0), 40000);
+	// More packets lost than sent is total loss.
+	assert_eq!(controller.update(10, 20, 50.0), 34000);
+}
+
+#[test]
+fn clamps_to_bounds() {
+	let controller = AdaptiveBitrateController::new(500000, 6000, 96000);
+	assert_eq!(controller.bitrate(), 96000);
+	assert_eq!(controller.min_bitrate(), 6000);
+	assert_eq!(controller.max_bitrate(), 96000);
+	assert_eq!(
+		AdaptiveBitrateController::new(0, 6000, 96000).bitrate(),
+		6000
+	);
+}
+
+#[test]
+#[should_panic(expected = "minimum bitrate is greater than the maximum")]
+fn rejects_inverted_bounds() {
+	AdaptiveBitrateController::new(32000, 64000, 16000);
+}
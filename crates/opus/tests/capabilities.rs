@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use meowlouder_opus::{capabilities, has_deep_plc, has_dred};
+
+#[test]
+#[cfg(feature = "osce")]
+fn decodes_with_osce() {
+	use common::{encoder, sine, FRAME_SIZE};
+	use meowlouder_opus::{Channels, OpusDecoder, SampleRate};
+
+	let capabilities = capabilities();
+	assert!(capabilities.osce);
+	assert!(capabilities.deep_plc);
+
+	let mut encoder = encoder(Channels::Mono);
+	encoder.set_bitrate(12000).expect("failed to set bitrate");
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Mono).expect("failed to create decoder");
+	// Enables the speech enhancement.
+	decoder.set_complexity(7).expect("failed to set complexity");
+	for frame in 0..20 {
+		let pcm = sine(FRAME_SIZE, 1, 220.0, frame * FRAME_SIZE);
+		let data = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		let decoded = decoder
+			.decode(Some(&data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(decoded.len(), FRAME_SIZE);
+	}
+	// And the deep PLC.
+	assert_eq!(
+		decoder.conceal(FRAME_SIZE).map(|pcm| pcm.len()),
+		Ok(FRAME_SIZE)
+	);
+}
+
+#[test]
+fn capabilities_match_features() {
+	// The bundled libopus is built with exactly the features enabled.
+	assert_eq!(has_dred(), cfg!(feature = "dred"));
+	assert_eq!(
+		has_deep_plc(),
+		cfg!(any(feature = "dred", feature = "osce"))
+	);
+	let capabilities = capabilities();
+	assert_eq!(capabilities.dred, has_dred());
+	assert_eq!(capabilities.deep_plc, has_deep_plc());
+	assert_eq!(capabilities.osce, cfg!(feature = "osce"));
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::util::{
+	downmix_stereo_to_mono, downmix_stereo_to_mono_f32, upmix_mono_to_stereo,
+	upmix_mono_to_stereo_f32,
+};
+
+#[test]
+fn downmixes_stereo_to_mono() {
+	assert_eq!(downmix_stereo_to_mono(&[1000, -1000]), [0]);
+	assert_eq!(downmix_stereo_to_mono(&[100, 200, -300, -301]), [150, -300]);
+	// Averaging full scale samples doesn't overflow.
+	assert_eq!(
+		downmix_stereo_to_mono(&[i16::MAX, i16::MAX, i16::MIN, i16::MIN]),
+		[i16::MAX, i16::MIN]
+	);
+	assert_eq!(downmix_stereo_to_mono_f32(&[0.5, -0.5, 1.0, 0.5]), [
+		0.0, 0.75
+	]);
+	assert!(downmix_stereo_to_mono(&[]).is_empty());
+}
+
+#[test]
+fn upmixes_mono_to_stereo() {
+	assert_eq!(upmix_mono_to_stereo(&[500]), [500, 500]);
+	assert_eq!(upmix_mono_to_stereo(&[1, -2]), [1, 1, -2, -2]);
+	assert_eq!(upmix_mono_to_stereo_f32(&[0.25]), [0.25, 0.25]);
+	assert_eq!(
+		downmix_stereo_to_mono(&upmix_mono_to_stereo(&[7, -7, 0])),
+		[7, -7, 0]
+	);
+}
+
+#[test]
+#[should_panic(expected = "odd number of samples")]
+fn rejects_odd_stereo() {
+	downmix_stereo_to_mono(&[1, 2, 3]);
+}
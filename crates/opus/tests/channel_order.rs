@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::channel_order::{
+	smpte_to_vorbis, smpte_to_vorbis_permutation, vorbis_to_smpte,
+};
+
+#[test]
+fn matches_rfc_7845_for_5_1() {
+	// RFC 7845 section 5.1.1.2 orders 5.1 audio as front left, center, front
+	// right, rear left, rear right, LFE.
+	let mut frames = ["FL", "FR", "C", "LFE", "RL", "RR"].repeat(2);
+	smpte_to_vorbis(6, &mut frames);
+	assert_eq!(frames, ["FL", "C", "FR", "RL", "RR", "LFE"].repeat(2));
+	vorbis_to_smpte(6, &mut frames);
+	assert_eq!(frames, ["FL", "FR", "C", "LFE", "RL", "RR"].repeat(2));
+	assert_eq!(
+		smpte_to_vorbis_permutation(6),
+		Some(&[0, 2, 1, 4, 5, 3][..])
+	);
+}
+
+#[test]
+fn conversions_undo_each_other() {
+	for channels in 1..=8 {
+		let permutation =
+			smpte_to_vorbis_permutation(channels).expect("no permutation for 1 to 8 channels");
+		let mut sorted = permutation.to_vec();
+		sorted.sort_unstable();
+		assert_eq!(
+			sorted,
+			(0..channels).collect::<Vec<_>>(),
+			"{channels} channels"
+		);
+
+		let original = (0..channels as i16 * 3).collect::<Vec<_>>();
+		let mut pcm = original.clone();
+		smpte_to_vorbis(channels, &mut pcm);
+		vorbis_to_smpte(channels, &mut pcm);
+		assert_eq!(pcm, original, "{channels} channels");
+
+		let original = original
+			.iter()
+			.map(|&sample| f32::from(sample))
+			.collect::<Vec<_>>();
+		let mut pcm = original.clone();
+		vorbis_to_smpte(channels, &mut pcm);
+		smpte_to_vorbis(channels, &mut pcm);
+		assert_eq!(pcm, original, "{channels} channels");
+	}
+	// Mono, stereo and quadraphonic are the same in both orders.
+	for channels in [1, 2, 4] {
+		let permutation = smpte_to_vorbis_permutation(channels).unwrap();
+		assert!(permutation.iter().enumerate().all(|(i, &j)| i == j));
+	}
+	assert_eq!(smpte_to_vorbis_permutation(0), None);
+	assert_eq!(smpte_to_vorbis_permutation(9), None);
+}
+
+#[test]
+#[should_panic(expected = "whole number")]
+fn rejects_partial_frames() {
+	smpte_to_vorbis(6, &mut [0i16; 7]);
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::{Channels, OpusApplication, OpusDecoder, OpusEncoder, SampleRate};
+
+const FRAME_SIZE: usize = 960;
+
+/// A frame of a stereo sweep, which changes from frame to frame so the codec
+/// state keeps evolving.
+fn frame(index: usize) -> Vec<i16> {
+	(0..FRAME_SIZE)
+		.flat_map(|i| {
+			let t = (index * FRAME_SIZE + i) as f32 / 48000.0;
+			let left = (t * (220.0 + 200.0 * t) * std::f32::consts::TAU).sin();
+			let right = (t * 330.0 * std::f32::consts::TAU).sin();
+			[(left * 12000.0) as i16, (right * 8000.0) as i16]
+		})
+		.collect()
+}
+
+fn encoder() -> OpusEncoder {
+	let mut encoder = OpusEncoder::new(
+		SampleRate::Hz48000,
+		Channels::Stereo,
+		OpusApplication::Audio,
+	)
+	.expect("failed to create encoder");
+	encoder.set_bitrate(64000).expect("failed to set bitrate");
+	encoder
+}
+
+/// Encodes the same frame with each encoder, checking that they all produce the
+/// same packet and final range.
+fn encode_all(encoders: &mut [&mut OpusEncoder], index: usize) -> Vec<u8> {
+	let pcm = frame(index);
+	let mut outputs = encoders.iter_mut().map(|encoder| {
+		let packet = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		let final_range = encoder.final_range().expect("failed to get final range");
+		(packet, final_range)
+	});
+	let first = outputs.next().expect("no encoders");
+	for output in outputs {
+		assert_eq!(output, first, "encoders diverged at frame {index}");
+	}
+	first.0
+}
+
+#[test]
+fn encoder_clone_is_bit_exact() {
+	let mut reference = encoder();
+	let mut original = encoder();
+	for index in 0..10 {
+		encode_all(&mut [&mut reference, &mut original], index);
+	}
+	let mut clone = original.clone();
+	assert_eq!(clone.timestamp(), original.timestamp());
+	for index in 10..50 {
+		encode_all(&mut [&mut reference, &mut original, &mut clone], index);
+	}
+}
+
+#[test]
+fn encoder_clone_is_independent() {
+	let mut original = encoder();
+	for index in 0..10 {
+		original.encode(&frame(index), FRAME_SIZE).unwrap();
+	}
+	let mut clone = original.clone();
+	let mut reference = original.clone();
+	// Encoding with the clone mustn't affect the original.
+	for index in 0..5 {
+		clone.encode(&frame(100 + index), FRAME_SIZE).unwrap();
+	}
+	for index in 10..20 {
+		encode_all(&mut [&mut reference, &mut original], index);
+	}
+	drop(clone);
+	encode_all(&mut [&mut reference, &mut original], 20);
+}
+
+#[test]
+fn encoder_clone_encodes_different_pcm_differently() {
+	let mut original = encoder();
+	for index in 0..10 {
+		original.encode(&frame(index), FRAME_SIZE).unwrap();
+	}
+	let mut clone = original.clone();
+	let mut reference = original.clone();
+	let silence = vec![0; FRAME_SIZE * 2];
+	for index in 10..20 {
+		let from_clone = clone.encode(&silence, FRAME_SIZE).unwrap();
+		let from_original = encode_all(&mut [&mut reference, &mut original], index);
+		assert_ne!(
+			from_original, from_clone,
+			"outputs matched at frame {index}"
+		);
+	}
+}
+
+#[test]
+fn decoder_clone_is_bit_exact() {
+	let mut encoder = encoder();
+	let packets: Vec<_> = (0..50)
+		.map(|index| encoder.encode(&frame(index), FRAME_SIZE).unwrap())
+		.collect();
+	let new_decoder = || OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).unwrap();
+	let mut reference = new_decoder();
+	let mut original = new_decoder();
+	let mut clone = None;
+	for (index, packet) in packets.iter().enumerate() {
+		if index == 10 {
+			clone = Some(original.clone());
+		}
+		// Lose every seventh packet, to cover concealment too.
+		let data = (index % 7 != 6).then_some(packet);
+		let expected = reference.decode(data, FRAME_SIZE, false).unwrap();
+		let final_range = reference.final_range().unwrap();
+		let decoders = std::iter::once(&mut original).chain(clone.as_mut());
+		for decoder in decoders {
+			assert_eq!(
+				decoder.decode(data, FRAME_SIZE, false).unwrap(),
+				expected,
+				"decoders diverged at packet {index}"
+			);
+			assert_eq!(decoder.final_range().unwrap(), final_range);
+		}
+	}
+}
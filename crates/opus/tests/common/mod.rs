@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Helpers shared between the integration tests.
+#![allow(dead_code)]
+use meowlouder_opus::{Channels, OpusApplication, OpusDecoder, OpusEncoder, SampleRate};
+
+/// 20 ms at 48 kHz.
+pub const FRAME_SIZE: usize = 960;
+
+/// Generates `frame_size` samples (per channel) of interleaved float PCM,
+/// with a sine tone of `freq` Hz at 48 kHz in each channel, starting
+/// `offset` samples into the tone so consecutive frames join up.
+pub fn sine_f32(frame_size: usize, channels: usize, freq: f32, offset: usize) -> Vec<f32> {
+	(offset..offset + frame_size)
+		.flat_map(|i| {
+			let t = i as f32 / 48000.0;
+			let sample = 0.5 * (t * freq * std::f32::consts::TAU).sin();
+			std::iter::repeat(sample).take(channels)
+		})
+		.collect()
+}
+
+/// Generates a sine tone like [`sine_f32`], as 16-bit PCM.
+pub fn sine(frame_size: usize, channels: usize, freq: f32, offset: usize) -> Vec<i16> {
+	sine_f32(frame_size, channels, freq, offset)
+		.into_iter()
+		.map(|sample| (sample * 32767.0) as i16)
+		.collect()
+}
+
+/// Creates a 48 kHz encoder for general audio.
+pub fn encoder(channels: Channels) -> OpusEncoder {
+	OpusEncoder::new(SampleRate::Hz48000, channels, OpusApplication::Audio)
+		.expect("failed to create encoder")
+}
+
+/// Creates a 48 kHz decoder.
+pub fn decoder(channels: Channels) -> OpusDecoder {
+	OpusDecoder::new(SampleRate::Hz48000, channels).expect("failed to create decoder")
+}
+
+/// Encodes `frames` consecutive 20 ms frames of a 440 Hz tone.
+pub fn encode_tone(encoder: &mut OpusEncoder, frames: usize) -> Vec<Vec<u8>> {
+	let channels = encoder.channels();
+	(0..frames)
+		.map(|frame| {
+			let pcm = sine(FRAME_SIZE, channels, 440.0, frame * FRAME_SIZE);
+			encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode")
+		})
+		.collect()
+}
+
+/// Returns the energy of the PCM, as the sum of its squared samples.
+pub fn energy(pcm: &[i16]) -> f64 {
+	pcm.iter().map(|&sample| f64::from(sample).powi(2)).sum()
+}
@@ -0,0 +1,367 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Conformance tests against the official Opus test vectors, from
+//! <https://opus-codec.org/testvectors/>.
+//!
+//! The vectors are too large to include in the repository, so extract
+//! `opus_testvectors-rfc8251.tar.gz` somewhere, and point the
+//! `OPUS_TESTVECTORS` environment variable to the directory with the
+//! `testvectorNN.bit` files (default: `tests/testvectors`), then run:
+//!
+//! ```sh
+//! cargo test -p meowlouder-opus --release --features conformance-tests --test conformance
+//! ```
+//!
+//! Like upstream's `run_vectors.sh`, a vector passes if the decoded output
+//! scores at least 0% on `opus_compare`'s quality metric against either
+//! reference output. The metric is slow to compute without optimizations.
+#![cfg(feature = "conformance-tests")]
+use meowlouder_opus::{Channels, OpusApplication, OpusDecoder, OpusEncoder, SampleRate};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// The largest frame a packet can hold: 120 ms at 48 kHz.
+const MAX_FRAME_SIZE: usize = 5760;
+
+/// A packet from a `.bit` file, along with the final range of the encoder
+/// after encoding it.
+struct Packet {
+	data: Vec<u8>,
+	final_range: u32,
+}
+
+fn vector_dir() -> PathBuf {
+	std::env::var_os("OPUS_TESTVECTORS")
+		.map(PathBuf::from)
+		.unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testvectors"))
+}
+
+fn read_vector_file(name: &str) -> Option<Vec<u8>> {
+	let path = vector_dir().join(name);
+	match fs::read(&path) {
+		Ok(data) => Some(data),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+		Err(err) => panic!("failed to read {}: {err}", path.display()),
+	}
+}
+
+/// Parses a `.bit` file, as written by `opus_demo`: each packet is preceded
+/// by its length and the encoder's final range, both as big-endian 32-bit
+/// integers.
+fn parse_bitstream(mut data: &[u8]) -> Vec<Packet> {
+	let mut packets = Vec::new();
+	while !data.is_empty() {
+		let (header, rest) = data.split_at(8);
+		let len = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+		let final_range = u32::from_be_bytes(header[4..].try_into().unwrap());
+		let (packet, rest) = rest.split_at(len);
+		packets.push(Packet {
+			data: packet.to_vec(),
+			final_range,
+		});
+		data = rest;
+	}
+	packets
+}
+
+fn parse_pcm(data: &[u8]) -> Vec<i16> {
+	data.chunks_exact(2)
+		.map(|sample| i16::from_le_bytes([sample[0], sample[1]]))
+		.collect()
+}
+
+/// Decodes a bitstream as stereo at 48 kHz, checking the decoder's final range
+/// against the encoder's after each packet.
+fn decode_bitstream(name: &str, packets: &[Packet]) -> Vec<i16> {
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).expect("failed to create decoder");
+	let mut pcm = Vec::new();
+	for (index, packet) in packets.iter().enumerate() {
+		// Lost packets are stored as empty ones, and concealed for as long as
+		// the last packet.
+		let data = (!packet.data.is_empty()).then_some(&packet.data);
+		let frame_size = match data {
+			Some(_) => MAX_FRAME_SIZE,
+			None => decoder
+				.last_packet_duration()
+				.expect("failed to get duration") as usize,
+		};
+		let decoded = decoder
+			.decode(data, frame_size, false)
+			.unwrap_or_else(|err| panic!("{name}: failed to decode packet {index}: {err}"));
+		pcm.extend_from_slice(&decoded);
+		if data.is_some() {
+			let final_range = decoder.final_range().expect("failed to get final range");
+			assert_eq!(
+				final_range, packet.final_range,
+				"{name}: final range mismatch after packet {index}"
+			);
+		}
+	}
+	pcm
+}
+
+/// Returns the position of the first byte that differs between the decoded
+/// and reference PCM, as written to a little-endian 16-bit PCM file.
+fn first_difference(decoded: &[i16], reference: &[i16]) -> Option<usize> {
+	match decoded.iter().zip(reference).position(|(a, b)| a != b) {
+		Some(sample) => {
+			let byte =
+				(decoded[sample].to_le_bytes()[0] == reference[sample].to_le_bytes()[0]) as usize;
+			Some(sample * 2 + byte)
+		}
+		None if decoded.len() != reference.len() => Some(decoded.len().min(reference.len()) * 2),
+		None => None,
+	}
+}
+
+/// The bands the quality metric is computed over, in bins of a 480-sample
+/// DFT, derived from the CELT bands.
+const BANDS: [usize; 22] = [
+	0, 2, 4, 6, 8, 10, 12, 14, 16, 20, 24, 28, 32, 40, 48, 56, 68, 80, 96, 120, 156, 200,
+];
+const NBANDS: usize = BANDS.len() - 1;
+const NFREQS: usize = 240;
+const WINDOW_SIZE: usize = 480;
+const WINDOW_STEP: usize = 120;
+
+/// Computes the power spectrum of each windowed frame of interleaved stereo
+/// `pcm`, along with the average power of each band if `band_power` is given.
+fn band_energy(pcm: &[f32], nframes: usize, mut band_power: Option<&mut [f32]>) -> Vec<f32> {
+	use std::f32::consts::PI;
+	let window: Vec<f32> = (0..WINDOW_SIZE)
+		.map(|i| 0.5 - 0.5 * ((2.0 * PI / (WINDOW_SIZE - 1) as f32) * i as f32).cos())
+		.collect();
+	let cos: Vec<f32> = (0..WINDOW_SIZE)
+		.map(|i| ((2.0 * PI / WINDOW_SIZE as f32) * i as f32).cos())
+		.collect();
+	let sin: Vec<f32> = (0..WINDOW_SIZE)
+		.map(|i| ((2.0 * PI / WINDOW_SIZE as f32) * i as f32).sin())
+		.collect();
+	let mut spectrum = vec![0.0; nframes * NFREQS * 2];
+	let mut x = vec![0.0; WINDOW_SIZE * 2];
+	for frame in 0..nframes {
+		for channel in 0..2 {
+			for i in 0..WINDOW_SIZE {
+				x[channel * WINDOW_SIZE + i] =
+					window[i] * pcm[(frame * WINDOW_STEP + i) * 2 + channel];
+			}
+		}
+		for band in 0..NBANDS {
+			let mut power = [0.0f32; 2];
+			for freq in BANDS[band]..BANDS[band + 1] {
+				for channel in 0..2 {
+					let (mut re, mut im) = (0.0f32, 0.0f32);
+					let mut t = 0;
+					for i in 0..WINDOW_SIZE {
+						re += cos[t] * x[channel * WINDOW_SIZE + i];
+						im -= sin[t] * x[channel * WINDOW_SIZE + i];
+						t = (t + freq) % WINDOW_SIZE;
+					}
+					let bin = &mut spectrum[(frame * NFREQS + freq) * 2 + channel];
+					*bin = re * re + im * im + 100000.0;
+					power[channel] += *bin;
+				}
+			}
+			if let Some(band_power) = band_power.as_deref_mut() {
+				for channel in 0..2 {
+					band_power[(frame * NBANDS + band) * 2 + channel] =
+						power[channel] / (BANDS[band + 1] - BANDS[band]) as f32;
+				}
+			}
+		}
+	}
+	spectrum
+}
+
+/// Rates how close the decoded output is to the reference, as upstream's
+/// `opus_compare -s` does for stereo output at 48 kHz. Returns the Opus
+/// quality metric, in percent, which is negative if the output isn't
+/// conformant, or `None` if the sample counts differ or are too small to
+/// compare.
+///
+/// The float decoder isn't bit-exact across compilers and SIMD code paths, so
+/// this, rather than an exact match, decides whether a vector passes.
+fn quality(reference: &[i16], decoded: &[i16]) -> Option<f32> {
+	if reference.len() != decoded.len() {
+		return None;
+	}
+	let x: Vec<f32> = reference.iter().map(|&sample| f32::from(sample)).collect();
+	let y: Vec<f32> = decoded.iter().map(|&sample| f32::from(sample)).collect();
+	let nframes = (x.len() / 2).saturating_sub(WINDOW_SIZE - WINDOW_STEP) / WINDOW_STEP;
+	if nframes == 0 {
+		return None;
+	}
+	let mut xb = vec![0.0; nframes * NBANDS * 2];
+	let mut xs = band_energy(&x, nframes, Some(&mut xb));
+	let mut ys = band_energy(&y, nframes, None);
+
+	let xb_at = |frame: usize, band: usize, channel: usize| (frame * NBANDS + band) * 2 + channel;
+	let bin_at = |frame: usize, freq: usize, channel: usize| (frame * NFREQS + freq) * 2 + channel;
+	for frame in 0..nframes {
+		// Frequency masking, with a slope of 10 dB/Bark from low to high, and
+		// 15 dB/Bark from high to low.
+		for band in 1..NBANDS {
+			for channel in 0..2 {
+				xb[xb_at(frame, band, channel)] += 0.1 * xb[xb_at(frame, band - 1, channel)];
+			}
+		}
+		for band in (0..NBANDS - 1).rev() {
+			for channel in 0..2 {
+				xb[xb_at(frame, band, channel)] += 0.03 * xb[xb_at(frame, band + 1, channel)];
+			}
+		}
+		// Temporal masking, with a slope of -3 dB/2.5 ms.
+		if frame > 0 {
+			for band in 0..NBANDS {
+				for channel in 0..2 {
+					xb[xb_at(frame, band, channel)] += 0.5 * xb[xb_at(frame - 1, band, channel)];
+				}
+			}
+		}
+		// Allowing some cross-talk.
+		for band in 0..NBANDS {
+			let (left, right) = (xb[xb_at(frame, band, 0)], xb[xb_at(frame, band, 1)]);
+			xb[xb_at(frame, band, 0)] += 0.01 * right;
+			xb[xb_at(frame, band, 1)] += 0.01 * left;
+		}
+		for band in 0..NBANDS {
+			for freq in BANDS[band]..BANDS[band + 1] {
+				for channel in 0..2 {
+					let mask = 0.1 * xb[xb_at(frame, band, channel)];
+					xs[bin_at(frame, freq, channel)] += mask;
+					ys[bin_at(frame, freq, channel)] += mask;
+				}
+			}
+		}
+	}
+
+	// Adds each frame to the next, to make the comparison slightly less
+	// sensitive.
+	for freq in 0..BANDS[NBANDS] {
+		for channel in 0..2 {
+			let (mut x_prev, mut y_prev) =
+				(xs[bin_at(0, freq, channel)], ys[bin_at(0, freq, channel)]);
+			for frame in 1..nframes {
+				let bin = bin_at(frame, freq, channel);
+				let (x_cur, y_cur) = (xs[bin], ys[bin]);
+				xs[bin] += x_prev;
+				ys[bin] += y_prev;
+				(x_prev, y_prev) = (x_cur, y_cur);
+			}
+		}
+	}
+
+	let mut err = 0.0f64;
+	for frame in 0..nframes {
+		let mut frame_err = 0.0f64;
+		for band in 0..NBANDS {
+			let mut band_err = 0.0f64;
+			for freq in BANDS[band]..BANDS[band + 1] {
+				for channel in 0..2 {
+					let bin = bin_at(frame, freq, channel);
+					let ratio = ys[bin] / xs[bin];
+					let mut im = (f64::from(ratio) - f64::from(ratio).ln() - 1.0) as f32;
+					// Less sensitive around the SILK/CELT crossover, to allow for
+					// mode freedom in the filters.
+					if (79..=81).contains(&freq) {
+						im *= 0.1;
+					}
+					if freq == 80 {
+						im *= 0.1;
+					}
+					band_err += f64::from(im);
+				}
+			}
+			band_err /= ((BANDS[band + 1] - BANDS[band]) * 2) as f64;
+			frame_err += band_err * band_err;
+		}
+		frame_err /= NBANDS as f64;
+		frame_err *= frame_err;
+		err += frame_err * frame_err;
+	}
+	let err = (err / nframes as f64).powf(1.0 / 16.0);
+	Some((100.0 * (1.0 - 0.5 * (1.0 + err).ln() / 1.13f64.ln())) as f32)
+}
+
+/// Encodes the reference output of a vector, checking that the decoder stays
+/// in sync with the encoder after every packet.
+fn encode_reference(name: &str, pcm: &[i16]) {
+	const FRAME_SIZE: usize = 960;
+	let mut encoder = OpusEncoder::new(
+		SampleRate::Hz48000,
+		Channels::Stereo,
+		OpusApplication::Audio,
+	)
+	.expect("failed to create encoder");
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).expect("failed to create decoder");
+	for (index, frame) in pcm.chunks_exact(FRAME_SIZE * 2).enumerate() {
+		let packet = encoder
+			.encode(frame, FRAME_SIZE)
+			.unwrap_or_else(|err| panic!("{name}: failed to encode frame {index}: {err}"));
+		decoder
+			.decode(Some(&packet), FRAME_SIZE, false)
+			.unwrap_or_else(|err| panic!("{name}: failed to decode frame {index}: {err}"));
+		assert_eq!(
+			encoder.final_range().expect("failed to get final range"),
+			decoder.final_range().expect("failed to get final range"),
+			"{name}: encoder and decoder final range mismatch at frame {index}"
+		);
+	}
+}
+
+#[test]
+fn test_vectors() {
+	let mut tested = 0;
+	for number in 1..=12 {
+		let name = format!("testvector{number:02}");
+		let Some(bitstream) = read_vector_file(&format!("{name}.bit")) else {
+			continue;
+		};
+		let packets = parse_bitstream(&bitstream);
+		let decoded = decode_bitstream(&name, &packets);
+		// Either the original output from RFC 6716, or the updated output from
+		// RFC 8251, is conformant.
+		let references: Vec<_> = [format!("{name}.dec"), format!("{name}m.dec")]
+			.iter()
+			.filter_map(|file| read_vector_file(file))
+			.map(|data| parse_pcm(&data))
+			.collect();
+		assert!(!references.is_empty(), "{name}: no reference output found");
+		// A bit-exact match passes outright, and otherwise the best quality of
+		// the two decides.
+		let differences: Vec<_> = references
+			.iter()
+			.map(|reference| first_difference(&decoded, reference))
+			.collect();
+		if let Some(Some(position)) = differences.iter().copied().min() {
+			let quality = references
+				.iter()
+				.filter_map(|reference| quality(reference, &decoded))
+				.max_by(f32::total_cmp);
+			match quality {
+				Some(quality) if quality >= 0.0 => println!(
+					"{name}: decoded output differs from the reference at byte {position}, but \
+					 passes with a quality of {quality:.1}%"
+				),
+				Some(quality) => panic!(
+					"{name}: decoded output differs from the reference at byte {position}, with a \
+					 quality of {quality:.1}%"
+				),
+				None => panic!(
+					"{name}: decoded output differs from the reference at byte {position}, and \
+					 has a different length"
+				),
+			}
+		}
+		encode_reference(&name, &references[0]);
+		tested += 1;
+	}
+	assert!(
+		tested > 0,
+		"no test vectors found in {}; set OPUS_TESTVECTORS to the directory they were extracted to",
+		vector_dir().display()
+	);
+}
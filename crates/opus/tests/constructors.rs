@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Checks that constructors reject invalid parameters with an error rather
+//! than relying on debug assertions, so these hold in release builds too.
+use meowlouder_opus::{
+	error::{OpusError, OpusErrorCode},
+	ChannelMapping, Channels, OpusApplication, OpusDecoder, OpusEncoder, OpusMSDecoder,
+	OpusMSEncoder, OpusProjectionDecoder, OpusProjectionEncoder, SampleRate,
+};
+
+const VALID_SAMPLE_RATES: [i32; 5] = [8000, 12000, 16000, 24000, 48000];
+const INVALID_SAMPLE_RATES: [i32; 7] = [-48000, -1, 0, 11025, 44100, 96000, i32::MAX];
+const INVALID_CHANNELS: [i32; 6] = [i32::MIN, -1, 0, 3, 7, 255];
+
+fn assert_bad_arg<T>(result: Result<T, OpusError>, what: &str) {
+	match result {
+		Ok(_) => panic!("{what}: expected an error"),
+		Err(err) => assert!(err == OpusErrorCode::BadArg, "{what}: {err}"),
+	}
+}
+
+#[test]
+fn encoder_rejects_invalid_parameters() {
+	for sample_rate in INVALID_SAMPLE_RATES {
+		assert_bad_arg(
+			OpusEncoder::new_raw(sample_rate, 2, OpusApplication::Audio),
+			&format!("{sample_rate} Hz"),
+		);
+	}
+	for channels in INVALID_CHANNELS {
+		assert_bad_arg(
+			OpusEncoder::new_raw(48000, channels, OpusApplication::Audio),
+			&format!("{channels} channels"),
+		);
+	}
+	for sample_rate in VALID_SAMPLE_RATES {
+		for channels in [1, 2] {
+			OpusEncoder::new_raw(sample_rate, channels, OpusApplication::Audio)
+				.expect("failed to create encoder");
+		}
+	}
+}
+
+#[test]
+fn decoder_rejects_invalid_parameters() {
+	for sample_rate in INVALID_SAMPLE_RATES {
+		assert_bad_arg(
+			OpusDecoder::new_raw(sample_rate, 2),
+			&format!("{sample_rate} Hz"),
+		);
+	}
+	for channels in INVALID_CHANNELS {
+		assert_bad_arg(
+			OpusDecoder::new_raw(48000, channels),
+			&format!("{channels} channels"),
+		);
+	}
+	for sample_rate in VALID_SAMPLE_RATES {
+		for channels in [1, 2] {
+			OpusDecoder::new_raw(sample_rate, channels).expect("failed to create decoder");
+		}
+	}
+}
+
+#[test]
+fn constructs_at_every_sample_rate() {
+	let sample_rates = [
+		SampleRate::Hz8000,
+		SampleRate::Hz12000,
+		SampleRate::Hz16000,
+		SampleRate::Hz24000,
+		SampleRate::Hz48000,
+	];
+	for (sample_rate, hz) in sample_rates.into_iter().zip(VALID_SAMPLE_RATES) {
+		assert_eq!(sample_rate.as_i32(), hz);
+		assert_eq!(SampleRate::try_from(hz), Ok(sample_rate));
+		assert_eq!(SampleRate::try_from(hz as u32), Ok(sample_rate));
+		let mut encoder = OpusEncoder::new(sample_rate, Channels::Stereo, OpusApplication::Audio)
+			.expect("failed to create encoder");
+		assert_eq!(encoder.sample_rate(), Ok(hz));
+		let mut decoder =
+			OpusDecoder::new(sample_rate, Channels::Mono).expect("failed to create decoder");
+		assert_eq!(decoder.sample_rate(), Ok(hz));
+	}
+	for sample_rate in INVALID_SAMPLE_RATES {
+		assert_eq!(
+			SampleRate::try_from(sample_rate),
+			Err(OpusErrorCode::BadArg)
+		);
+	}
+	for sample_rate in [0, 44100, 96000, u32::MAX] {
+		assert_eq!(
+			SampleRate::try_from(sample_rate),
+			Err(OpusErrorCode::BadArg)
+		);
+	}
+}
+
+#[test]
+fn channels_are_validated_in_every_profile() {
+	for (channels, count) in [(Channels::Mono, 1), (Channels::Stereo, 2)] {
+		assert_eq!(channels.count(), count);
+		assert_eq!(Channels::try_from(count as i32), Ok(channels));
+		let encoder = OpusEncoder::new(SampleRate::Hz48000, channels, OpusApplication::Audio)
+			.expect("failed to create encoder");
+		assert_eq!(encoder.channels(), count);
+		let decoder =
+			OpusDecoder::new(SampleRate::Hz48000, channels).expect("failed to create decoder");
+		assert_eq!(decoder.channels(), count);
+	}
+	// These used to only be caught by a `debug_assert!`.
+	for channels in [0, 3] {
+		assert_eq!(Channels::try_from(channels), Err(OpusErrorCode::BadArg));
+		assert_bad_arg(
+			OpusEncoder::new_raw(48000, channels, OpusApplication::Audio),
+			&format!("{channels} channels"),
+		);
+		assert_bad_arg(
+			OpusDecoder::new_raw(48000, channels),
+			&format!("{channels} channels"),
+		);
+	}
+}
+
+#[test]
+fn multistream_rejects_invalid_sample_rates() {
+	let mapping = ChannelMapping::family0(2).expect("invalid mapping");
+	for sample_rate in INVALID_SAMPLE_RATES {
+		let what = format!("{sample_rate} Hz");
+		assert_bad_arg(
+			OpusMSEncoder::new(sample_rate, &mapping, OpusApplication::Audio),
+			&what,
+		);
+		assert_bad_arg(
+			OpusMSEncoder::new_surround(sample_rate, 6, 1, OpusApplication::Audio),
+			&what,
+		);
+		assert_bad_arg(OpusMSDecoder::new(sample_rate, &mapping), &what);
+	}
+}
+
+#[test]
+fn projection_rejects_invalid_sample_rates() {
+	let mut encoder = OpusProjectionEncoder::new(48000, 4, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let demixing_matrix = encoder
+		.demixing_matrix()
+		.expect("failed to get demixing matrix");
+	for sample_rate in INVALID_SAMPLE_RATES {
+		let what = format!("{sample_rate} Hz");
+		assert_bad_arg(
+			OpusProjectionEncoder::new(sample_rate, 4, OpusApplication::Audio),
+			&what,
+		);
+		assert_bad_arg(
+			OpusProjectionDecoder::new(
+				sample_rate,
+				4,
+				encoder.streams() as u8,
+				encoder.coupled_streams() as u8,
+				&demixing_matrix,
+			),
+			&what,
+		);
+	}
+}
@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::util::{pcm_f32_to_i16, pcm_f32_to_i16_saturating, pcm_i16_to_f32};
+use proptest::prelude::*;
+
+proptest! {
+	#[test]
+	fn i16_round_trips_within_one_lsb(src in prop::collection::vec(any::<i16>(), 0..1024)) {
+		let mut float = vec![0.0; src.len()];
+		pcm_i16_to_f32(&src, &mut float);
+		prop_assert!(float.iter().all(|sample| (-1.0..1.0).contains(sample)));
+		let mut dst = vec![0; src.len()];
+		pcm_f32_to_i16(&float, &mut dst);
+		for (&src, &dst) in src.iter().zip(&dst) {
+			prop_assert!((i32::from(src) - i32::from(dst)).abs() <= 1, "{src} became {dst}");
+		}
+	}
+
+	#[test]
+	fn f32_to_i16_saturates(src in prop::collection::vec(any::<f32>(), 0..1024)) {
+		let dst = pcm_f32_to_i16_saturating(&src);
+		prop_assert_eq!(dst.len(), src.len());
+		for (&src, &dst) in src.iter().zip(&dst) {
+			if src >= 1.0 {
+				prop_assert_eq!(dst, i16::MAX);
+			} else if src <= -1.0 {
+				prop_assert!(dst <= -i16::MAX, "{src} became {dst}");
+			} else if src.is_nan() {
+				prop_assert_eq!(dst, 0);
+			}
+		}
+	}
+}
+
+#[test]
+fn converts_full_scale() {
+	let mut float = [0.0; 3];
+	pcm_i16_to_f32(&[i16::MIN, 0, i16::MAX], &mut float);
+	assert_eq!(float, [-1.0, 0.0, 32767.0 / 32768.0]);
+	assert_eq!(pcm_f32_to_i16_saturating(&[-2.0, -1.0, 0.0, 1.0, 2.0]), [
+		i16::MIN,
+		-i16::MAX,
+		0,
+		i16::MAX,
+		i16::MAX
+	]);
+}
+
+#[test]
+#[should_panic(expected = "lengths differ")]
+fn rejects_mismatched_lengths() {
+	pcm_i16_to_f32(&[0; 4], &mut [0.0; 3]);
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MPL-2.0
+#![cfg(feature = "custom")]
+use meowlouder_opus::{
+	custom::{OpusCustomDecoder, OpusCustomEncoder, OpusCustomMode},
+	error::OpusErrorCode,
+	OpusDecoder,
+};
+
+/// 128 samples at 44.1 kHz, neither of which standard Opus supports.
+const FRAME_SIZE: usize = 128;
+
+/// Generates `frames` frames of a stereo 1 kHz tone at 44.1 kHz.
+fn tone(frames: usize) -> Vec<i16> {
+	(0..frames * FRAME_SIZE)
+		.flat_map(|i| {
+			let t = i as f32 / 44100.0;
+			let sample = (0.5 * 32767.0 * (t * 1000.0 * std::f32::consts::TAU).sin()) as i16;
+			[sample, sample]
+		})
+		.collect()
+}
+
+fn energy(pcm: &[i16]) -> f64 {
+	pcm.iter().map(|&sample| f64::from(sample).powi(2)).sum()
+}
+
+#[test]
+fn round_trips_128_samples_at_44100_hz() {
+	assert!(OpusDecoder::new_raw(44100, 2).is_err());
+	assert!(OpusCustomMode::new(44100, 127).is_err());
+
+	let mode = OpusCustomMode::new(44100, FRAME_SIZE).expect("failed to create mode");
+	assert_eq!(mode.sample_rate(), 44100);
+	assert_eq!(mode.frame_size(), FRAME_SIZE);
+	let mut encoder = OpusCustomEncoder::new(mode.clone(), 2).expect("failed to create encoder");
+	let mut decoder = OpusCustomDecoder::new(mode.clone(), 2).expect("failed to create decoder");
+	// The encoder and decoder keep the mode alive.
+	drop(mode);
+
+	let pcm = tone(100);
+	let mut decoded = Vec::new();
+	// Custom modes are always constant bitrate, with the bitrate set by the
+	// size of the output buffer.
+	let mut data = [0; 100];
+	for frame in pcm.chunks_exact(FRAME_SIZE * 2) {
+		let len = encoder
+			.encode_into(frame, FRAME_SIZE, &mut data)
+			.expect("failed to encode");
+		assert_eq!(len, data.len());
+		let pcm = decoder
+			.decode(Some(&data[..len]), FRAME_SIZE)
+			.expect("failed to decode");
+		assert_eq!(pcm.len(), FRAME_SIZE * 2);
+		decoded.extend(pcm);
+	}
+	// Skip the first frames, which are mostly the encoder's lookahead.
+	let skip = FRAME_SIZE * 2 * 20;
+	let ratio = energy(&decoded[skip..]) / energy(&pcm[skip..]);
+	assert!((ratio - 1.0).abs() < 0.2, "energy ratio of {ratio}");
+
+	let data = encoder
+		.encode_float(&[0.0; FRAME_SIZE * 2], FRAME_SIZE)
+		.expect("failed to encode");
+	let pcm = decoder
+		.decode_float(Some(&data), FRAME_SIZE)
+		.expect("failed to decode");
+	assert_eq!(pcm.len(), FRAME_SIZE * 2);
+
+	assert_eq!(
+		OpusCustomEncoder::new(decoder.mode().clone(), 3).unwrap_err(),
+		OpusErrorCode::BadArg
+	);
+	assert_eq!(encoder.channels(), 2);
+}
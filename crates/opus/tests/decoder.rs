@@ -0,0 +1,335 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{decoder, encode_tone, encoder, energy, sine, FRAME_SIZE};
+use meowlouder_opus::{
+	error::OpusErrorCode, packet, Channels, DecodeOutcome, OpusApplication, OpusBandwidth,
+	OpusEncoder, SampleRate,
+};
+
+#[test]
+fn conceal_loss_matches_stream_duration() {
+	let mut encoder = encoder(Channels::Stereo);
+	let packets = encode_tone(&mut encoder, 3);
+	let mut decoder = decoder(Channels::Stereo);
+
+	let err = decoder
+		.conceal_loss()
+		.expect_err("concealed before decoding");
+	assert_eq!(err, OpusErrorCode::BadArg);
+
+	let a = decoder
+		.decode(Some(&packets[0]), FRAME_SIZE, false)
+		.expect("failed to decode");
+	let b = decoder.conceal_loss().expect("failed to conceal");
+	let mut b_float = vec![0.0; FRAME_SIZE * 2];
+	let b_float_len = decoder
+		.conceal_loss_float_into(&mut b_float)
+		.expect("failed to conceal");
+	let c = decoder
+		.decode(Some(&packets[2]), FRAME_SIZE, false)
+		.expect("failed to decode");
+
+	assert_eq!(a.len(), FRAME_SIZE * 2);
+	assert_eq!(b.len(), a.len());
+	assert_eq!(b_float_len, FRAME_SIZE);
+	assert_eq!(c.len(), a.len());
+	assert_eq!(decoder.concealment_count(), 2);
+}
+
+#[test]
+fn debug_shows_configuration() {
+	let decoder = decoder(Channels::Mono);
+	let debug = format!("{decoder:?}");
+	assert!(debug.contains("48000"), "{debug}");
+	assert!(debug.contains("Mono"), "{debug}");
+}
+
+#[test]
+fn decode_frame_reports_metadata() {
+	// Low bitrate speech, which is coded with SILK, so the pitch is coded.
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	encoder.set_bitrate(16000).expect("failed to set bitrate");
+	encoder
+		.set_max_bandwidth(OpusBandwidth::Wideband)
+		.expect("failed to set max bandwidth");
+	let mut decoder = decoder(Channels::Mono);
+	let mut frame = None;
+	for index in 0..10 {
+		let pcm = sine(FRAME_SIZE, 1, 200.0, index * FRAME_SIZE);
+		let data = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		frame = Some(
+			decoder
+				.decode_frame(Some(&data), FRAME_SIZE, false)
+				.expect("failed to decode"),
+		);
+	}
+	let frame = frame.unwrap();
+	assert_eq!(frame.nb_samples, FRAME_SIZE);
+	assert_eq!(frame.pcm.len(), FRAME_SIZE);
+	assert_eq!(frame.bandwidth, OpusBandwidth::Wideband);
+	let pitch = frame.pitch.expect("no pitch for a voiced frame");
+	// A 200 Hz tone has a period of 240 samples at 48 kHz.
+	assert!((pitch - 240).abs() <= 2, "pitch of {pitch}");
+	assert!(!frame.concealed);
+
+	let concealed = decoder
+		.decode_frame(None::<&[u8]>, FRAME_SIZE, false)
+		.expect("failed to conceal");
+	assert!(concealed.concealed);
+	assert_eq!(concealed.nb_samples, FRAME_SIZE);
+}
+
+#[test]
+fn conceals_every_other_packet() {
+	let mut encoder = encoder(Channels::Mono);
+	let packets = encode_tone(&mut encoder, 100);
+	let mut decoder = decoder(Channels::Mono);
+	let mut decoded = Vec::new();
+	for (index, data) in packets.iter().enumerate() {
+		// Every other packet is lost, and concealed in each of the ways
+		// counted.
+		let pcm = match (index % 2, index / 2 % 3) {
+			(0, _) => decoder.decode(Some(data), FRAME_SIZE, false),
+			(_, 0) => decoder.conceal(FRAME_SIZE),
+			(_, 1) => decoder.decode(None::<&[u8]>, FRAME_SIZE, false),
+			_ => decoder.conceal_float(FRAME_SIZE).map(|pcm| {
+				pcm.iter()
+					.map(|&sample| (sample * 32767.0) as i16)
+					.collect()
+			}),
+		}
+		.expect("failed to decode");
+		assert_eq!(pcm.len(), FRAME_SIZE);
+		decoded.extend(pcm);
+	}
+	assert_eq!(decoder.concealment_count(), 50);
+	assert_eq!(decoded.len(), 100 * FRAME_SIZE);
+	// The concealed audio continues the tone rather than being silent.
+	let concealed = &decoded[FRAME_SIZE * 41..FRAME_SIZE * 42];
+	assert!(energy(concealed) > 0.0);
+}
+
+#[test]
+fn in_dtx_tracks_silence() {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	encoder.set_dtx(true).expect("failed to enable DTX");
+	let mut decoder = decoder(Channels::Mono);
+	assert!(!decoder.in_dtx());
+
+	let silence = vec![0; FRAME_SIZE];
+	let mut dtx_packets = Vec::new();
+	for frame in 0..100 {
+		// Some speech, and then silence for the encoder to stop sending.
+		let pcm = if frame < 10 {
+			sine(FRAME_SIZE, 1, 300.0, frame * FRAME_SIZE)
+		} else {
+			silence.clone()
+		};
+		let data = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		let (decoded, in_dtx) = decoder
+			.decode_and_in_dtx(Some(&data), FRAME_SIZE)
+			.expect("failed to decode");
+		assert_eq!(decoded.len(), FRAME_SIZE);
+		assert_eq!(in_dtx, decoder.in_dtx());
+		if frame < 10 {
+			assert!(!in_dtx, "speech frame {frame} decoded in DTX");
+		}
+		if packet::is_dtx(&data) {
+			assert!(in_dtx, "DTX packet {frame} decoded outside of DTX");
+			dtx_packets.push(data);
+		}
+	}
+	assert!(
+		dtx_packets.len() > 50,
+		"only {} DTX packets",
+		dtx_packets.len()
+	);
+	assert!(decoder.in_dtx());
+
+	// The frames between DTX packets are concealed, and still in DTX.
+	let (_, in_dtx) = decoder
+		.decode_and_in_dtx(None, FRAME_SIZE)
+		.expect("failed to conceal");
+	assert!(in_dtx);
+
+	decoder
+		.decode(Some(&dtx_packets[0]), FRAME_SIZE, false)
+		.expect("failed to decode");
+	assert!(decoder.last_packet_was_dtx());
+	decoder.reset().expect("failed to reset");
+	assert!(!decoder.in_dtx());
+	assert!(!decoder.last_packet_was_dtx());
+	assert_eq!(decoder.concealment_count(), 0);
+}
+
+#[test]
+fn decode_lossy_conceals_invalid_packets() {
+	let mut packets = encode_tone(&mut encoder(Channels::Stereo), 10);
+	// A code 3 packet, which is missing its frame count byte.
+	packets[5] = vec![0x03];
+	let mut decoder = decoder(Channels::Stereo);
+	let mut decoded = Vec::new();
+	for (index, data) in packets.iter().enumerate() {
+		let outcome = decoder.decode_lossy(Some(data), FRAME_SIZE, false);
+		assert_eq!(outcome.is_concealed(), index == 5, "packet {index}");
+		assert_eq!(outcome.pcm().map(<[i16]>::len), Some(FRAME_SIZE * 2));
+		if index != 5 {
+			assert!(matches!(outcome, DecodeOutcome::Decoded(_)));
+		}
+		decoded.extend(outcome.into_result().expect("failed to decode"));
+	}
+	assert_eq!(decoded.len(), packets.len() * FRAME_SIZE * 2);
+	assert_eq!(decoder.concealment_count(), 1);
+
+	// Lost packets are concealed too.
+	let outcome = decoder.decode_lossy(None, FRAME_SIZE, false);
+	assert!(outcome.is_concealed());
+
+	// Other errors aren't hidden, such as a frame too small for the packet.
+	let outcome = decoder.decode_lossy(Some(&packets[0]), FRAME_SIZE / 2, false);
+	let DecodeOutcome::Failed(err) = outcome else {
+		panic!("decoded into too small a frame: {outcome:?}");
+	};
+	assert_eq!(err, OpusErrorCode::BufferTooSmall);
+}
+
+#[test]
+fn buffer_too_small_reports_lengths() {
+	let mut encoder = encoder(Channels::Stereo);
+	let data = encode_tone(&mut encoder, 1).remove(0);
+	let mut decoder = decoder(Channels::Stereo);
+
+	let mut pcm = vec![0; FRAME_SIZE];
+	let err = decoder
+		.decode_into(Some(&data), &mut pcm, FRAME_SIZE, false)
+		.expect_err("decoded into a mono sized buffer");
+	assert_eq!(err, OpusErrorCode::BufferTooSmall);
+	assert_eq!(err.required(), Some(FRAME_SIZE * 2));
+	assert_eq!(err.provided(), Some(FRAME_SIZE));
+	assert_eq!(
+		err.to_string(),
+		"failed to decode: buffer too small (required 1920, provided 960)"
+	);
+	let mut pcm = vec![0.0; FRAME_SIZE * 2 - 1];
+	let err = decoder
+		.decode_float_into(Some(&data), &mut pcm, FRAME_SIZE, false)
+		.expect_err("decoded into a short buffer");
+	assert_eq!(
+		(err.required(), err.provided()),
+		(Some(FRAME_SIZE * 2), Some(FRAME_SIZE * 2 - 1))
+	);
+
+	let err = encoder
+		.encode(&sine(FRAME_SIZE, 1, 440.0, 0), FRAME_SIZE)
+		.expect_err("encoded a mono frame as stereo");
+	assert_eq!(err, OpusErrorCode::BufferTooSmall);
+	assert_eq!(
+		(err.required(), err.provided()),
+		(Some(FRAME_SIZE * 2), Some(FRAME_SIZE))
+	);
+	// Errors from libopus itself don't carry any lengths.
+	let err = decoder
+		.decode(Some([0xFB, 0x3F]), FRAME_SIZE, false)
+		.expect_err("decoded an invalid packet");
+	assert_eq!(err, OpusErrorCode::InvalidPacket);
+	assert_eq!(err.required(), None);
+}
+
+#[test]
+fn decodes_both_stereo_channels() {
+	let mut encoder = encoder(Channels::Stereo);
+	let mut decoder = decoder(Channels::Stereo);
+	let mut float_decoder = decoder.clone();
+	for frame in 0..10 {
+		// A tone panned hard right.
+		let pcm = sine(FRAME_SIZE, 1, 440.0, frame * FRAME_SIZE)
+			.into_iter()
+			.flat_map(|sample| [0, sample])
+			.collect::<Vec<_>>();
+		let data = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		let samples = packet::nb_samples(&data, 48000).expect("invalid packet");
+
+		let decoded = decoder
+			.decode(Some(&data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(decoded.len(), samples * 2);
+		let decoded_float = float_decoder
+			.decode_float(Some(&data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(decoded_float.len(), samples * 2);
+
+		// The first frame is mostly the encoder's lookahead.
+		if frame > 0 {
+			let right = decoded
+				.iter()
+				.skip(1)
+				.step_by(2)
+				.copied()
+				.collect::<Vec<_>>();
+			let left = decoded.iter().step_by(2).copied().collect::<Vec<_>>();
+			assert!(right.iter().filter(|&&sample| sample != 0).count() > samples / 2);
+			assert!(energy(&right) > 100.0 * energy(&left));
+			// Truncating to the samples per channel used to drop the second
+			// half of the frame.
+			assert!(decoded_float[samples..]
+				.iter()
+				.skip(1)
+				.step_by(2)
+				.any(|&sample| sample.abs() > 0.01));
+		}
+	}
+}
+
+#[test]
+fn safe_decoding_always_checks_the_buffer() {
+	// Even with the deprecated
+	// `i-can-be-trusted-to-size-my-decoder-buffer-correctly` feature enabled,
+	// which used to skip this check.
+	let data = encode_tone(&mut encoder(Channels::Stereo), 1).remove(0);
+	let mut decoder = decoder(Channels::Stereo);
+	let reference = decoder.clone();
+
+	let mut pcm = vec![0; FRAME_SIZE * 2 - 1];
+	let err = decoder
+		.decode_into(Some(&data), &mut pcm, FRAME_SIZE, false)
+		.expect_err("decoded into a short buffer");
+	assert_eq!(err, OpusErrorCode::BufferTooSmall);
+	let mut pcm = vec![0.0; FRAME_SIZE];
+	let err = decoder
+		.decode_float_into(Some(&data), &mut pcm, FRAME_SIZE, false)
+		.expect_err("decoded into a short buffer");
+	assert_eq!(err, OpusErrorCode::BufferTooSmall);
+	let err = decoder
+		.decode_generic_into::<i16, _, _>(None::<&[u8]>, &mut [], FRAME_SIZE, false)
+		.expect_err("concealed into an empty buffer");
+	assert_eq!(err, OpusErrorCode::BufferTooSmall);
+	assert_eq!(decoder.concealment_count(), 0);
+
+	// The unchecked methods decode the same as the checked ones, given a
+	// large enough buffer.
+	let mut pcm = vec![0; FRAME_SIZE * 2];
+	// SAFETY: `pcm` has room for `FRAME_SIZE` samples for each channel.
+	let len = unsafe { decoder.decode_into_unchecked(Some(&data), &mut pcm, FRAME_SIZE, false) };
+	assert_eq!(len, Ok(FRAME_SIZE));
+	assert_eq!(
+		Ok(pcm),
+		reference.clone().decode(Some(&data), FRAME_SIZE, false)
+	);
+	let mut pcm = vec![0.0; FRAME_SIZE * 2];
+	// SAFETY: As above.
+	let len = unsafe {
+		reference
+			.clone()
+			.decode_float_into_unchecked(Some(&data), &mut pcm, FRAME_SIZE, false)
+	};
+	assert_eq!(len, Ok(FRAME_SIZE));
+	assert_eq!(
+		Ok(pcm),
+		reference
+			.clone()
+			.decode_float(Some(&data), FRAME_SIZE, false)
+	);
+}
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MPL-2.0
+#![cfg(feature = "dred")]
+use meowlouder_opus::{
+	Channels, OpusApplication, OpusDecoder, OpusDred, OpusDredDecoder, OpusEncoder, SampleRate,
+};
+
+/// 20 ms at 16 kHz, where DRED is most useful.
+const FRAME_SIZE: usize = 320;
+
+/// Creates a 16 kHz speech encoder carrying 500 ms of DRED in each packet.
+fn dred_encoder() -> OpusEncoder {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz16000, Channels::Mono, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	encoder.set_bitrate(32000).expect("failed to set bitrate");
+	// DRED is only sent when packets are expected to be lost.
+	encoder
+		.set_expected_packet_loss(20)
+		.expect("failed to set expected packet loss");
+	encoder
+		.set_dred_duration(50)
+		.expect("failed to set DRED duration");
+	encoder
+}
+
+/// Encodes `frames` consecutive frames of a 220 Hz tone at 16 kHz.
+fn encode_tone(encoder: &mut OpusEncoder, frames: usize) -> Vec<Vec<u8>> {
+	(0..frames)
+		.map(|frame| {
+			let pcm = (frame * FRAME_SIZE..(frame + 1) * FRAME_SIZE)
+				.map(|i| {
+					let t = i as f32 / 16000.0;
+					(0.3 * 32767.0 * (t * 220.0 * std::f32::consts::TAU).sin()) as i16
+				})
+				.collect::<Vec<_>>();
+			encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode")
+		})
+		.collect()
+}
+
+#[test]
+fn parses_dred_from_encoded_packets() {
+	let mut encoder = dred_encoder();
+	assert_eq!(encoder.dred_duration(), Ok(50));
+	let packets = encode_tone(&mut encoder, 50);
+
+	let mut dred_decoder = OpusDredDecoder::new().expect("failed to create DRED decoder");
+	let mut dred = OpusDred::new();
+	let mut with_dred = 0;
+	for data in &packets {
+		let (offset, _) = dred_decoder
+			.dred_parse(&mut dred, data, 48000, 48000, true)
+			.expect("failed to parse DRED");
+		if offset > 0 {
+			with_dred += 1;
+			dred_decoder
+				.dred_process(&mut dred)
+				.expect("failed to process DRED");
+		}
+	}
+	// The encoder needs a few packets to get going.
+	assert!(with_dred > 40, "only {with_dred} packets carried DRED");
+
+	// A CELT packet, which has no room for DRED.
+	let (offset, _) = dred_decoder
+		.dred_parse(&mut dred, &[0x08, 1, 2], 48000, 48000, false)
+		.expect("failed to parse DRED");
+	assert_eq!(offset, 0);
+}
+
+#[test]
+fn recovers_lost_packets_from_dred() {
+	let packets = encode_tone(&mut dred_encoder(), 60);
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz16000, Channels::Mono).expect("failed to create decoder");
+	let mut dred_decoder = OpusDredDecoder::new().expect("failed to create DRED decoder");
+	let mut dred = OpusDred::new();
+
+	let mut decoded = Vec::new();
+	for data in &packets[..40] {
+		decoded.extend(
+			decoder
+				.decode(Some(data), FRAME_SIZE, false)
+				.expect("failed to decode"),
+		);
+	}
+	// Packets 40 to 42 are lost, and recovered from the DRED in packet 43.
+	let recovered = decoder
+		.recover_with_dred(&mut dred_decoder, &mut dred, &packets[43], 3, FRAME_SIZE)
+		.expect("failed to recover");
+	assert_eq!(recovered.len(), 3 * FRAME_SIZE);
+	let energy = recovered
+		.iter()
+		.map(|&sample| f64::from(sample).powi(2))
+		.sum::<f64>();
+	assert!(energy > 1e6, "the recovered audio is nearly silent");
+	// None of it needed concealing.
+	assert_eq!(decoder.concealment_count(), 0);
+	decoded.extend(recovered);
+	for data in &packets[43..] {
+		decoded.extend(
+			decoder
+				.decode(Some(data), FRAME_SIZE, false)
+				.expect("failed to decode"),
+		);
+	}
+	assert_eq!(decoded.len(), packets.len() * FRAME_SIZE);
+
+	// Further back than the DRED reaches, the audio is concealed instead.
+	let recovered = decoder
+		.recover_with_dred(&mut dred_decoder, &mut dred, &packets[59], 200, FRAME_SIZE)
+		.expect("failed to recover");
+	assert_eq!(recovered.len(), 200 * FRAME_SIZE);
+	assert!(decoder.concealment_count() > 0);
+
+	let recovered = decoder
+		.decode_dred_float(&dred, FRAME_SIZE as i32, FRAME_SIZE)
+		.expect("failed to decode DRED");
+	assert_eq!(recovered.len(), FRAME_SIZE);
+}
@@ -0,0 +1,387 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{decoder, encode_tone, encoder, energy, sine, FRAME_SIZE};
+use meowlouder_opus::{
+	error::OpusErrorCode, packet, Channels, FrameDuration, OpusApplication, OpusBandwidth,
+	OpusEncodable, OpusEncoder, OpusEncoderConfig, OpusEncoderPreset, OpusPacket, OpusSignal,
+	SampleRate,
+};
+
+#[test]
+fn debug_shows_configuration() {
+	let encoder = encoder(Channels::Stereo);
+	let debug = format!("{encoder:?}");
+	assert!(debug.contains("48000"), "{debug}");
+	assert!(debug.contains("Stereo"), "{debug}");
+	assert!(debug.contains("Audio"), "{debug}");
+	assert!(!debug.contains("<error>"), "{debug}");
+}
+
+#[test]
+fn restore_undoes_configuration_changes() {
+	let mut encoder = encoder(Channels::Stereo);
+	let original = encoder.snapshot().expect("failed to snapshot");
+
+	encoder.set_bitrate(24000).expect("failed to set bitrate");
+	encoder.set_complexity(2).expect("failed to set complexity");
+	encoder.set_vbr(false).expect("failed to set VBR");
+	encoder
+		.set_vbr_constraint(false)
+		.expect("failed to set VBR constraint");
+	encoder.set_inband_fec(true).expect("failed to set FEC");
+	encoder.set_dtx(true).expect("failed to set DTX");
+	encoder
+		.set_signal(Some(OpusSignal::Voice))
+		.expect("failed to set signal");
+	encoder
+		.set_max_bandwidth(OpusBandwidth::Wideband)
+		.expect("failed to set max bandwidth");
+	encoder
+		.set_expected_packet_loss(20)
+		.expect("failed to set expected packet loss");
+	encoder
+		.set_force_channels(Some(1))
+		.expect("failed to force channels");
+	encoder
+		.set_prediction_disabled(true)
+		.expect("failed to disable prediction");
+	encoder
+		.set_phase_inversion_disabled(true)
+		.expect("failed to disable phase inversion");
+	encoder.set_lsb_depth(16).expect("failed to set LSB depth");
+
+	let changed = encoder.snapshot().expect("failed to snapshot");
+	assert_eq!(changed.bitrate, 24000);
+	assert_eq!(changed.force_channels, Some(1));
+	assert_ne!(changed, original);
+
+	encoder.restore(&original).expect("failed to restore");
+	assert_eq!(encoder.snapshot().expect("failed to snapshot"), original);
+}
+
+#[test]
+fn encode_frame_timestamps_increment() {
+	let mut encoder = encoder(Channels::Mono);
+	for index in 0..100u64 {
+		let pcm = sine(FRAME_SIZE, 1, 440.0, index as usize * FRAME_SIZE);
+		let frame = encoder
+			.encode_frame(&pcm, FRAME_SIZE)
+			.expect("failed to encode");
+		assert_eq!(frame.frame_size_samples, FRAME_SIZE);
+		assert_eq!(frame.timestamp, Some(index * FRAME_SIZE as u64));
+		assert!(!frame.dtx);
+		assert_eq!(packet::bandwidth(&frame.data), Ok(frame.bandwidth));
+		assert!(!frame.into_data().is_empty());
+	}
+	assert_eq!(encoder.timestamp(), 100 * FRAME_SIZE as u64);
+
+	encoder.reset().expect("failed to reset");
+	let pcm = sine(FRAME_SIZE, 1, 440.0, 0);
+	let frame = encoder
+		.encode_frame(&pcm, FRAME_SIZE)
+		.expect("failed to encode");
+	assert_eq!(frame.timestamp, Some(0));
+}
+
+#[test]
+fn encode_duration_derives_frame_size() {
+	let mut encoder = encoder(Channels::Stereo);
+	for (duration, frame_size) in [
+		(FrameDuration::Ms2_5, 120),
+		(FrameDuration::Ms20, 960),
+		(FrameDuration::Ms60, 2880),
+	] {
+		let pcm = sine(frame_size, 2, 440.0, 0);
+		let data = encoder
+			.encode_duration(&pcm, duration)
+			.expect("failed to encode");
+		assert_eq!(packet::nb_samples(&data, 48000), Ok(frame_size));
+	}
+
+	let mut encoder = OpusEncoder::new(SampleRate::Hz16000, Channels::Mono, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	let pcm = sine(320, 1, 440.0, 0);
+	let data = encoder
+		.encode_duration(&pcm, FrameDuration::Ms20)
+		.expect("failed to encode");
+	assert_eq!(packet::nb_samples(&data, 16000), Ok(320));
+}
+
+#[test]
+fn drain_flushes_lookahead() {
+	let mut encoder = encoder(Channels::Mono);
+	assert_eq!(encoder.drain(), Ok(Vec::new()));
+	let pre_skip = encoder.lookahead().expect("failed to get lookahead") as usize;
+	let frames = 10;
+	let mut packets = encode_tone(&mut encoder, frames);
+	let drained = encoder.drain().expect("failed to drain");
+	assert_eq!(drained.len(), pre_skip.div_ceil(FRAME_SIZE));
+	packets.extend(drained);
+
+	let mut decoder = decoder(Channels::Mono);
+	let decoded = packets
+		.iter()
+		.flat_map(|packet| {
+			decoder
+				.decode(Some(packet), FRAME_SIZE, false)
+				.expect("failed to decode")
+		})
+		.collect::<Vec<_>>();
+	// Once the pre-skip is removed, all of the input is there, including the
+	// last frame, which would otherwise have been stuck in the lookahead.
+	let input_len = frames * FRAME_SIZE;
+	assert!(decoded.len() - pre_skip >= input_len);
+	let last_frame = &decoded[pre_skip + input_len - FRAME_SIZE..pre_skip + input_len];
+	let expected = energy(&sine(FRAME_SIZE, 1, 440.0, input_len - FRAME_SIZE));
+	assert!(
+		energy(last_frame) > expected / 2.0,
+		"the last frame wasn't flushed"
+	);
+}
+
+#[test]
+fn batch_encode_matches_encoding_frame_by_frame() {
+	let pcm = (0..5)
+		.flat_map(|frame| sine(FRAME_SIZE, 2, 440.0, frame * FRAME_SIZE))
+		.collect::<Vec<_>>();
+	let mut encoder = encoder(Channels::Stereo);
+	let mut expected_encoder = encoder.clone();
+
+	let packets = encoder
+		.batch_encode(&pcm, FRAME_SIZE)
+		.expect("failed to encode");
+	let expected = pcm
+		.chunks_exact(FRAME_SIZE * 2)
+		.map(|frame| expected_encoder.encode(frame, FRAME_SIZE))
+		.collect::<Result<Vec<_>, _>>()
+		.expect("failed to encode");
+	assert_eq!(packets, expected);
+
+	// Half a frame left over.
+	let err = encoder
+		.batch_encode(&pcm[..FRAME_SIZE * 3], FRAME_SIZE)
+		.expect_err("encoded a partial frame");
+	assert_eq!(err, OpusErrorCode::BadArg);
+	assert_eq!(
+		encoder.batch_encode(&[] as &[i16], FRAME_SIZE),
+		Ok(Vec::new())
+	);
+}
+
+#[test]
+fn expert_frame_duration_forces_frame_size() {
+	let mut encoder = encoder(Channels::Stereo);
+	assert_eq!(encoder.expert_frame_duration(), Ok(None));
+	encoder
+		.set_expert_frame_duration(Some(FrameDuration::Ms10))
+		.expect("failed to set expert frame duration");
+	assert_eq!(
+		encoder.expert_frame_duration(),
+		Ok(Some(FrameDuration::Ms10))
+	);
+
+	for frame in 0..10 {
+		let pcm = sine(480, 2, 440.0, frame * 480);
+		let data = encoder.encode(&pcm, 480).expect("failed to encode");
+		let packet = OpusPacket::try_from(data).expect("invalid packet");
+		assert_eq!(packet.toc().samples_per_frame(SampleRate::Hz48000), 480);
+		assert_eq!(packet.frames(), 1);
+	}
+	// Shorter frames than forced can't be encoded.
+	let err = encoder
+		.encode(&sine(240, 2, 440.0, 0), 240)
+		.expect_err("encoded a frame shorter than forced");
+	assert_eq!(err, OpusErrorCode::BadArg);
+
+	encoder
+		.set_expert_frame_duration(None)
+		.expect("failed to set expert frame duration");
+	assert_eq!(encoder.expert_frame_duration(), Ok(None));
+
+	// The `OPUS_FRAMESIZE_*` constants, from `OPUS_FRAMESIZE_2_5_MS` to
+	// `OPUS_FRAMESIZE_120_MS`.
+	let durations = [
+		FrameDuration::Ms2_5,
+		FrameDuration::Ms5,
+		FrameDuration::Ms10,
+		FrameDuration::Ms20,
+		FrameDuration::Ms40,
+		FrameDuration::Ms60,
+		FrameDuration::Ms80,
+		FrameDuration::Ms100,
+		FrameDuration::Ms120,
+	];
+	for (value, duration) in (5001..).zip(durations) {
+		assert_eq!(i32::from(duration), value);
+		assert_eq!(FrameDuration::try_from(value), Ok(duration));
+	}
+	assert_eq!(FrameDuration::try_from(5000), Err(OpusErrorCode::BadArg));
+	assert_eq!(FrameDuration::try_from(5010), Err(OpusErrorCode::BadArg));
+}
+
+#[test]
+fn presets_apply_every_setting() {
+	let default = encoder(Channels::Stereo)
+		.snapshot()
+		.expect("failed to snapshot");
+	let presets = [
+		(
+			OpusEncoderPreset::VoipCall,
+			OpusApplication::Voip,
+			None,
+			OpusEncoderConfig {
+				bitrate: 32000,
+				complexity: 8,
+				vbr: false,
+				inband_fec: true,
+				expected_packet_loss: 10,
+				..default.clone()
+			},
+		),
+		(
+			OpusEncoderPreset::MusicStreaming,
+			OpusApplication::Audio,
+			None,
+			OpusEncoderConfig {
+				bitrate: 128000,
+				complexity: 10,
+				..default.clone()
+			},
+		),
+		(
+			OpusEncoderPreset::LowLatency,
+			OpusApplication::RestrictedLowDelay,
+			Some(FrameDuration::Ms10),
+			OpusEncoderConfig {
+				bitrate: 24000,
+				complexity: 5,
+				..default.clone()
+			},
+		),
+		(
+			OpusEncoderPreset::Podcast,
+			OpusApplication::Audio,
+			None,
+			OpusEncoderConfig {
+				bitrate: 48000,
+				complexity: 9,
+				force_channels: Some(1),
+				..default.clone()
+			},
+		),
+	];
+	for (preset, application, frame_duration, config) in presets {
+		let mut encoder = encoder(Channels::Stereo);
+		encoder
+			.apply_preset(preset)
+			.expect("failed to apply preset");
+		assert_eq!(encoder.application(), Ok(application), "{preset:?}");
+		assert_eq!(
+			encoder.expert_frame_duration(),
+			Ok(frame_duration),
+			"{preset:?}"
+		);
+		assert_eq!(encoder.snapshot(), Ok(config), "{preset:?}");
+	}
+}
+
+#[test]
+fn failed_preset_rolls_back() {
+	let mut encoder = encoder(Channels::Stereo);
+	encoder.set_bitrate(64000).expect("failed to set bitrate");
+	encode_tone(&mut encoder, 1);
+	let config = encoder.snapshot().expect("failed to snapshot");
+
+	// The application can't change once a frame has been encoded.
+	let err = encoder
+		.apply_preset(OpusEncoderPreset::VoipCall)
+		.expect_err("changed the application after encoding");
+	assert_eq!(err, OpusErrorCode::BadArg);
+	assert_eq!(encoder.application(), Ok(OpusApplication::Audio));
+	assert_eq!(encoder.snapshot(), Ok(config));
+
+	encoder
+		.apply_preset(OpusEncoderPreset::MusicStreaming)
+		.expect("failed to apply preset");
+	assert_eq!(encoder.bitrate(), Ok(128000));
+}
+
+#[test]
+fn rejects_short_buffers_before_encoding() {
+	for channels in [Channels::Mono, Channels::Stereo] {
+		let count = channels.count();
+		let mut encoder = encoder(channels);
+		let reference = encoder.clone();
+		let pcm = sine(FRAME_SIZE, count, 440.0, 0);
+		let pcm_f32 = common::sine_f32(FRAME_SIZE, count, 440.0, 0);
+		let short = &pcm[..pcm.len() - 1];
+		let mut data = [0; 4000];
+
+		let err = encoder
+			.encode_into(short, FRAME_SIZE, &mut data)
+			.expect_err("encoded a short frame");
+		assert_eq!(err, OpusErrorCode::BufferTooSmall);
+		assert_eq!(
+			(err.required(), err.provided()),
+			(Some(FRAME_SIZE * count), Some(FRAME_SIZE * count - 1))
+		);
+		let err = encoder
+			.encode(&pcm_f32[..pcm_f32.len() - 1], FRAME_SIZE)
+			.expect_err("encoded a short frame");
+		assert_eq!(err, OpusErrorCode::BufferTooSmall);
+		// Straight through the trait, bypassing the frame size validation.
+		let err = i16::encode(&mut encoder, short, FRAME_SIZE, &mut data)
+			.expect_err("encoded a short frame");
+		assert_eq!(err, OpusErrorCode::BufferTooSmall);
+		let err = f32::encode(&mut encoder, &[], FRAME_SIZE, &mut data)
+			.expect_err("encoded an empty frame");
+		assert_eq!(err.provided(), Some(0));
+
+		// Nowhere to write the packet to.
+		let err = encoder
+			.encode_into(&pcm, FRAME_SIZE, &mut [])
+			.expect_err("encoded into an empty buffer");
+		assert_eq!(err, OpusErrorCode::BufferTooSmall);
+		assert_eq!((err.required(), err.provided()), (Some(1), Some(0)));
+		let err = f32::encode(&mut encoder, &pcm_f32, FRAME_SIZE, &mut [])
+			.expect_err("encoded into an empty buffer");
+		assert_eq!(err, OpusErrorCode::BufferTooSmall);
+
+		// None of that reached libopus.
+		assert_eq!(encoder.timestamp(), 0);
+		assert_eq!(
+			encoder.encode(&pcm, FRAME_SIZE),
+			reference.clone().encode(&pcm, FRAME_SIZE)
+		);
+	}
+}
+
+#[test]
+fn encodes_long_high_bitrate_packets() {
+	let mut encoder = encoder(Channels::Stereo);
+	encoder.set_bitrate(510000).expect("failed to set bitrate");
+	encoder.set_vbr(false).expect("failed to disable VBR");
+	let frame_size = 5760;
+	for frame in 0..3 {
+		let pcm = sine(frame_size, 2, 440.0, frame * frame_size);
+		let data = encoder.encode(&pcm, frame_size).expect("failed to encode");
+		assert!(data.len() > 1275, "{} byte packet", data.len());
+		assert!(data.len() <= packet::max_packet_size(frame_size, SampleRate::Hz48000));
+		assert_eq!(packet::nb_samples(&data, 48000), Ok(frame_size));
+	}
+
+	// Up to 20 ms fits in a single frame, and each 20 ms beyond that adds
+	// another.
+	for (frame_size, sample_rate, frames) in [
+		(120, SampleRate::Hz48000, 1),
+		(960, SampleRate::Hz48000, 1),
+		(1920, SampleRate::Hz48000, 2),
+		(5760, SampleRate::Hz48000, 6),
+		(1920, SampleRate::Hz16000, 6),
+	] {
+		let size = packet::max_packet_size(frame_size, sample_rate);
+		assert_eq!(size, frames * 1277 + 2, "{frame_size} at {sample_rate:?}");
+		assert!(size >= frames * 1275);
+	}
+}
@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::{
+	error::OpusErrorCode, FrameDuration, FrameSize, OpusApplication, OpusBandwidth, OpusSignal,
+	SampleRate,
+};
+use std::collections::HashSet;
+
+#[test]
+fn enums_are_hashable() {
+	let applications = [
+		OpusApplication::Voip,
+		OpusApplication::Audio,
+		OpusApplication::RestrictedLowDelay,
+		OpusApplication::Audio,
+	]
+	.into_iter()
+	.collect::<HashSet<_>>();
+	assert_eq!(applications.len(), 3);
+
+	let bandwidths = [
+		OpusBandwidth::Narrowband,
+		OpusBandwidth::Mediumband,
+		OpusBandwidth::Wideband,
+		OpusBandwidth::Superwideband,
+		OpusBandwidth::Fullband,
+	]
+	.into_iter()
+	.collect::<HashSet<_>>();
+	assert_eq!(bandwidths.len(), 5);
+
+	let signals = [OpusSignal::Voice, OpusSignal::Music]
+		.into_iter()
+		.collect::<HashSet<_>>();
+	assert_eq!(signals.len(), 2);
+
+	let codes = [
+		OpusErrorCode::BadArg,
+		OpusErrorCode::InvalidPacket,
+		OpusErrorCode::BadArg,
+	]
+	.into_iter()
+	.collect::<HashSet<_>>();
+	assert_eq!(codes.len(), 2);
+}
+
+#[test]
+fn enums_display_their_names() {
+	for application in [
+		OpusApplication::Voip,
+		OpusApplication::Audio,
+		OpusApplication::RestrictedLowDelay,
+	] {
+		assert_eq!(application.to_string(), application.name());
+	}
+	assert_eq!(OpusBandwidth::Fullband.to_string(), "fullband");
+	assert_eq!(OpusSignal::Voice.to_string(), "voice");
+	assert_eq!(
+		OpusErrorCode::BadArg.to_string(),
+		OpusErrorCode::BadArg.description()
+	);
+}
+
+#[test]
+fn frame_durations_match_reference_table() {
+	const RATES: [SampleRate; 5] = [
+		SampleRate::Hz8000,
+		SampleRate::Hz12000,
+		SampleRate::Hz16000,
+		SampleRate::Hz24000,
+		SampleRate::Hz48000,
+	];
+	let table = [
+		(FrameDuration::Ms2_5, [20, 30, 40, 60, 120]),
+		(FrameDuration::Ms5, [40, 60, 80, 120, 240]),
+		(FrameDuration::Ms10, [80, 120, 160, 240, 480]),
+		(FrameDuration::Ms20, [160, 240, 320, 480, 960]),
+		(FrameDuration::Ms40, [320, 480, 640, 960, 1920]),
+		(FrameDuration::Ms60, [480, 720, 960, 1440, 2880]),
+		(FrameDuration::Ms80, [640, 960, 1280, 1920, 3840]),
+		(FrameDuration::Ms100, [800, 1200, 1600, 2400, 4800]),
+		(FrameDuration::Ms120, [960, 1440, 1920, 2880, 5760]),
+	];
+	for (duration, samples) in table {
+		for (sample_rate, samples) in RATES.into_iter().zip(samples) {
+			assert_eq!(
+				duration.samples_at(sample_rate.as_i32() as u32),
+				samples,
+				"{duration:?} at {sample_rate:?}"
+			);
+			let frame_size = FrameSize::from_duration(duration, sample_rate);
+			assert_eq!(frame_size.samples(), samples);
+			assert_eq!(frame_size.duration(sample_rate), Some(duration));
+		}
+	}
+}
+
+/// Checks that every variant survives being displayed and parsed, and
+/// converted to and from its libopus constant.
+fn assert_round_trips<T>(variants: &[T])
+where
+	T: Copy + std::fmt::Debug + PartialEq + std::fmt::Display + std::str::FromStr + TryFrom<i32>,
+	<T as std::str::FromStr>::Err: std::fmt::Debug,
+	<T as TryFrom<i32>>::Error: std::fmt::Debug,
+	i32: From<T>,
+{
+	for &variant in variants {
+		assert_eq!(variant.to_string().parse::<T>().unwrap(), variant);
+		assert_eq!(T::try_from(i32::from(variant)).unwrap(), variant);
+	}
+}
+
+#[test]
+fn enums_round_trip_through_strings_and_constants() {
+	assert_round_trips(&[
+		OpusApplication::Voip,
+		OpusApplication::Audio,
+		OpusApplication::RestrictedLowDelay,
+	]);
+	assert_round_trips(&[
+		OpusBandwidth::Narrowband,
+		OpusBandwidth::Mediumband,
+		OpusBandwidth::Wideband,
+		OpusBandwidth::Superwideband,
+		OpusBandwidth::Fullband,
+	]);
+	assert_round_trips(&[OpusSignal::Voice, OpusSignal::Music]);
+	assert_eq!("lowdelay".parse(), Ok(OpusApplication::RestrictedLowDelay));
+	assert_eq!(
+		OpusBandwidth::try_from("narrowband"),
+		Ok(OpusBandwidth::Narrowband)
+	);
+
+	for input in ["", "VOIP", "voice", "restricted_lowdelay"] {
+		let err = input
+			.parse::<OpusApplication>()
+			.expect_err("parsed an unknown application");
+		assert_eq!(err.input(), input);
+		assert_eq!(err.expected(), ["voip", "audio", "lowdelay"]);
+	}
+	let err = "loud".parse::<OpusSignal>().unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		r#"unknown signal "loud", expected one of: voice, music"#
+	);
+	assert_eq!(OpusApplication::try_from(0), Err(OpusErrorCode::BadArg));
+	assert_eq!(OpusBandwidth::try_from(1100), Err(OpusErrorCode::BadArg));
+	assert_eq!(OpusSignal::try_from(-1000), Err(OpusErrorCode::BadArg));
+}
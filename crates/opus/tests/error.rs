@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: MPL-2.0
+#![cfg(feature = "std")]
+use meowlouder_opus::{
+	error::{OpusError, OpusErrorCode},
+	packet, Channels, OpusDecoder, SampleRate,
+};
+use std::io::{self, ErrorKind};
+
+/// Decodes a packet in a function returning `io::Result`, as an `io::Read`
+/// implementation would, relying on `?` to convert the error.
+fn decode_io(packet: &[u8]) -> io::Result<Vec<i16>> {
+	let mut decoder = OpusDecoder::new(SampleRate::Hz48000, Channels::Mono)?;
+	Ok(decoder.decode(Some(packet), 960, false)?)
+}
+
+fn nb_frames_io(packet: &[u8]) -> io::Result<usize> {
+	Ok(packet::nb_frames(packet)?)
+}
+
+#[test]
+fn question_mark_converts_to_io_error() {
+	// A code 3 packet declaring more frames than it has bytes for.
+	let err = decode_io(&[0xFB, 0x3F]).expect_err("decoded an invalid packet");
+	assert_eq!(err.kind(), ErrorKind::InvalidData);
+	let source = err
+		.into_inner()
+		.expect("no source error")
+		.downcast::<OpusError>()
+		.expect("source isn't an OpusError");
+	assert_eq!(*source, OpusErrorCode::InvalidPacket);
+
+	let err = nb_frames_io(&[]).expect_err("counted frames of an empty packet");
+	assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn error_codes_map_to_io_error_kinds() {
+	let cases = [
+		(OpusErrorCode::BadArg, ErrorKind::InvalidInput),
+		(OpusErrorCode::BufferTooSmall, ErrorKind::WriteZero),
+		(OpusErrorCode::InvalidPacket, ErrorKind::InvalidData),
+		(OpusErrorCode::InvalidState, ErrorKind::Other),
+		(OpusErrorCode::InternalError, ErrorKind::Other),
+		(OpusErrorCode::AllocFail, ErrorKind::Other),
+	];
+	for (code, kind) in cases {
+		let err = io::Error::from(code);
+		assert_eq!(err.kind(), kind, "{code:?}");
+		let source = err
+			.into_inner()
+			.expect("no source error")
+			.downcast::<OpusErrorCode>()
+			.expect("source isn't an OpusErrorCode");
+		assert_eq!(*source, code);
+	}
+}
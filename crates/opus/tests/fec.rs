@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{decoder, energy, sine, FRAME_SIZE};
+use meowlouder_opus::{Channels, OpusApplication, OpusEncoder, SampleRate};
+
+/// Returns the energy of the difference between two PCM buffers.
+fn error(a: &[i16], b: &[i16]) -> f64 {
+	a.iter()
+		.zip(b)
+		.map(|(&a, &b)| (f64::from(a) - f64::from(b)).powi(2))
+		.sum()
+}
+
+#[test]
+fn recover_with_fec_restores_lost_packet() {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	encoder.set_bitrate(32000).expect("failed to set bitrate");
+	encoder.set_inband_fec(true).expect("failed to enable FEC");
+	encoder
+		.set_expected_packet_loss(30)
+		.expect("failed to set expected packet loss");
+	let packets = (0..12)
+		.map(|frame| {
+			let pcm = sine(FRAME_SIZE, 1, 300.0, frame * FRAME_SIZE);
+			encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode")
+		})
+		.collect::<Vec<_>>();
+
+	// What the lost packet decodes to when it isn't lost.
+	let mut reference = decoder(Channels::Mono);
+	for packet in &packets[..10] {
+		reference
+			.decode(Some(packet), FRAME_SIZE, false)
+			.expect("failed to decode");
+	}
+	let expected = reference
+		.decode(Some(&packets[10]), FRAME_SIZE, false)
+		.expect("failed to decode");
+
+	let mut concealing = decoder(Channels::Mono);
+	let mut recovering = decoder(Channels::Mono);
+	for packet in &packets[..10] {
+		concealing
+			.decode(Some(packet), FRAME_SIZE, false)
+			.expect("failed to decode");
+		recovering
+			.decode(Some(packet), FRAME_SIZE, false)
+			.expect("failed to decode");
+	}
+	let concealed = concealing.conceal(FRAME_SIZE).expect("failed to conceal");
+	let (recovered, next) = recovering
+		.recover_with_fec(&packets[11], FRAME_SIZE)
+		.expect("failed to recover");
+
+	assert_eq!(recovered.len(), FRAME_SIZE);
+	assert_eq!(next.len(), FRAME_SIZE);
+	assert!(
+		error(&recovered, &expected) < error(&concealed, &expected),
+		"FEC recovery wasn't closer to the lost audio than concealment"
+	);
+}
+
+#[test]
+fn decode_fec_recovers_the_previous_frame() {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	encoder.set_bitrate(32000).expect("failed to set bitrate");
+	encoder.set_inband_fec(true).expect("failed to enable FEC");
+	encoder
+		.set_expected_packet_loss(30)
+		.expect("failed to set expected packet loss");
+	let packets = (0..3)
+		.map(|frame| {
+			let pcm = sine(FRAME_SIZE, 1, 300.0, frame * FRAME_SIZE);
+			encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode")
+		})
+		.collect::<Vec<_>>();
+
+	// Frame 2 is lost, and recovered from the FEC data in frame 3.
+	let mut decoder = decoder(Channels::Mono);
+	let mut float_decoder = decoder.clone();
+	decoder
+		.decode(Some(&packets[0]), FRAME_SIZE, false)
+		.expect("failed to decode");
+	let recovered = decoder
+		.decode_fec(&packets[2], FRAME_SIZE)
+		.expect("failed to recover");
+	assert_eq!(recovered.len(), FRAME_SIZE);
+	assert!(energy(&recovered) > 0.0, "the recovered frame is silent");
+	let next = decoder
+		.decode(Some(&packets[2]), FRAME_SIZE, false)
+		.expect("failed to decode");
+	assert_eq!(next.len(), FRAME_SIZE);
+
+	float_decoder
+		.decode_float(Some(&packets[0]), FRAME_SIZE, false)
+		.expect("failed to decode");
+	let recovered = float_decoder
+		.decode_fec_float(&packets[2], FRAME_SIZE)
+		.expect("failed to recover");
+	assert_eq!(recovered.len(), FRAME_SIZE);
+	assert!(recovered.iter().any(|&sample| sample != 0.0));
+}
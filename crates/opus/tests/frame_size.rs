@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::sine;
+use meowlouder_opus::{
+	error::OpusErrorCode, packet, Channels, FrameDuration, FrameSize, OpusApplication, OpusDecoder,
+	OpusEncoder, OpusMSDecoder, OpusMSEncoder, SampleRate,
+};
+
+const DURATIONS: [FrameDuration; 9] = [
+	FrameDuration::Ms2_5,
+	FrameDuration::Ms5,
+	FrameDuration::Ms10,
+	FrameDuration::Ms20,
+	FrameDuration::Ms40,
+	FrameDuration::Ms60,
+	FrameDuration::Ms80,
+	FrameDuration::Ms100,
+	FrameDuration::Ms120,
+];
+
+#[test]
+fn encodes_and_decodes_every_legal_duration() {
+	for sample_rate in [SampleRate::Hz48000, SampleRate::Hz16000] {
+		let mut encoder = OpusEncoder::new(sample_rate, Channels::Stereo, OpusApplication::Audio)
+			.expect("failed to create encoder");
+		let mut decoder =
+			OpusDecoder::new(sample_rate, Channels::Stereo).expect("failed to create decoder");
+		for duration in DURATIONS {
+			let frame_size = FrameSize::from_duration(duration, sample_rate);
+			let samples = frame_size.samples();
+			assert_eq!(
+				FrameSize::from_samples(samples, sample_rate),
+				Ok(frame_size)
+			);
+			let pcm = sine(samples, 2, 440.0, 0);
+			let data = encoder
+				.encode(&pcm, frame_size)
+				.unwrap_or_else(|err| panic!("failed to encode {duration:?}: {err}"));
+			assert_eq!(packet::nb_samples(&data, sample_rate.as_i32()), Ok(samples));
+			let decoded = decoder
+				.decode(Some(&data), frame_size, false)
+				.unwrap_or_else(|err| panic!("failed to decode {duration:?}: {err}"));
+			assert_eq!(decoded.len(), samples * 2);
+		}
+	}
+}
+
+#[test]
+fn rejects_illegal_sizes() {
+	for samples in [0, 1, 100, 1000, 961, 5761, 11520] {
+		assert_eq!(
+			FrameSize::from_samples(samples, SampleRate::Hz48000),
+			Err(OpusErrorCode::BadArg),
+			"{samples} samples"
+		);
+		assert_eq!(FrameSize::from(samples).duration(SampleRate::Hz48000), None);
+	}
+	// Legal at 48 kHz, but not at 16 kHz.
+	assert_eq!(
+		FrameSize::from_samples(960, SampleRate::Hz16000),
+		Ok(FrameSize::from_duration(
+			FrameDuration::Ms60,
+			SampleRate::Hz16000
+		))
+	);
+	assert_eq!(
+		FrameSize::from_samples(120, SampleRate::Hz16000),
+		Err(OpusErrorCode::BadArg)
+	);
+	assert_eq!(FrameSize::max_at(SampleRate::Hz16000).samples(), 1920);
+
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let err = encoder
+		.encode(&sine(1000, 1, 440.0, 0), 1000)
+		.expect_err("encoded 1000 samples");
+	assert_eq!(err, OpusErrorCode::BadArg);
+}
+
+#[test]
+fn frame_size_is_accepted_everywhere() {
+	let sample_rate = SampleRate::Hz48000;
+	let frame_size = FrameSize::from_duration(FrameDuration::Ms20, sample_rate);
+	let mut encoder = OpusEncoder::new(sample_rate, Channels::Mono, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let mut decoder =
+		OpusDecoder::new(sample_rate, Channels::Mono).expect("failed to create decoder");
+	let pcm = sine(960, 1, 440.0, 0);
+
+	let frame = encoder
+		.encode_frame(&pcm, frame_size)
+		.expect("failed to encode");
+	assert_eq!(frame.frame_size_samples, 960);
+	let packets = encoder
+		.batch_encode(&[pcm.clone(), pcm.clone()].concat(), frame_size)
+		.expect("failed to encode");
+	assert_eq!(packets.len(), 2);
+	let self_delimited = encoder
+		.encode_self_delimited(&pcm, frame_size)
+		.expect("failed to encode");
+
+	let decoded = decoder
+		.decode_float(Some(&frame.data), frame_size, false)
+		.expect("failed to decode");
+	assert_eq!(decoded.len(), 960);
+	let mut pcm_out = [0.0; 960];
+	assert_eq!(
+		decoder.decode_float_into(Some(&packets[0]), &mut pcm_out[..], frame_size, false),
+		Ok(960)
+	);
+	assert_eq!(
+		decoder
+			.decode_self_delimited(&self_delimited, frame_size)
+			.map(|pcm| pcm.len()),
+		Ok(960)
+	);
+	assert_eq!(decoder.conceal(frame_size).map(|pcm| pcm.len()), Ok(960));
+	assert_eq!(
+		decoder.conceal_float(frame_size).map(|pcm| pcm.len()),
+		Ok(960)
+	);
+	assert_eq!(
+		decoder
+			.decode_fec(&packets[1], frame_size)
+			.map(|pcm| pcm.len()),
+		Ok(960)
+	);
+}
+
+#[test]
+fn rejects_frame_sizes_before_allocating() {
+	let mut encoder = OpusEncoder::new(
+		SampleRate::Hz48000,
+		Channels::Stereo,
+		OpusApplication::Audio,
+	)
+	.expect("failed to create encoder");
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).expect("failed to create decoder");
+	let pcm = sine(960, 2, 440.0, 0);
+	let data = encoder.encode(&pcm, 960).expect("failed to encode");
+
+	for frame_size in [1234, usize::MAX / 2, usize::MAX] {
+		let mut buffer = [0; 4000];
+		let err = encoder
+			.encode_into(&pcm, frame_size, &mut buffer)
+			.expect_err("encoded an illegal frame size");
+		assert_eq!(err, OpusErrorCode::BadArg, "{frame_size}");
+		let err = encoder
+			.encode(&pcm, frame_size)
+			.expect_err("encoded an illegal frame size");
+		assert_eq!(err, OpusErrorCode::BadArg, "{frame_size}");
+
+		// These would otherwise try to allocate exabytes.
+		let err = decoder
+			.decode(Some(&data), frame_size, false)
+			.expect_err("decoded with an illegal frame size");
+		assert_eq!(err, OpusErrorCode::BadArg, "{frame_size}");
+		let err = decoder
+			.conceal_float(frame_size)
+			.expect_err("concealed with an illegal frame size");
+		assert_eq!(err, OpusErrorCode::BadArg, "{frame_size}");
+		let mut pcm_out = vec![0; 1920];
+		let err = decoder
+			.decode_into(Some(&data), &mut pcm_out, frame_size, false)
+			.expect_err("decoded with an illegal frame size");
+		assert_eq!(err, OpusErrorCode::BadArg, "{frame_size}");
+	}
+	// Nothing was encoded or decoded.
+	assert_eq!(encoder.timestamp(), 960);
+	assert_eq!(decoder.concealment_count(), 0);
+
+	// Multistream frame sizes go straight to libopus, after checking that the
+	// size in samples doesn't overflow.
+	let (mut encoder, mapping) =
+		OpusMSEncoder::new_surround_5_1(SampleRate::Hz48000, OpusApplication::Audio)
+			.expect("failed to create encoder");
+	let mut decoder = OpusMSDecoder::new(48000, &mapping).expect("failed to create decoder");
+	let pcm = sine(960, 6, 440.0, 0);
+	let data = encoder.encode(&pcm, 960).expect("failed to encode");
+	for frame_size in [usize::MAX / 2, usize::MAX / 6 + 1] {
+		let err = encoder
+			.encode(&pcm, frame_size)
+			.expect_err("encoded a huge frame");
+		assert_eq!(err, OpusErrorCode::BadArg, "{frame_size}");
+		let mut pcm_out = vec![0; 960 * 6];
+		let err = decoder
+			.decode_into(Some(&data), &mut pcm_out, frame_size, false)
+			.expect_err("decoded a huge frame");
+		assert_eq!(err, OpusErrorCode::BadArg, "{frame_size}");
+	}
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MPL-2.0
+#![cfg(feature = "futures")]
+mod common;
+
+use bytes::Bytes;
+use common::{encoder, sine, FRAME_SIZE};
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use meowlouder_opus::{decoder::OpusDecoderStream, encode::OpusEncoderSink, packet, Channels};
+
+#[tokio::test]
+async fn encoder_sink_encodes_every_frame() {
+	let (sender, receiver) = mpsc::channel::<Bytes>(4);
+	let mut sink = OpusEncoderSink::new(encoder(Channels::Mono), sender, FRAME_SIZE);
+	let pcm = sine(FRAME_SIZE * 100, 1, 440.0, 0);
+	let send = async {
+		// Feed blocks which don't line up with the frames.
+		for block in pcm.chunks(700) {
+			sink.feed(block).await.expect("failed to feed");
+		}
+		sink.close().await.expect("failed to close");
+	};
+	let (packets, ()) = tokio::join!(receiver.collect::<Vec<_>>(), send);
+
+	assert_eq!(packets.len(), 100);
+	for data in packets {
+		assert_eq!(packet::nb_samples(&data, 48000), Ok(FRAME_SIZE));
+	}
+}
+
+#[tokio::test]
+async fn encoder_sink_flushes_partial_frame() {
+	let (sender, receiver) = mpsc::channel::<Bytes>(4);
+	let mut sink = OpusEncoderSink::new(encoder(Channels::Mono), sender, FRAME_SIZE);
+	let pcm = sine(FRAME_SIZE + 10, 1, 440.0, 0);
+	let send = async {
+		sink.send(&pcm[..]).await.expect("failed to send");
+		sink.close().await.expect("failed to close");
+	};
+	let (packets, ()) = tokio::join!(receiver.collect::<Vec<_>>(), send);
+	assert_eq!(packets.len(), 2);
+}
+
+#[tokio::test]
+async fn decoder_stream_decodes_encoder_sink() {
+	let (sender, receiver) = mpsc::channel::<Bytes>(4);
+	let mut sink = OpusEncoderSink::new(encoder(Channels::Stereo), sender, FRAME_SIZE);
+	let pcm = sine(FRAME_SIZE * 50, 2, 440.0, 0);
+	let send = async {
+		sink.feed(&pcm[..]).await.expect("failed to feed");
+		sink.close().await.expect("failed to close");
+	};
+	let stream =
+		OpusDecoderStream::from_stream_48k_20ms(receiver).expect("failed to create decoder");
+	let (decoded, ()) = tokio::join!(stream.collect::<Vec<_>>(), send);
+
+	assert_eq!(decoded.len(), 50);
+	let samples = decoded
+		.into_iter()
+		.map(|pcm| pcm.expect("failed to decode").len())
+		.sum::<usize>();
+	assert_eq!(samples, pcm.len());
+}
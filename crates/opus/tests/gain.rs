@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{decoder, encode_tone, encoder, energy, FRAME_SIZE};
+use meowlouder_opus::{util, Channels};
+
+#[test]
+fn converts_between_q8_decibels_and_linear() {
+	for (db, q8) in [
+		(0.0, 0),
+		(1.0, 256),
+		(-1.0, -256),
+		(6.0, 1536),
+		(-6.0, -1536),
+		(0.5, 128),
+	] {
+		assert_eq!(util::db_to_q8(db), q8, "{db} dB");
+		assert_eq!(util::q8_to_db(q8), db, "{q8} Q7.8");
+	}
+	// Doubling the amplitude is about +6.02 dB.
+	assert_eq!(util::linear_to_q8(1.0), 0);
+	assert!((util::linear_to_q8(2.0) - 1541).abs() <= 1);
+	assert!((util::linear_to_q8(0.5) + 1541).abs() <= 1);
+
+	// Saturates to what libopus accepts.
+	assert_eq!(util::db_to_q8(1000.0), 32767);
+	assert_eq!(util::db_to_q8(-1000.0), -32768);
+	assert_eq!(util::db_to_q8(f32::INFINITY), 32767);
+	assert_eq!(util::linear_to_q8(0.0), -32768);
+	assert_eq!(util::db_to_q8(f32::NAN), 0);
+
+	for q8 in (-32768..=32767).step_by(7) {
+		let round_trip = util::db_to_q8(util::q8_to_db(q8));
+		assert!((round_trip - q8).abs() <= 1, "{q8} became {round_trip}");
+	}
+}
+
+#[test]
+fn decoder_gain_in_decibels() {
+	let mut decoder = decoder(Channels::Mono);
+	assert_eq!(decoder.gain_db(), Ok(0.0));
+	decoder.set_gain_db(-6.0).expect("failed to set gain");
+	assert_eq!(decoder.gain(), Ok(-1536));
+	assert_eq!(decoder.gain_db(), Ok(-6.0));
+	// Saturated rather than rejected.
+	decoder.set_gain_db(-500.0).expect("failed to set gain");
+	assert_eq!(decoder.gain(), Ok(-32768));
+
+	let packets = encode_tone(&mut encoder(Channels::Mono), 10);
+	let mut reference = decoder.clone();
+	reference.set_gain_db(0.0).expect("failed to set gain");
+	decoder.set_gain_db(-6.0).expect("failed to set gain");
+	let (mut quiet, mut loud) = (Vec::new(), Vec::new());
+	for data in &packets {
+		quiet.extend(
+			decoder
+				.decode(Some(data), FRAME_SIZE, false)
+				.expect("failed to decode"),
+		);
+		loud.extend(
+			reference
+				.decode(Some(data), FRAME_SIZE, false)
+				.expect("failed to decode"),
+		);
+	}
+	// -6 dB is about a quarter of the energy.
+	let ratio = energy(&quiet) / energy(&loud);
+	assert!((0.2..0.3).contains(&ratio), "energy ratio {ratio}");
+}
+
+#[test]
+fn gain_ramp_interpolates_between_endpoints() {
+	let ramp = util::GainRamp::new(0.0, -6.0, 10);
+	assert_eq!(ramp.len(), 10);
+	let gains = ramp.collect::<Vec<_>>();
+	assert_eq!(gains.len(), 10);
+	assert_eq!(gains[0], 0);
+	assert_eq!(gains[9], -1536);
+	assert!(gains.windows(2).all(|pair| pair[1] < pair[0]), "{gains:?}");
+
+	assert_eq!(util::GainRamp::new(3.0, -3.0, 1).collect::<Vec<_>>(), [
+		-768
+	]);
+	assert_eq!(util::GainRamp::new(0.0, -6.0, 0).next(), None);
+}
+
+#[test]
+fn gain_ramp_sets_decoder_gain_per_frame() {
+	let mut decoder = decoder(Channels::Mono);
+	decoder.set_gain_db(-2.0).expect("failed to set gain");
+	let packets = encode_tone(&mut encoder(Channels::Mono), 5);
+
+	let mut ramp =
+		util::GainRamp::apply_to_decoder(&mut decoder, 2.0, 5).expect("failed to start ramp");
+	// Nothing changes until the ramp is advanced.
+	assert_eq!(ramp.decoder().gain(), Ok(-512));
+	let mut gains = Vec::new();
+	for data in &packets {
+		let gain = ramp
+			.next()
+			.expect("ramp ended early")
+			.expect("failed to set gain");
+		assert_eq!(ramp.decoder().gain(), Ok(gain));
+		ramp.decoder()
+			.decode(Some(data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		gains.push(gain);
+	}
+	assert!(ramp.next().is_none());
+	assert_eq!(gains, [-512, -256, 0, 256, 512]);
+	assert_eq!(decoder.gain_db(), Ok(2.0));
+}
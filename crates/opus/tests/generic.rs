@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::{
+	Channels, OpusApplication, OpusDecodable, OpusDecoder, OpusEncodable, OpusEncoder, SampleRate,
+};
+
+const FRAME_SIZE: usize = 960;
+
+/// Encodes and decodes a few frames of silence, with nothing specific to the
+/// sample type.
+fn roundtrip<T: OpusEncodable + OpusDecodable>() {
+	let mut encoder = OpusEncoder::new(
+		SampleRate::Hz48000,
+		Channels::Stereo,
+		OpusApplication::Audio,
+	)
+	.expect("failed to create encoder");
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).expect("failed to create decoder");
+	let pcm = vec![T::default(); FRAME_SIZE * 2];
+
+	for _ in 0..5 {
+		let packet = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		let decoded: Vec<T> = decoder
+			.decode_generic(Some(&packet), FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(decoded.len(), FRAME_SIZE * 2);
+
+		let mut buffer = vec![T::default(); FRAME_SIZE * 2];
+		let len = decoder
+			.decode_generic_into(Some(&packet), &mut buffer, FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(len, FRAME_SIZE);
+	}
+
+	let concealed: Vec<T> = decoder
+		.decode_generic(None::<&[u8]>, FRAME_SIZE, false)
+		.expect("failed to conceal");
+	assert_eq!(concealed.len(), FRAME_SIZE * 2);
+	assert_eq!(decoder.concealment_count(), 1);
+}
+
+#[test]
+fn roundtrip_i16() {
+	roundtrip::<i16>();
+}
+
+#[test]
+fn roundtrip_f32() {
+	roundtrip::<f32>();
+}
+
+#[test]
+fn decode_generic_into_checks_buffer_size() {
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).expect("failed to create decoder");
+	let mut pcm = [0f32; FRAME_SIZE];
+	assert!(decoder
+		.decode_generic_into(None::<&[u8]>, &mut pcm, FRAME_SIZE, false)
+		.is_err());
+}
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::util::{deinterleave, deinterleave_into, interleave};
+
+#[test]
+fn interleave_round_trip() {
+	let left = (0..100).map(|i| i as f32).collect::<Vec<_>>();
+	let right = (0..100).map(|i| -(i as f32) - 0.5).collect::<Vec<_>>();
+	let interleaved = interleave(&[&left, &right]);
+	assert_eq!(interleaved.len(), 200);
+	assert_eq!(interleaved[..4], [0.0, -0.5, 1.0, -1.5]);
+
+	assert_eq!(deinterleave(&interleaved, 2), [left.clone(), right.clone()]);
+	let mut planes = [[0.0; 100]; 2];
+	let [a, b] = &mut planes;
+	deinterleave_into(&interleaved, 2, &mut [a, b]);
+	assert_eq!(planes[0][..], left[..]);
+	assert_eq!(planes[1][..], right[..]);
+
+	// Mono is unchanged either way.
+	assert_eq!(interleave(&[&left]), left);
+	assert_eq!(deinterleave(&left, 1), std::slice::from_ref(&left));
+	assert!(interleave(&[]).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "multiple of the channel count")]
+fn deinterleave_rejects_partial_frames() {
+	deinterleave(&[0.0; 5], 2);
+}
+
+#[test]
+#[should_panic(expected = "different lengths")]
+fn interleave_rejects_uneven_planes() {
+	interleave(&[&[0.0; 4], &[0.0; 3]]);
+}
+
+#[test]
+#[should_panic(expected = "wrong length")]
+fn deinterleave_into_rejects_short_planes() {
+	let mut a = [0.0; 2];
+	let mut b = [0.0; 1];
+	deinterleave_into(&[0.0; 4], 2, &mut [&mut a, &mut b]);
+}
@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: MPL-2.0
+#![cfg(feature = "std")]
+mod common;
+
+use common::{decoder, encoder, sine, FRAME_SIZE};
+use meowlouder_opus::{packet, Channels, OpusDecoderReader, OpusEncoderWriter};
+use std::io::{self, BufRead, ErrorKind, Read, Write};
+
+/// Builds a 16-bit PCM WAV file holding the samples.
+fn wav(pcm: &[i16], channels: u16) -> Vec<u8> {
+	let data_len = (pcm.len() * 2) as u32;
+	let mut wav = Vec::with_capacity(44 + pcm.len() * 2);
+	wav.extend_from_slice(b"RIFF");
+	wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+	wav.extend_from_slice(b"WAVEfmt ");
+	wav.extend_from_slice(&16u32.to_le_bytes());
+	wav.extend_from_slice(&1u16.to_le_bytes());
+	wav.extend_from_slice(&channels.to_le_bytes());
+	wav.extend_from_slice(&48000u32.to_le_bytes());
+	wav.extend_from_slice(&(48000 * 2 * u32::from(channels)).to_le_bytes());
+	wav.extend_from_slice(&(2 * channels).to_le_bytes());
+	wav.extend_from_slice(&16u16.to_le_bytes());
+	wav.extend_from_slice(b"data");
+	wav.extend_from_slice(&data_len.to_le_bytes());
+	wav.extend(pcm.iter().flat_map(|sample| sample.to_le_bytes()));
+	wav
+}
+
+/// Splits a stream of packets with 4-byte big-endian length prefixes.
+fn split_stream(mut stream: &[u8]) -> Vec<&[u8]> {
+	let mut packets = Vec::new();
+	while !stream.is_empty() {
+		let (len, rest) = stream.split_at(4);
+		let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+		let (packet, rest) = rest.split_at(len);
+		packets.push(packet);
+		stream = rest;
+	}
+	packets
+}
+
+#[test]
+fn encoder_writer_encodes_wav_file() {
+	// One second of audio, and then a bit more which only fills part of a
+	// frame.
+	let pcm = sine(48000 + 100, 2, 440.0, 0);
+	let wav = wav(&pcm, 2);
+	let mut writer = OpusEncoderWriter::new(encoder(Channels::Stereo), Vec::new(), FRAME_SIZE);
+	let copied = io::copy(&mut &wav[44..], &mut writer).expect("failed to copy");
+	assert_eq!(copied, pcm.len() as u64 * 2);
+	let stream = writer.finish().expect("failed to finish");
+
+	let packets = split_stream(&stream);
+	assert_eq!(packets.len(), 51);
+	for packet in packets {
+		assert_eq!(packet::nb_samples(packet, 48000), Ok(FRAME_SIZE));
+	}
+}
+
+#[test]
+fn encoder_writer_rejects_partial_sample() {
+	let mut writer = OpusEncoderWriter::new(encoder(Channels::Mono), Vec::new(), FRAME_SIZE);
+	// Writing a byte at a time still assembles whole samples.
+	for byte in sine(FRAME_SIZE, 1, 440.0, 0)
+		.iter()
+		.flat_map(|sample| sample.to_le_bytes())
+	{
+		assert_eq!(writer.write(&[byte]).expect("failed to write"), 1);
+	}
+	assert_eq!(split_stream(writer.get_ref()).len(), 1);
+
+	writer.write_all(&[1]).expect("failed to write");
+	let err = writer.flush().expect_err("flushed half a sample");
+	assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn decoder_reader_round_trips_encoder_writer() {
+	let pcm = sine(48000, 2, 440.0, 0);
+	let bytes = pcm
+		.iter()
+		.flat_map(|sample| sample.to_le_bytes())
+		.collect::<Vec<_>>();
+	let mut writer = OpusEncoderWriter::new(encoder(Channels::Stereo), Vec::new(), FRAME_SIZE);
+	let lookahead = writer
+		.encoder_mut()
+		.lookahead()
+		.expect("failed to get lookahead");
+	writer.write_all(&bytes).expect("failed to write");
+	let stream = writer.finish().expect("failed to finish");
+
+	let mut reader = OpusDecoderReader::new(decoder(Channels::Stereo), &stream[..], FRAME_SIZE);
+	assert_eq!(
+		reader.fill_buf().expect("failed to fill buffer").len(),
+		FRAME_SIZE * 2 * 2
+	);
+	let mut decoded = Vec::new();
+	reader
+		.read_to_end(&mut decoded)
+		.expect("failed to read to end");
+	assert_eq!(decoded.len(), bytes.len());
+
+	// Opus is lossy, so compare the signal to noise ratio rather than the
+	// exact samples, after skipping the encoder's lookahead.
+	let decoded = decoded
+		.chunks_exact(2)
+		.map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+		.collect::<Vec<_>>();
+	let delay = lookahead as usize * 2;
+	let signal = common::energy(&pcm);
+	let noise = pcm
+		.iter()
+		.zip(&decoded[delay..])
+		.map(|(&a, &b)| (f64::from(a) - f64::from(b)).powi(2))
+		.sum::<f64>();
+	let snr = 10.0 * (signal / noise).log10();
+	assert!(snr > 15.0, "SNR of {snr} dB");
+
+	let mut truncated = OpusDecoderReader::new(
+		decoder(Channels::Stereo),
+		&stream[..stream.len() - 1],
+		FRAME_SIZE,
+	);
+	assert!(truncated.read_to_end(&mut Vec::new()).is_err());
+}
+
+#[test]
+fn decoder_reader_rejects_oversized_packets() {
+	let mut reader = OpusDecoderReader::new(decoder(Channels::Mono), &[0xff; 4][..], FRAME_SIZE);
+	let err = reader
+		.read_to_end(&mut Vec::new())
+		.expect_err("read a 4 GiB packet");
+	assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+	// The largest legal packet, 48 frames of 1275 bytes, gets as far as
+	// reading it.
+	let len = (48 * 1277 + 2u32).to_be_bytes();
+	let mut reader = OpusDecoderReader::new(decoder(Channels::Mono), &len[..], FRAME_SIZE);
+	let err = reader
+		.read_to_end(&mut Vec::new())
+		.expect_err("read a missing packet");
+	assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+/// A writer that fails the first write, and then succeeds.
+#[derive(Default)]
+struct FailOnce {
+	failed: bool,
+	data: Vec<u8>,
+}
+
+impl Write for FailOnce {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if !self.failed {
+			self.failed = true;
+			return Err(ErrorKind::BrokenPipe.into());
+		}
+		self.data.write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+#[test]
+fn encoder_writer_reports_errors_on_the_next_call() {
+	let bytes = sine(FRAME_SIZE * 3 / 2, 1, 440.0, 0)
+		.iter()
+		.flat_map(|sample| sample.to_le_bytes())
+		.collect::<Vec<_>>();
+	let (first, rest) = bytes.split_at(FRAME_SIZE * 2 + 10);
+	let mut writer =
+		OpusEncoderWriter::new(encoder(Channels::Mono), FailOnce::default(), FRAME_SIZE);
+	// Writing the first packet fails once the frame is complete, so only the
+	// bytes up to the end of the frame were taken.
+	assert_eq!(
+		writer.write(first).expect("failed to write"),
+		FRAME_SIZE * 2
+	);
+	let err = writer
+		.write(&first[FRAME_SIZE * 2..])
+		.expect_err("wrote after a failed packet");
+	assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+
+	// The failed frame was dropped, and the rest is encoded as usual.
+	writer
+		.write_all(&first[FRAME_SIZE * 2..])
+		.expect("failed to write");
+	writer.write_all(rest).expect("failed to write");
+	let stream = writer.finish().expect("failed to finish").data;
+	assert_eq!(split_stream(&stream).len(), 1);
+
+	// An error completing a frame from the first byte of a call still counts
+	// that byte as written.
+	let mut writer =
+		OpusEncoderWriter::new(encoder(Channels::Mono), FailOnce::default(), FRAME_SIZE);
+	writer
+		.write_all(&bytes[..FRAME_SIZE * 2 - 1])
+		.expect("failed to write");
+	assert_eq!(
+		writer
+			.write(&bytes[FRAME_SIZE * 2 - 1..])
+			.expect("failed to write"),
+		1
+	);
+	let err = writer.flush().expect_err("flushed after a failed packet");
+	assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+	writer.flush().expect("failed to flush");
+}
@@ -0,0 +1,367 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{energy, sine, sine_f32, FRAME_SIZE};
+use meowlouder_opus::{
+	error::OpusErrorCode,
+	multistream::{channel_mapping_for_count, MappingError},
+	packet, ChannelLayout, ChannelMapping, OpusApplication, OpusBandwidth, OpusMSDecoder,
+	OpusMSEncoder, OpusSignal, SampleRate,
+};
+
+/// Creates a 5.1 encoder, along with its mapping.
+fn surround_5_1() -> (OpusMSEncoder, ChannelMapping) {
+	OpusMSEncoder::new_surround_5_1(SampleRate::Hz48000, OpusApplication::Audio)
+		.expect("failed to create encoder")
+}
+
+/// Generates interleaved PCM with a different tone in each channel, starting
+/// `offset` samples in.
+fn tones(channels: usize, offset: usize) -> Vec<i16> {
+	let per_channel = (0..channels)
+		.map(|channel| sine(FRAME_SIZE, 1, 200.0 + 150.0 * channel as f32, offset))
+		.collect::<Vec<_>>();
+	(0..FRAME_SIZE)
+		.flat_map(|i| per_channel.iter().map(move |channel| channel[i]))
+		.collect()
+}
+
+/// Returns the energy of one channel of interleaved PCM.
+fn channel_energy(pcm: &[i16], channels: usize, channel: usize) -> f64 {
+	energy(
+		&pcm.iter()
+			.skip(channel)
+			.step_by(channels)
+			.copied()
+			.collect::<Vec<_>>(),
+	)
+}
+
+#[test]
+fn encodes_5_1_for_multistream_decoder() {
+	let mapping = ChannelMapping::family1(ChannelLayout::Surround5_1);
+	let mut encoder = OpusMSEncoder::new(48000, &mapping, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	assert_eq!(encoder.channels(), 6);
+	assert_eq!(encoder.streams(), 4);
+	assert_eq!(encoder.coupled_streams(), 2);
+	// libopus reports the total of the bitrates it allocated to each stream,
+	// which only happens when encoding, and is subject to rounding.
+	encoder.set_bitrate(256000).expect("failed to set bitrate");
+	encoder
+		.encode(&tones(6, 0), FRAME_SIZE)
+		.expect("failed to encode");
+	let bitrate = encoder.bitrate().expect("failed to get bitrate");
+	assert!((bitrate - 256000).abs() < 100, "bitrate of {bitrate}");
+	encoder.set_complexity(5).expect("failed to set complexity");
+	assert_eq!(encoder.complexity(), Ok(5));
+
+	let mut decoder = OpusMSDecoder::new(48000, &mapping).expect("failed to create decoder");
+	let mut decoded = Vec::new();
+	for frame in 0..10 {
+		let data = encoder
+			.encode(&tones(6, frame * FRAME_SIZE), FRAME_SIZE)
+			.expect("failed to encode");
+		decoded = decoder
+			.decode(Some(&data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(decoded.len(), FRAME_SIZE * 6);
+	}
+	// Every channel, even the band-limited LFE, carries its tone through.
+	for channel in 0..6 {
+		assert!(
+			channel_energy(&decoded, 6, channel) > 1e9,
+			"channel {channel} is silent"
+		);
+	}
+
+	let pcm = sine_f32(FRAME_SIZE, 6, 440.0, 0);
+	let data = encoder
+		.encode_float(&pcm, FRAME_SIZE)
+		.expect("failed to encode");
+	assert_eq!(
+		decoder
+			.decode_float(Some(&data), FRAME_SIZE, false)
+			.map(|pcm| pcm.len()),
+		Ok(FRAME_SIZE * 6)
+	);
+	encoder.reset().expect("failed to reset");
+	assert_eq!(
+		encoder
+			.encode(&tones(6, 0)[..FRAME_SIZE], FRAME_SIZE)
+			.unwrap_err(),
+		OpusErrorCode::BadArg
+	);
+}
+
+#[test]
+fn pad_and_unpad_multistream_round_trip() {
+	let (mut encoder, mapping) = surround_5_1();
+	let streams = i32::from(mapping.streams());
+	let pcm = sine(FRAME_SIZE, 6, 440.0, 0);
+	let original = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+	let decode = |data: &[u8]| {
+		OpusMSDecoder::new(48000, &mapping)
+			.expect("failed to create decoder")
+			.decode(Some(data), FRAME_SIZE, false)
+			.expect("failed to decode")
+	};
+	let expected = decode(&original);
+
+	let mut padded = original.clone();
+	assert_eq!(
+		packet::pad_multistream(&mut padded, original.len() - 1, streams).unwrap_err(),
+		OpusErrorCode::BadArg
+	);
+	packet::pad_multistream(&mut padded, original.len() + 300, streams).expect("failed to pad");
+	assert_eq!(padded.len(), original.len() + 300);
+	assert_eq!(decode(&padded), expected);
+
+	packet::unpad_multistream(&mut padded, streams).expect("failed to unpad");
+	assert_eq!(padded.len(), original.len());
+	assert_eq!(decode(&padded), expected);
+}
+
+#[test]
+fn decodes_and_conceals_5_1() {
+	let (mut encoder, mapping) = surround_5_1();
+	let mut decoder = OpusMSDecoder::new(48000, &mapping).expect("failed to create decoder");
+	assert_eq!(decoder.channels(), 6);
+	assert_eq!(decoder.streams(), mapping.streams() as usize);
+	assert_eq!(
+		decoder.coupled_streams(),
+		mapping.coupled_streams() as usize
+	);
+	let packets = (0..5)
+		.map(|frame| {
+			encoder
+				.encode(&tones(6, frame * FRAME_SIZE), FRAME_SIZE)
+				.expect("failed to encode")
+		})
+		.collect::<Vec<_>>();
+
+	// A buffer sized for stereo is too small for 5.1.
+	let mut pcm = vec![0; FRAME_SIZE * 2];
+	assert_eq!(
+		decoder
+			.decode_into(Some(&packets[0]), &mut pcm, FRAME_SIZE, false)
+			.unwrap_err(),
+		OpusErrorCode::BufferTooSmall
+	);
+	let mut pcm = vec![0; FRAME_SIZE * 6];
+	assert_eq!(
+		decoder.decode_into(Some(&packets[0]), &mut pcm, FRAME_SIZE, false),
+		Ok(FRAME_SIZE)
+	);
+	let pcm = decoder
+		.decode_float(Some(&packets[1]), FRAME_SIZE, false)
+		.expect("failed to decode");
+	assert_eq!(pcm.len(), FRAME_SIZE * 6);
+
+	// Lose the third packet, and recover what can be from the fourth.
+	let concealed = decoder
+		.decode(None::<&[u8]>, FRAME_SIZE, false)
+		.expect("failed to conceal");
+	assert_eq!(concealed.len(), FRAME_SIZE * 6);
+	assert!(energy(&concealed) > 0.0);
+	let recovered = decoder
+		.decode(Some(&packets[3]), FRAME_SIZE, true)
+		.expect("failed to decode FEC");
+	assert_eq!(recovered.len(), FRAME_SIZE * 6);
+	let decoded = decoder
+		.decode(Some(&packets[3]), FRAME_SIZE, false)
+		.expect("failed to decode");
+	assert_eq!(decoded.len(), FRAME_SIZE * 6);
+
+	decoder.reset().expect("failed to reset");
+	assert_eq!(
+		decoder
+			.decode(Some(&packets[4]), FRAME_SIZE, false)
+			.map(|pcm| pcm.len()),
+		Ok(FRAME_SIZE * 6)
+	);
+}
+
+#[test]
+fn surround_encoders_match_rfc_7845() {
+	// The stream and coupled stream counts for 1 to 8 channels, from RFC 7845
+	// section 5.1.1.2.
+	let counts = [
+		(1, 0),
+		(1, 1),
+		(2, 1),
+		(2, 2),
+		(3, 2),
+		(4, 2),
+		(4, 3),
+		(5, 3),
+	];
+	for (channels, (streams, coupled_streams)) in (1..=8).zip(counts) {
+		let (encoder, mapping) =
+			OpusMSEncoder::new_surround(48000, channels, 1, OpusApplication::Audio)
+				.expect("failed to create encoder");
+		assert_eq!(encoder.channels(), channels as usize);
+		assert_eq!(
+			(encoder.streams(), encoder.coupled_streams()),
+			(streams, coupled_streams),
+			"{channels} channels"
+		);
+		assert_eq!(mapping.family(), 1);
+		assert_eq!(mapping.streams() as usize, streams);
+		assert_eq!(mapping.coupled_streams() as usize, coupled_streams);
+	}
+
+	let (_, mapping) = OpusMSEncoder::new_surround_5_1(SampleRate::Hz48000, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	assert_eq!(mapping.table(), [0, 4, 1, 2, 3, 5]);
+	assert_eq!(mapping, ChannelMapping::family1(ChannelLayout::Surround5_1));
+	let (_, mapping) = OpusMSEncoder::new_surround_7_1(SampleRate::Hz48000, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	assert_eq!(mapping.table(), [0, 6, 1, 2, 3, 4, 5, 7]);
+	assert_eq!(mapping, ChannelMapping::family1(ChannelLayout::Surround7_1));
+
+	// Mapping family 0 only covers mono and stereo.
+	assert!(OpusMSEncoder::new_surround(48000, 2, 0, OpusApplication::Audio).is_ok());
+	assert!(OpusMSEncoder::new_surround(48000, 3, 0, OpusApplication::Audio).is_err());
+	assert!(OpusMSEncoder::new_surround(48000, 9, 1, OpusApplication::Audio).is_err());
+}
+
+#[test]
+fn surround_constructors_round_trip() {
+	for (constructor, channels) in [
+		(OpusMSEncoder::new_surround_5_1 as fn(_, _) -> _, 6),
+		(OpusMSEncoder::new_surround_7_1, 8),
+	] {
+		let (mut encoder, mapping) = constructor(SampleRate::Hz48000, OpusApplication::Audio)
+			.expect("failed to create encoder");
+		assert_eq!(encoder.channels(), channels);
+		assert_eq!(mapping.channels(), channels);
+		let mut decoder = OpusMSDecoder::new(48000, &mapping).expect("failed to create decoder");
+		assert_eq!(decoder.channels(), channels);
+
+		let mut decoded = Vec::new();
+		for frame in 0..10 {
+			let data = encoder
+				.encode(&tones(channels, frame * FRAME_SIZE), FRAME_SIZE)
+				.expect("failed to encode");
+			decoded = decoder
+				.decode(Some(&data), FRAME_SIZE, false)
+				.expect("failed to decode");
+			assert_eq!(decoded.len(), FRAME_SIZE * channels);
+		}
+		// The LFE is last, and low-passed by the surround tuning, which drops
+		// its tone.
+		for channel in 0..channels - 1 {
+			assert!(
+				channel_energy(&decoded, channels, channel) > 1e9,
+				"{channels} channels: channel {channel} is silent"
+			);
+		}
+	}
+
+	let (encoder, _) = OpusMSEncoder::new_surround_5_1(SampleRate::Hz16000, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	assert_eq!((encoder.streams(), encoder.coupled_streams()), (4, 2));
+}
+
+#[test]
+fn channel_mapping_for_count_matches_rfc_7845() {
+	// From the table in RFC 7845 section 5.1.1.2.
+	let table: [(u8, u8, u8, &[u8]); 5] = [
+		(1, 1, 0, &[0]),
+		(2, 1, 1, &[0, 1]),
+		(3, 2, 1, &[0, 2, 1]),
+		(6, 4, 2, &[0, 4, 1, 2, 3, 5]),
+		(8, 5, 3, &[0, 6, 1, 2, 3, 4, 5, 7]),
+	];
+	for (channels, streams, coupled_streams, mapping) in table {
+		let channel_mapping = channel_mapping_for_count(channels).expect("no mapping");
+		assert_eq!(channel_mapping.family(), 1);
+		assert_eq!(channel_mapping.streams(), streams, "{channels} channels");
+		assert_eq!(
+			channel_mapping.coupled_streams(),
+			coupled_streams,
+			"{channels} channels"
+		);
+		assert_eq!(channel_mapping.table(), mapping, "{channels} channels");
+		OpusMSEncoder::new(48000, &channel_mapping, OpusApplication::Audio)
+			.expect("failed to create encoder");
+	}
+	for channels in [4, 5, 7] {
+		assert_eq!(
+			channel_mapping_for_count(channels).map(|mapping| mapping.channels()),
+			Ok(channels as usize)
+		);
+	}
+
+	for channels in [0, 9, 255] {
+		let err = channel_mapping_for_count(channels).expect_err("mapped an unsupported count");
+		assert_eq!(err, MappingError::UnsupportedChannelCount(channels));
+	}
+	assert_eq!(
+		MappingError::UnsupportedChannelCount(9).to_string(),
+		"no standard channel mapping for 9 channels, expected 1 to 8"
+	);
+}
+
+#[test]
+fn channel_mapping_validates_tables() {
+	// 5.1 with four streams, two of them coupled, has coded channels 0 to 5.
+	let mapping =
+		ChannelMapping::custom(4, 2, &[0, 4, 1, 2, 3, 5]).expect("rejected the 5.1 layout");
+	assert_eq!(mapping.family(), 255);
+	assert_eq!(mapping.streams(), 4);
+	assert_eq!(mapping.coupled_streams(), 2);
+	assert_eq!(mapping.table(), [0, 4, 1, 2, 3, 5]);
+	assert!(OpusMSDecoder::new(48000, &mapping).is_ok());
+
+	// Coded channel 6 doesn't exist, but 255 marks a silent channel.
+	assert_eq!(
+		ChannelMapping::custom(4, 2, &[0, 4, 1, 2, 3, 6]),
+		Err(OpusErrorCode::BadArg)
+	);
+	assert!(ChannelMapping::custom(4, 2, &[0, 4, 1, 2, 3, 255]).is_ok());
+	assert_eq!(
+		ChannelMapping::custom(0, 0, &[0]),
+		Err(OpusErrorCode::BadArg)
+	);
+	assert_eq!(
+		ChannelMapping::custom(1, 2, &[0]),
+		Err(OpusErrorCode::BadArg)
+	);
+	assert_eq!(
+		ChannelMapping::custom(1, 0, &[]),
+		Err(OpusErrorCode::BadArg)
+	);
+
+	assert_eq!(ChannelMapping::family0(2).unwrap().table(), [0, 1]);
+	assert_eq!(ChannelMapping::family0(3), Err(OpusErrorCode::BadArg));
+}
+
+#[test]
+fn configures_streams_separately() {
+	let (mut encoder, _) = surround_5_1();
+	// The front pair is the first coupled stream, and the LFE the last stream.
+	let mut front = encoder.stream_encoder(0).expect("failed to get stream");
+	assert_eq!(front.stream(), 0);
+	front.set_bitrate(96000).expect("failed to set bitrate");
+	front
+		.set_signal(Some(OpusSignal::Music))
+		.expect("failed to set signal");
+	let mut lfe = encoder.stream_encoder(3).expect("failed to get stream");
+	lfe.set_bitrate(8000).expect("failed to set bitrate");
+	lfe.set_bandwidth(Some(OpusBandwidth::Narrowband))
+		.expect("failed to set bandwidth");
+
+	let mut front = encoder.stream_encoder(0).expect("failed to get stream");
+	assert_eq!(front.bitrate(), Ok(96000));
+	assert_eq!(front.signal(), Ok(Some(OpusSignal::Music)));
+	let mut lfe = encoder.stream_encoder(3).expect("failed to get stream");
+	assert_eq!(lfe.bitrate(), Ok(8000));
+	assert_eq!(lfe.signal(), Ok(None));
+
+	let err = encoder
+		.stream_encoder(4)
+		.expect_err("got a stream that doesn't exist");
+	assert_eq!(err, OpusErrorCode::BadArg);
+}
@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Checks that the crate works without its `std` feature, with:
+//!
+//! ```sh
+//! cargo test -p meowlouder-opus --no-default-features --test no_std
+//! ```
+#![cfg(not(feature = "std"))]
+use meowlouder_opus::{Channels, OpusApplication, OpusDecoder, OpusEncoder, SampleRate};
+
+#[test]
+fn encode_without_std() {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let packet = encoder.encode(&[0i16; 960], 960).expect("failed to encode");
+	assert!(!packet.is_empty());
+
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Mono).expect("failed to create decoder");
+	let pcm = decoder
+		.decode(Some(&packet), 960, false)
+		.expect("failed to decode");
+	assert_eq!(pcm.len(), 960);
+}
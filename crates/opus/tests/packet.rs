@@ -0,0 +1,450 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{decoder, encode_tone, encoder, sine, FRAME_SIZE};
+use meowlouder_opus::{
+	error::{OpusError, OpusErrorCode},
+	packet, Channels, OpusApplication, OpusBandwidth, OpusEncoder, OpusMode, OpusPacket,
+	OpusPacketRef, OpusRepacketizer, SampleRate,
+};
+
+#[test]
+fn inspects_encoded_packets() {
+	for channels in [Channels::Mono, Channels::Stereo] {
+		let mut encoder = encoder(channels);
+		for frame_size in [120, 240, 480, 960, 1920, 2880] {
+			let pcm = sine(frame_size, channels.count(), 440.0, 0);
+			let data = encoder.encode(&pcm, frame_size).expect("failed to encode");
+
+			assert_eq!(packet::nb_channels(&data), Ok(channels.count()));
+			assert_eq!(packet::nb_samples(&data, 48000), Ok(frame_size));
+			let frames = packet::nb_frames(&data).expect("failed to count frames");
+			let samples_per_frame =
+				packet::samples_per_frame(&data, 48000).expect("failed to get frame size");
+			assert_eq!(frames * samples_per_frame, frame_size);
+			assert_eq!(packet::nb_samples(&data, 24000), Ok(frame_size / 2));
+		}
+	}
+}
+
+#[test]
+fn rejects_garbage_packets() {
+	for inspect in [
+		packet::nb_channels,
+		packet::nb_frames,
+		|data: &[u8]| packet::samples_per_frame(data, 48000),
+		|data: &[u8]| packet::nb_samples(data, 48000),
+	] {
+		assert_eq!(inspect(&[]).unwrap_err(), OpusErrorCode::InvalidPacket);
+	}
+	// A code 3 packet, which is missing its frame count byte.
+	assert_eq!(
+		packet::nb_frames(&[0xFF]).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+	assert_eq!(
+		packet::nb_samples(&[0xFF], 48000).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+	// A code 3 packet declaring 63 frames of 120 ms.
+	assert_eq!(
+		packet::nb_samples(&[0xFB, 0x3F], 48000).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+}
+
+#[test]
+fn classifies_packet_bandwidth() {
+	for bandwidth in [OpusBandwidth::Narrowband, OpusBandwidth::Fullband] {
+		let mut encoder = encoder(Channels::Mono);
+		encoder
+			.set_bandwidth(Some(bandwidth))
+			.expect("failed to set bandwidth");
+		let pcm = sine(FRAME_SIZE, 1, 440.0, 0);
+		let data = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		assert_eq!(packet::bandwidth(&data), Ok(bandwidth));
+	}
+	assert_eq!(
+		packet::bandwidth(&[]).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+}
+
+#[test]
+fn parses_single_frame_packet() {
+	let mut encoder = encoder(Channels::Mono);
+	let pcm = sine(FRAME_SIZE, 1, 440.0, 0);
+	let data = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+	assert_eq!(data[0] & 0x3, 0, "encoder didn't produce a code 0 packet");
+
+	let parsed = packet::parse(&data).expect("failed to parse");
+	assert_eq!(parsed.toc, data[0]);
+	assert_eq!(parsed.payload_offset, 1);
+	assert_eq!(parsed.frames, [&data[1..]]);
+}
+
+#[test]
+fn parses_multi_frame_packet() {
+	// A code 3 VBR packet with three frames of 2, 3 and 4 bytes.
+	let data = [0x03, 0x83, 2, 3, 1, 1, 2, 2, 2, 3, 3, 3, 3];
+	let parsed = packet::parse(&data).expect("failed to parse");
+	assert_eq!(parsed.toc, 0x03);
+	assert_eq!(parsed.payload_offset, 4);
+	assert_eq!(parsed.frames, [
+		&[1, 1][..],
+		&[2, 2, 2][..],
+		&[3, 3, 3, 3][..]
+	]);
+	// The frames borrow from the packet, rather than being copied.
+	let range = data.as_ptr_range();
+	for frame in &parsed.frames {
+		assert!(range.contains(&frame.as_ptr()));
+	}
+
+	// The same packet, with the second frame's length claiming more data than
+	// there is.
+	let truncated = [0x03, 0x83, 2, 10, 1, 1, 2, 2, 2];
+	assert_eq!(
+		packet::parse(&truncated).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+}
+
+#[test]
+fn pad_and_unpad_round_trip() {
+	let mut encoder = encoder(Channels::Mono);
+	let pcm = sine(FRAME_SIZE, 1, 440.0, 0);
+	let original = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+
+	let mut padded = original.clone();
+	assert_eq!(
+		packet::pad(&mut padded, original.len() - 1).unwrap_err(),
+		OpusErrorCode::BadArg
+	);
+	assert_eq!(padded, original);
+	packet::pad(&mut padded, original.len() + 100).expect("failed to pad");
+	assert_eq!(padded.len(), original.len() + 100);
+	assert_eq!(
+		decoder(Channels::Mono).decode(Some(&padded), FRAME_SIZE, false),
+		decoder(Channels::Mono).decode(Some(&original), FRAME_SIZE, false)
+	);
+
+	let mut unpadded = padded.clone();
+	packet::unpad(&mut unpadded).expect("failed to unpad");
+	assert_eq!(unpadded, original);
+}
+
+#[test]
+fn packet_newtype_validates_and_reports_duration() {
+	for data in [vec![], vec![0xFF], vec![0x03, 0x83, 2, 10, 1, 1, 2, 2, 2]] {
+		assert_eq!(
+			OpusPacket::try_from(data).unwrap_err(),
+			OpusErrorCode::InvalidPacket
+		);
+	}
+
+	let mut encoder = encoder(Channels::Stereo);
+	let mut decoder = decoder(Channels::Stereo);
+	for frame_size in [480, 960, 2880] {
+		let pcm = sine(frame_size, 2, 440.0, 0);
+		let data = encoder.encode(&pcm, frame_size).expect("failed to encode");
+		let packet = OpusPacket::try_from(data.clone()).expect("rejected a valid packet");
+		assert_eq!(packet.duration_ms(), frame_size as f32 / 48.0);
+		assert_eq!(packet.duration_samples(SampleRate::Hz48000), frame_size);
+		assert_eq!(packet.duration_samples(SampleRate::Hz16000), frame_size / 3);
+		assert_eq!(packet.channels(), 2);
+		assert_eq!(packet.frames(), packet::nb_frames(&data).unwrap());
+		assert_eq!(
+			OpusPacketRef::try_from(&data[..]).map(OpusPacketRef::to_packet),
+			Ok(packet.clone())
+		);
+
+		let pcm = decoder
+			.decode(Some(&packet), frame_size, false)
+			.expect("failed to decode");
+		assert_eq!(pcm.len(), frame_size * 2);
+	}
+}
+
+#[test]
+fn self_delimited_round_trip() {
+	let mut packets = Vec::new();
+	// The frame length takes two bytes from 252 bytes on.
+	for (frame_len, len_bytes) in [(0, 1), (1, 1), (251, 1), (252, 2), (1275, 2)] {
+		// A code 0 packet of 20 ms fullband CELT.
+		let mut data = vec![31 << 3];
+		data.extend((0..frame_len).map(|i| i as u8));
+		let delimited = packet::to_self_delimited(&data).expect("failed to delimit");
+		assert_eq!(delimited.len(), data.len() + len_bytes);
+		assert_eq!(
+			packet::from_self_delimited(&delimited),
+			Ok((data.clone(), delimited.len()))
+		);
+		packets.push(data);
+	}
+	// Encoded packets of several frames.
+	let mut encoder = encoder(Channels::Stereo);
+	for frame_size in [1920, 2880] {
+		let pcm = sine(frame_size, 2, 440.0, 0);
+		packets.push(encoder.encode(&pcm, frame_size).expect("failed to encode"));
+	}
+
+	let concatenated = packets
+		.iter()
+		.flat_map(|data| packet::to_self_delimited(data).expect("failed to delimit"))
+		.collect::<Vec<_>>();
+	let split = packet::self_delimited_packets(&concatenated)
+		.collect::<Result<Vec<_>, _>>()
+		.expect("failed to split");
+	assert_eq!(split, packets);
+
+	// The frame claims 5 bytes, but only has 2.
+	assert!(packet::from_self_delimited(&[31 << 3, 5, 1, 2]).is_err());
+	assert!(packet::from_self_delimited(&[]).is_err());
+}
+
+#[test]
+fn detects_dtx_packets() {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	encoder.set_dtx(true).expect("failed to enable DTX");
+	let mut decoder = decoder(Channels::Mono);
+	let silence = vec![0; FRAME_SIZE];
+	let mut dtx_packets = 0;
+	for frame in 0..100 {
+		// Some speech, and then silence for the encoder to stop sending.
+		let pcm = if frame < 10 {
+			sine(FRAME_SIZE, 1, 300.0, frame * FRAME_SIZE)
+		} else {
+			silence.clone()
+		};
+		let data = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		if frame < 5 {
+			assert!(!packet::is_dtx(&data));
+			assert!(!packet::is_silence_candidate(&data));
+		}
+		if packet::is_dtx(&data) {
+			dtx_packets += 1;
+			assert!(packet::is_silence_candidate(&data));
+		}
+		decoder
+			.decode(Some(&data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(decoder.last_packet_was_dtx(), packet::is_dtx(&data));
+	}
+	assert!(dtx_packets > 50, "only {dtx_packets} DTX packets");
+	decoder.conceal(FRAME_SIZE).expect("failed to conceal");
+	assert!(!decoder.last_packet_was_dtx());
+
+	assert!(packet::is_dtx(&[]));
+	// Code 3 packets with zero frames, without and with padding.
+	assert!(packet::is_dtx(&[0x03, 0x00, 0x00, 0x00]));
+	assert!(packet::is_dtx(&[0x03, 0x40, 0x05, 0, 0, 0, 0, 0]));
+	// A code 2 packet, which always has two frames.
+	assert!(!packet::is_dtx(&[0x02, 0x00, 0x00, 0x00]));
+}
+
+#[test]
+fn validates_packet_structure() {
+	let packets = encode_tone(&mut encoder(Channels::Stereo), 3);
+	for data in &packets {
+		assert_eq!(packet::validate_packet(data), Ok(()));
+	}
+	let invalid: [&[u8]; 5] = [
+		// Empty.
+		&[],
+		// A code 1 packet, whose two frames must be the same length, with an
+		// odd number of bytes for them.
+		&[0x01, 0, 0, 0],
+		// A code 2 packet whose first frame is longer than the packet.
+		&[0x02, 10, 0],
+		// A code 3 packet, which is missing its frame count byte.
+		&[0x03],
+		// A code 3 packet declaring 63 frames of 120 ms.
+		&[0xFB, 0x3F],
+	];
+	for data in invalid {
+		let err = packet::validate_packet(data).expect_err("validated an invalid packet");
+		assert_eq!(err, OpusErrorCode::InvalidPacket, "{data:?}");
+	}
+
+	// Rejected packets don't touch the decoder state.
+	let mut decoder = decoder(Channels::Stereo);
+	decoder
+		.decode_validated(&packets[0], FRAME_SIZE, false)
+		.expect("failed to decode");
+	let mut expected_decoder = decoder.clone();
+	for data in invalid {
+		let err = decoder
+			.decode_validated(data, FRAME_SIZE, false)
+			.expect_err("decoded an invalid packet");
+		assert_eq!(err, OpusErrorCode::InvalidPacket);
+	}
+	assert_eq!(
+		decoder.decode_validated(&packets[1], FRAME_SIZE, false),
+		expected_decoder.decode(Some(&packets[1]), FRAME_SIZE, false)
+	);
+}
+
+#[test]
+fn encodes_and_decodes_self_delimited_packets() {
+	let mut encoder = encoder(Channels::Mono);
+	// Small enough packets for their frame length to take a single byte.
+	encoder.set_bitrate(32000).expect("failed to set bitrate");
+	let mut expected_encoder = encoder.clone();
+	let mut delimited = Vec::new();
+	let mut expected = Vec::new();
+	for frame in 0..3 {
+		let pcm = sine(FRAME_SIZE, 1, 440.0, frame * FRAME_SIZE);
+		let data = encoder
+			.encode_self_delimited(&pcm, FRAME_SIZE)
+			.expect("failed to encode");
+		let undelimited = expected_encoder
+			.encode(&pcm, FRAME_SIZE)
+			.expect("failed to encode");
+		// The TOC byte is unchanged, and followed by the frame's length.
+		assert_eq!(data[0], undelimited[0]);
+		assert_eq!(usize::from(data[1]), undelimited.len() - 1);
+		assert_eq!(data.len(), undelimited.len() + 1);
+		delimited.extend(data);
+		expected.push(undelimited);
+	}
+
+	// Each packet is decoded straight out of the stream, without a length
+	// prefix.
+	let mut decoder = decoder(Channels::Mono);
+	let mut expected_decoder = decoder.clone();
+	let mut stream = &delimited[..];
+	for undelimited in &expected {
+		let pcm = decoder
+			.decode_self_delimited(stream, FRAME_SIZE)
+			.expect("failed to decode");
+		assert_eq!(
+			pcm,
+			expected_decoder
+				.decode(Some(undelimited), FRAME_SIZE, false)
+				.expect("failed to decode")
+		);
+		stream = &stream[undelimited.len() + 1..];
+	}
+	assert!(stream.is_empty());
+}
+
+#[test]
+fn pads_to_a_fixed_size() {
+	let mut encoder = encoder(Channels::Stereo);
+	encoder.set_bitrate(32000).expect("failed to set bitrate");
+	for data in encode_tone(&mut encoder, 5) {
+		let mut padded = data.clone();
+		packet::pad(&mut padded, 200).expect("failed to pad");
+		assert_eq!(padded.len(), 200);
+		assert_eq!(packet::validate_packet(&padded), Ok(()));
+		// Padding to the current length does nothing.
+		let before = padded.clone();
+		packet::pad(&mut padded, 200).expect("failed to pad");
+		assert_eq!(padded, before);
+
+		packet::unpad(&mut padded).expect("failed to unpad");
+		assert_eq!(padded, data);
+	}
+}
+
+#[test]
+fn toc_fields_follow_the_encoding_mode() {
+	for (application, bandwidth, bitrate, mode) in [
+		(
+			OpusApplication::Voip,
+			OpusBandwidth::Narrowband,
+			12000,
+			OpusMode::Silk,
+		),
+		(
+			OpusApplication::Voip,
+			OpusBandwidth::Wideband,
+			16000,
+			OpusMode::Silk,
+		),
+		(
+			OpusApplication::Voip,
+			OpusBandwidth::Superwideband,
+			32000,
+			OpusMode::Hybrid,
+		),
+		(
+			OpusApplication::Audio,
+			OpusBandwidth::Fullband,
+			128000,
+			OpusMode::Celt,
+		),
+	] {
+		let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Stereo, application)
+			.expect("failed to create encoder");
+		encoder.set_bitrate(bitrate).expect("failed to set bitrate");
+		encoder
+			.set_bandwidth(Some(bandwidth))
+			.expect("failed to set bandwidth");
+		// The mode settles after the first few frames.
+		let data = encode_tone(&mut encoder, 10).pop().unwrap();
+		let packet = OpusPacket::try_from(data).expect("invalid packet");
+		assert_eq!(packet.mode(), mode, "{application:?} at {bandwidth:?}");
+		assert_eq!(packet.toc().bandwidth(), bandwidth);
+
+		let toc_byte = packet.toc_byte();
+		assert_eq!(toc_byte, packet[0]);
+		assert_eq!(packet.config_index(), toc_byte >> 3);
+		assert_eq!(packet.stereo(), toc_byte & 0b100 != 0);
+		assert_eq!(packet.frame_count_code(), toc_byte & 0b11);
+	}
+
+	// The configuration ranges for each mode.
+	for config in 0..32u8 {
+		let data = [config << 3 | 0b101];
+		let packet = OpusPacketRef::try_from(&data[..]).expect("invalid packet");
+		let mode = match config {
+			0..=11 => OpusMode::Silk,
+			12..=15 => OpusMode::Hybrid,
+			_ => OpusMode::Celt,
+		};
+		assert_eq!(packet.mode(), mode, "config {config}");
+		assert_eq!(packet.config_index(), config);
+		assert!(packet.stereo());
+		assert_eq!(packet.frame_count_code(), 1);
+	}
+}
+
+#[test]
+fn rejects_lengths_libopus_cannot_represent() {
+	// Zeroed, so only the pages actually read are ever touched.
+	let mut huge = vec![0u8; i32::MAX as usize + 1];
+	fn bad_arg<T: std::fmt::Debug>(result: Result<T, OpusError>) {
+		assert_eq!(result.unwrap_err(), OpusErrorCode::BadArg);
+	}
+	bad_arg(packet::nb_frames(&huge));
+	bad_arg(packet::nb_samples(&huge, 48000));
+	bad_arg(packet::parse(&huge));
+	bad_arg(packet::unpad(&mut huge));
+	bad_arg(packet::unpad_multistream(&mut huge, 1));
+	assert_eq!(huge.len(), i32::MAX as usize + 1);
+
+	let mut decoder = decoder(Channels::Mono);
+	bad_arg(decoder.nb_samples(&huge));
+	bad_arg(decoder.decode(Some(&huge), FRAME_SIZE, false));
+	let mut repacketizer = OpusRepacketizer::new();
+	bad_arg(repacketizer.cat(&huge));
+	drop(repacketizer);
+	drop(huge);
+
+	// Padding past the largest length is rejected before allocating anything.
+	let mut data = encode_tone(&mut encoder(Channels::Mono), 1).remove(0);
+	let original = data.clone();
+	bad_arg(packet::pad(&mut data, usize::MAX / 2));
+	bad_arg(packet::pad_multistream(&mut data, i32::MAX as usize + 1, 1));
+	assert_eq!(data, original);
+
+	let mut repacketizer = OpusRepacketizer::new();
+	repacketizer.cat(&data).expect("failed to add packet");
+	bad_arg(repacketizer.out_range(0, usize::MAX / 2, 4000));
+	bad_arg(repacketizer.out_range(i32::MAX as usize + 1, 0, 4000));
+	// An oversized output buffer is fine, as long as the packet fits.
+	assert_eq!(repacketizer.out_range(0, 1, 4000), Ok(original));
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{decoder, encode_tone, encoder, FRAME_SIZE};
+use meowlouder_opus::{error::OpusErrorCode, Channels, PacketPadder};
+
+const TARGET_LEN: usize = 400;
+
+#[test]
+fn pads_every_packet_to_target_len() {
+	let mut encoder = encoder(Channels::Stereo);
+	encoder.set_vbr(true).expect("failed to enable VBR");
+	let packets = encode_tone(&mut encoder, 50);
+	assert!(packets
+		.iter()
+		.any(|packet| packet.len() != packets[0].len()));
+
+	let mut padder = PacketPadder::new(TARGET_LEN);
+	let mut original_decoder = decoder(Channels::Stereo);
+	let mut padded_decoder = decoder(Channels::Stereo);
+	let mut expected_padding = 0;
+	for packet in &packets {
+		let mut padded = packet.clone();
+		padder.pad(&mut padded).expect("failed to pad");
+		assert_eq!(padded.len(), TARGET_LEN);
+		expected_padding += (TARGET_LEN - packet.len()) as u64;
+		assert_eq!(
+			padded_decoder.decode(Some(&padded), FRAME_SIZE, false),
+			original_decoder.decode(Some(packet), FRAME_SIZE, false)
+		);
+	}
+	assert_eq!(padder.packets_padded(), packets.len() as u64);
+	assert_eq!(padder.padding_added(), expected_padding);
+}
+
+#[test]
+fn rejects_packets_over_target_len() {
+	let packets = encode_tone(&mut encoder(Channels::Stereo), 3);
+	let mut padder = PacketPadder::new(packets[0].len() - 1);
+	let mut packet = packets[0].clone();
+	assert_eq!(padder.pad(&mut packet).unwrap_err(), OpusErrorCode::BadArg);
+	assert_eq!(packet, packets[0]);
+	assert_eq!(padder.packets_padded(), 0);
+
+	// Merging three packets can't fit in the size of one.
+	let packets = packets.iter().map(Vec::as_slice).collect::<Vec<_>>();
+	assert!(padder.repacketize(&packets).is_err());
+	let mut padder = PacketPadder::new(TARGET_LEN * 3);
+	let merged = padder.repacketize(&packets).expect("failed to repacketize");
+	assert_eq!(merged.len(), TARGET_LEN * 3);
+}
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{encode_tone, encoder, sine, FRAME_SIZE};
+use meowlouder_opus::{BufferPool, Channels};
+
+#[test]
+fn encode_pooled_reuses_buffers() {
+	let pool = BufferPool::new(2);
+	assert_eq!(pool.capacity(), 2);
+	assert!(pool.is_empty());
+
+	let mut encoder = encoder(Channels::Mono);
+	let mut expected_encoder = encoder.clone();
+	let expected = encode_tone(&mut expected_encoder, 4);
+	let pcm = (0..4)
+		.map(|frame| sine(FRAME_SIZE, 1, 440.0, frame * FRAME_SIZE))
+		.collect::<Vec<_>>();
+
+	// The pool starts empty, so the first packets are fresh allocations.
+	let a = encoder
+		.encode_pooled(&pcm[0], FRAME_SIZE, &pool)
+		.expect("failed to encode");
+	let b = encoder
+		.encode_pooled(&pcm[1], FRAME_SIZE, &pool)
+		.expect("failed to encode");
+	assert_eq!(*a, *expected[0]);
+	assert_eq!(*b, *expected[1]);
+	let a_ptr = a.as_ptr();
+	drop(a);
+	drop(b);
+	assert_eq!(pool.len(), 2);
+
+	// Returned buffers are taken again rather than allocating.
+	let c = encoder
+		.encode_pooled(&pcm[2], FRAME_SIZE, &pool)
+		.expect("failed to encode");
+	assert_eq!(*c, *expected[2]);
+	assert_eq!(c.as_ptr(), a_ptr);
+	assert_eq!(pool.len(), 1);
+
+	// Once taken for good, a buffer isn't returned.
+	let d = encoder
+		.encode_pooled(&pcm[3], FRAME_SIZE, &pool)
+		.expect("failed to encode")
+		.into_vec();
+	assert_eq!(d, expected[3]);
+	assert!(pool.is_empty());
+	drop(c);
+	assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn pool_is_bounded() {
+	let pool = BufferPool::new(1);
+	let packets = (0..3).map(|_| pool.get()).collect::<Vec<_>>();
+	drop(packets);
+	// The fresh buffers had no allocation, so none were worth keeping.
+	assert!(pool.is_empty());
+
+	let mut packets = (0..3).map(|_| pool.get()).collect::<Vec<_>>();
+	for packet in &mut packets {
+		packet.as_mut_vec().push(0);
+	}
+	drop(packets);
+	assert_eq!(pool.len(), 1);
+	assert!(pool.get().is_empty());
+	assert_eq!(BufferPool::default().capacity(), 64);
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{sine_f32, FRAME_SIZE};
+use meowlouder_opus::{
+	error::OpusErrorCode, OpusApplication, OpusProjectionDecoder, OpusProjectionEncoder,
+};
+
+/// Generates a frame of interleaved noise, different in every channel.
+fn noise(channels: usize, seed: u32) -> Vec<f32> {
+	let mut state = seed | 1;
+	(0..FRAME_SIZE * channels)
+		.map(|_| {
+			// A xorshift generator, so the input is the same on every run.
+			state ^= state << 13;
+			state ^= state >> 17;
+			state ^= state << 5;
+			0.5 * (state as f32 / u32::MAX as f32 - 0.5)
+		})
+		.collect()
+}
+
+#[test]
+fn encodes_first_order_ambisonics() {
+	let mut encoder = OpusProjectionEncoder::new(48000, 4, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	assert_eq!(encoder.channels(), 4);
+	assert_eq!((encoder.streams(), encoder.coupled_streams()), (2, 2));
+	for seed in 0..5 {
+		let data = encoder
+			.encode_float(&noise(4, seed), FRAME_SIZE)
+			.expect("failed to encode");
+		assert!(!data.is_empty());
+	}
+
+	// A 16-bit coefficient for each of the 4 output channels and 4 coded
+	// channels.
+	let size = encoder
+		.demixing_matrix_size()
+		.expect("failed to get demixing matrix size");
+	assert_eq!(size, 2 * 4 * (2 + 2));
+	let matrix = encoder
+		.demixing_matrix()
+		.expect("failed to get demixing matrix");
+	assert_eq!(matrix.len(), size);
+	assert!(matrix.iter().any(|&byte| byte != 0));
+
+	// First order ambisonics with a non-diegetic stereo pair.
+	let mut encoder = OpusProjectionEncoder::new(48000, 6, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let coded_channels = encoder.streams() + encoder.coupled_streams();
+	assert_eq!(encoder.demixing_matrix_size(), Ok(2 * 6 * coded_channels));
+
+	let err = OpusProjectionEncoder::new(48000, 5, OpusApplication::Audio)
+		.expect_err("created an encoder for 5 channels");
+	assert_eq!(err, OpusErrorCode::BadArg);
+}
+
+/// Generates a frame of interleaved float PCM with a different tone in each
+/// channel, starting `offset` samples in.
+fn tones(channels: usize, offset: usize) -> Vec<f32> {
+	let per_channel = (0..channels)
+		.map(|channel| sine_f32(FRAME_SIZE, 1, 300.0 + 200.0 * channel as f32, offset))
+		.collect::<Vec<_>>();
+	(0..FRAME_SIZE)
+		.flat_map(|i| per_channel.iter().map(move |channel| channel[i]))
+		.collect()
+}
+
+fn energy(pcm: &[f32]) -> f64 {
+	pcm.iter().map(|&sample| f64::from(sample).powi(2)).sum()
+}
+
+#[test]
+fn projection_round_trip() {
+	let mut encoder = OpusProjectionEncoder::new(48000, 4, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	encoder.set_bitrate(256000).expect("failed to set bitrate");
+	// The demixing matrix is all the decoder needs from the encoder, besides
+	// the stream counts, as it would be carried in the stream header.
+	let matrix = encoder
+		.demixing_matrix()
+		.expect("failed to get demixing matrix");
+	let mut decoder = OpusProjectionDecoder::new(
+		48000,
+		4,
+		encoder.streams() as u8,
+		encoder.coupled_streams() as u8,
+		&matrix,
+	)
+	.expect("failed to create decoder");
+	assert_eq!(decoder.channels(), 4);
+	assert_eq!(decoder.streams(), encoder.streams());
+	assert_eq!(decoder.coupled_streams(), encoder.coupled_streams());
+
+	let mut input_energy = 0.0;
+	let mut output_energy = 0.0;
+	for frame in 0..20 {
+		let pcm = tones(4, frame * FRAME_SIZE);
+		let data = encoder
+			.encode_float(&pcm, FRAME_SIZE)
+			.expect("failed to encode");
+		let decoded = decoder
+			.decode_float(Some(&data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(decoded.len(), FRAME_SIZE * 4);
+		// Skip the first frames, which are mostly the encoder's lookahead.
+		if frame >= 2 {
+			input_energy += energy(&pcm);
+			output_energy += energy(&decoded);
+		}
+	}
+	let ratio = output_energy / input_energy;
+	assert!((0.5..2.0).contains(&ratio), "energy ratio of {ratio}");
+
+	let concealed = decoder
+		.decode(None::<&[u8]>, FRAME_SIZE, false)
+		.expect("failed to conceal");
+	assert_eq!(concealed.len(), FRAME_SIZE * 4);
+
+	// The matrix must match the stream counts.
+	let err = OpusProjectionDecoder::new(48000, 4, 2, 2, &matrix[..matrix.len() - 2])
+		.expect_err("created a decoder with a truncated matrix");
+	assert_eq!(err, OpusErrorCode::BadArg);
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MPL-2.0
+// `?` converts errors into proptest failures through `std::error::Error`.
+#![cfg(feature = "std")]
+use meowlouder_opus::{
+	error::OpusErrorCode, Channels, FrameDuration, FrameSize, OpusApplication, OpusDecoder,
+	OpusEncoder, SampleRate,
+};
+use proptest::prelude::*;
+use std::fmt::Debug;
+
+/// A valid frame of PCM to encode, along with the format it's in.
+#[derive(Debug, Clone)]
+struct Frame<T> {
+	sample_rate: SampleRate,
+	channels: Channels,
+	frame_size: usize,
+	pcm: Vec<T>,
+}
+
+fn sample_rate() -> impl Strategy<Value = SampleRate> {
+	prop_oneof![
+		Just(SampleRate::Hz8000),
+		Just(SampleRate::Hz12000),
+		Just(SampleRate::Hz16000),
+		Just(SampleRate::Hz24000),
+		Just(SampleRate::Hz48000),
+	]
+}
+
+fn channels() -> impl Strategy<Value = Channels> {
+	prop_oneof![Just(Channels::Mono), Just(Channels::Stereo)]
+}
+
+/// The frame durations from 2.5 to 60 ms, which are 120 to 2880 samples at
+/// 48 kHz.
+fn frame_duration() -> impl Strategy<Value = FrameDuration> {
+	prop_oneof![
+		Just(FrameDuration::Ms2_5),
+		Just(FrameDuration::Ms5),
+		Just(FrameDuration::Ms10),
+		Just(FrameDuration::Ms20),
+		Just(FrameDuration::Ms40),
+		Just(FrameDuration::Ms60),
+	]
+}
+
+/// Generates frames of exactly `frame_size * channels` samples, so empty PCM
+/// and mismatched frame sizes are never generated.
+fn frame<T: Debug + Clone + 'static>(
+	sample: impl Strategy<Value = T> + Clone,
+) -> impl Strategy<Value = Frame<T>> {
+	(sample_rate(), channels(), frame_duration()).prop_flat_map(
+		move |(sample_rate, channels, duration)| {
+			let frame_size = FrameSize::from_duration(duration, sample_rate).samples();
+			prop::collection::vec(sample.clone(), frame_size * channels.count()).prop_map(
+				move |pcm| Frame {
+					sample_rate,
+					channels,
+					frame_size,
+					pcm,
+				},
+			)
+		},
+	)
+}
+
+fn codecs<T>(frame: &Frame<T>) -> (OpusEncoder, OpusDecoder) {
+	let encoder = OpusEncoder::new(frame.sample_rate, frame.channels, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let decoder =
+		OpusDecoder::new(frame.sample_rate, frame.channels).expect("failed to create decoder");
+	(encoder, decoder)
+}
+
+proptest! {
+	#[test]
+	fn round_trip_i16(frame in frame(any::<i16>())) {
+		let (mut encoder, mut decoder) = codecs(&frame);
+		let packet = encoder.encode(&frame.pcm, frame.frame_size)?;
+		prop_assert!(!packet.is_empty());
+		let decoded = decoder.decode(Some(&packet), frame.frame_size, false);
+		prop_assert!(!matches!(decoded, Err(err) if err == OpusErrorCode::InvalidPacket));
+		prop_assert_eq!(decoded?.len(), frame.pcm.len());
+	}
+
+	#[test]
+	fn round_trip_f32(frame in frame(-1.0f32..=1.0)) {
+		let (mut encoder, mut decoder) = codecs(&frame);
+		let packet = encoder.encode(&frame.pcm, frame.frame_size)?;
+		prop_assert!(!packet.is_empty());
+		let decoded = decoder.decode_float(Some(&packet), frame.frame_size, false);
+		prop_assert!(!matches!(decoded, Err(err) if err == OpusErrorCode::InvalidPacket));
+		prop_assert_eq!(decoded?.len(), frame.pcm.len());
+	}
+}
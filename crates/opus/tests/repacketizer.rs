@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{decoder, encode_tone, encoder, sine, FRAME_SIZE};
+use meowlouder_opus::{
+	error::OpusErrorCode, packet, Channels, FrameDuration, OpusBandwidth, OpusPacket,
+	OpusRepacketizer, SampleRate,
+};
+
+/// Decodes each packet in turn with a single decoder, concatenating the PCM.
+fn decode_all(packets: &[impl AsRef<[u8]>], channels: Channels) -> Vec<i16> {
+	let mut decoder = decoder(channels);
+	packets
+		.iter()
+		.flat_map(|packet| {
+			decoder
+				.decode(Some(packet), 5760, false)
+				.expect("failed to decode")
+		})
+		.collect()
+}
+
+#[test]
+fn merges_three_frames_into_one_packet() {
+	let packets = encode_tone(&mut encoder(Channels::Stereo), 3);
+	let mut repacketizer = OpusRepacketizer::new();
+	for packet in &packets {
+		repacketizer.cat(packet).expect("failed to add packet");
+	}
+	assert_eq!(repacketizer.nb_frames(), 3);
+	let merged = repacketizer.out(4000).expect("failed to merge");
+	assert_eq!(packet::nb_frames(&merged), Ok(3));
+	assert_eq!(packet::nb_samples(&merged, 48000), Ok(FRAME_SIZE * 3));
+
+	assert_eq!(
+		decode_all(&[&merged], Channels::Stereo),
+		decode_all(&packets, Channels::Stereo)
+	);
+
+	// Taking only the last two frames.
+	let tail = repacketizer.out_range(1, 3, 4000).expect("failed to merge");
+	assert_eq!(packet::nb_frames(&tail), Ok(2));
+}
+
+#[test]
+fn combine_frames_makes_one_60_ms_packet() {
+	let packets = encode_tone(&mut encoder(Channels::Stereo), 3);
+	let packets = packets.iter().map(Vec::as_slice).collect::<Vec<_>>();
+	let combined = OpusRepacketizer::combine_frames(&packets).expect("failed to combine");
+	let combined = OpusPacket::try_from(combined).expect("invalid packet");
+	assert_eq!(combined.frames(), 3);
+	assert_eq!(
+		combined.toc().samples_per_frame(SampleRate::Hz48000) * 3,
+		packet::nb_samples(&combined, 48000).expect("invalid packet")
+	);
+	assert_eq!(
+		decode_all(&[&combined], Channels::Stereo),
+		decode_all(&packets, Channels::Stereo)
+	);
+
+	let err = OpusRepacketizer::combine_frames(&[]).expect_err("combined no packets");
+	assert_eq!(err, OpusErrorCode::BadArg);
+	// Seven 20 ms frames is over 120 ms.
+	let packets = encode_tone(&mut encoder(Channels::Stereo), 7);
+	let packets = packets.iter().map(Vec::as_slice).collect::<Vec<_>>();
+	let err = OpusRepacketizer::combine_frames(&packets).expect_err("combined 140 ms");
+	assert_eq!(err, OpusErrorCode::InvalidPacket);
+}
+
+#[test]
+fn combines_packets_up_to_max_duration() {
+	let packets = encode_tone(&mut encoder(Channels::Stereo), 6);
+	let packets = packets.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+	let combined =
+		packet::combine_packets(&packets, FrameDuration::Ms120).expect("failed to combine");
+	assert_eq!(combined.len(), 1);
+	assert_eq!(packet::nb_samples(&combined[0], 48000), Ok(FRAME_SIZE * 6));
+	let decoded = decode_all(&combined, Channels::Stereo);
+	assert_eq!(decoded.len(), FRAME_SIZE * 6 * 2);
+	assert_eq!(decoded, decode_all(&packets, Channels::Stereo));
+
+	let combined =
+		packet::combine_packets(&packets, FrameDuration::Ms40).expect("failed to combine");
+	assert_eq!(combined.len(), 3);
+	assert_eq!(
+		decode_all(&combined, Channels::Stereo).len(),
+		FRAME_SIZE * 6 * 2
+	);
+}
+
+#[test]
+fn refuses_to_combine_different_configurations() {
+	let mut wideband = encoder(Channels::Stereo);
+	wideband
+		.set_bandwidth(Some(OpusBandwidth::Wideband))
+		.expect("failed to set bandwidth");
+	let a = encode_tone(&mut encoder(Channels::Stereo), 1);
+	let b = encode_tone(&mut wideband, 1);
+	assert_ne!(a[0][0] >> 3, b[0][0] >> 3);
+	assert_eq!(
+		packet::combine_packets(&[&a[0], &b[0]], FrameDuration::Ms120).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+	assert_eq!(
+		OpusRepacketizer::combine_frames(&[&a[0], &a[0], &b[0]]).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+	// Mono and stereo packets can't be mixed either.
+	let mono = encode_tone(&mut encoder(Channels::Mono), 1);
+	assert_eq!(
+		OpusRepacketizer::combine_frames(&[&mono[0], &a[0]]).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+	assert_eq!(
+		packet::combine_packets(&[&mono[0], &a[0]], FrameDuration::Ms120).unwrap_err(),
+		OpusErrorCode::InvalidPacket
+	);
+}
+
+#[test]
+fn splits_packet_into_frames() {
+	let mut encoder = encoder(Channels::Stereo);
+	let pcm = sine(FRAME_SIZE * 3, 2, 440.0, 0);
+	let original = encoder
+		.encode_duration(&pcm, FrameDuration::Ms60)
+		.expect("failed to encode");
+	let frames = packet::nb_frames(&original).expect("failed to count frames");
+	assert!(frames > 1, "encoder produced a single 60 ms frame");
+
+	let split = packet::split_packet(&original).expect("failed to split");
+	assert_eq!(split.len(), frames);
+	for piece in &split {
+		assert_eq!(packet::nb_frames(piece), Ok(1));
+		assert_eq!(piece[0] >> 3, original[0] >> 3);
+	}
+	// Decoding the pieces in turn with a fresh decoder gives the same audio as
+	// decoding the original.
+	assert_eq!(
+		decode_all(&split, Channels::Stereo),
+		decode_all(&[&original], Channels::Stereo)
+	);
+
+	let single = encode_tone(&mut encoder, 1).remove(0);
+	assert_eq!(packet::split_packet(&single), Ok(vec![single.clone()]));
+}
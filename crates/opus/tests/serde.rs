@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: MPL-2.0
+#![cfg(feature = "serde")]
+mod common;
+
+use common::encoder;
+use meowlouder_opus::{Channels, OpusApplication, OpusBandwidth, OpusEncoderConfig, OpusSignal};
+
+#[test]
+fn encoder_config_round_trips() {
+	let mut encoder = encoder(Channels::Stereo);
+	encoder.set_bitrate(64000).expect("failed to set bitrate");
+	encoder.set_complexity(8).expect("failed to set complexity");
+	encoder
+		.set_signal(Some(OpusSignal::Music))
+		.expect("failed to set signal");
+	let config = encoder.snapshot().expect("failed to snapshot");
+
+	let json = serde_json::to_string(&config).expect("failed to serialize");
+	assert!(json.contains(r#""bitrate":64000"#), "{json}");
+	assert!(json.contains(r#""complexity":8"#), "{json}");
+	assert!(json.contains(r#""signal":"music""#), "{json}");
+	let restored = serde_json::from_str::<OpusEncoderConfig>(&json).expect("failed to deserialize");
+	assert_eq!(restored.bitrate, config.bitrate);
+	assert_eq!(restored.complexity, config.complexity);
+	assert_eq!(restored.vbr, config.vbr);
+	assert_eq!(restored.signal, config.signal);
+	assert_eq!(restored.max_bandwidth, config.max_bandwidth);
+	assert_eq!(restored, config);
+}
+
+#[test]
+fn enums_serialize_as_names() {
+	assert_eq!(
+		serde_json::to_string(&OpusApplication::Voip).expect("failed to serialize"),
+		r#""voip""#
+	);
+	assert_eq!(
+		serde_json::from_str::<OpusBandwidth>(r#""wideband""#).expect("failed to deserialize"),
+		OpusBandwidth::Wideband
+	);
+	assert_eq!(
+		serde_json::from_str::<OpusSignal>(r#""voice""#).expect("failed to deserialize"),
+		OpusSignal::Voice
+	);
+	assert!(serde_json::from_str::<OpusApplication>(r#""podcast""#).is_err());
+}
@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: MPL-2.0
+#![cfg(feature = "smallvec")]
+mod common;
+
+use common::{encoder, sine, FRAME_SIZE};
+use meowlouder_opus::Channels;
+
+#[test]
+fn encode_small_stays_inline() {
+	let mut encoder = encoder(Channels::Mono);
+	encoder.set_bitrate(32000).expect("failed to set bitrate");
+	encoder.set_vbr(false).expect("failed to disable VBR");
+	let mut expected_encoder = encoder.clone();
+	for frame in 0..10 {
+		let pcm = sine(FRAME_SIZE, 1, 440.0, frame * FRAME_SIZE);
+		let data = encoder
+			.encode_small(&pcm, FRAME_SIZE)
+			.expect("failed to encode");
+		// 32 kbps for 20 ms is exactly 80 bytes with a constant bitrate.
+		assert_eq!(data.len(), 80);
+		assert!(!data.spilled(), "the packet was moved to the heap");
+		let expected = expected_encoder
+			.encode(&pcm, FRAME_SIZE)
+			.expect("failed to encode");
+		assert_eq!(*data, *expected);
+	}
+}
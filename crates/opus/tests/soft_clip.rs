@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MPL-2.0
+mod common;
+
+use common::{decoder, encode_tone, encoder, sine_f32, FRAME_SIZE};
+use meowlouder_opus::{error::OpusErrorCode, util, Channels, OpusSoftClip};
+
+#[test]
+fn soft_clip_keeps_over_range_sine_in_range() {
+	// A 100 Hz sine at twice full scale, so the steepest change between
+	// samples is about 2 * 2 * pi * 100 / 48000.
+	let mut pcm = sine_f32(4800, 2, 100.0, 0)
+		.into_iter()
+		.map(|sample| sample * 4.0)
+		.collect::<Vec<_>>();
+	let mut clip = OpusSoftClip::new();
+	// Clipping it in blocks mustn't introduce discontinuities at the block
+	// boundaries.
+	for block in pcm.chunks_mut(2 * 480) {
+		clip.process(block, 2).expect("failed to clip");
+	}
+
+	assert!(pcm.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+	assert!(pcm.iter().any(|sample| sample.abs() > 0.9));
+	let max_step = pcm
+		.chunks(2)
+		.zip(pcm.chunks(2).skip(1))
+		.map(|(a, b)| (b[0] - a[0]).abs())
+		.fold(0.0, f32::max);
+	assert!(max_step < 0.05, "discontinuity of {max_step}");
+}
+
+#[test]
+fn soft_clip_rejects_mismatched_channels() {
+	let mut clip = OpusSoftClip::new();
+	let mut pcm = [0.0; 5];
+	assert_eq!(
+		clip.process(&mut pcm, 2).unwrap_err(),
+		OpusErrorCode::BadArg
+	);
+	assert_eq!(
+		clip.process(&mut pcm, 0).unwrap_err(),
+		OpusErrorCode::BadArg
+	);
+	clip.process(&mut pcm, 1).expect("failed to clip");
+}
+
+#[test]
+fn soft_clip_tames_decoder_gain() {
+	let packets = encode_tone(&mut encoder(Channels::Stereo), 10);
+	let mut decoder = decoder(Channels::Stereo);
+	// A 0.5 amplitude tone boosted by 24 dB peaks at about 8.
+	decoder
+		.set_gain(util::db_to_q8(24.0))
+		.expect("failed to set gain");
+	let mut clip = OpusSoftClip::new();
+	let mut memory = [0.0; 2];
+	let mut over_range = false;
+	for data in &packets {
+		let mut pcm = decoder
+			.decode_float(Some(data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		over_range |= pcm.iter().any(|sample| sample.abs() > 1.0);
+		let mut clipped = pcm.clone();
+		clip.process(&mut clipped, 2).expect("failed to clip");
+		assert!(clipped.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+		// The free function gives the same result, with the state kept by
+		// the caller.
+		util::soft_clip(&mut pcm, 2, &mut memory).expect("failed to clip");
+		assert_eq!(pcm, clipped);
+	}
+	assert!(over_range, "the gain didn't push the audio out of range");
+	assert!(memory.iter().any(|&memory| memory != 0.0));
+	assert_eq!(
+		util::soft_clip(&mut [0.0; 4], 2, &mut [0.0; 1]).unwrap_err(),
+		OpusErrorCode::BadArg
+	);
+}
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::{
+	Channels, OpusApplication, OpusDecoder, OpusEncoder, OpusMSDecoder, OpusMSEncoder,
+	OpusProjectionDecoder, OpusProjectionEncoder, OpusRepacketizer, SampleRate,
+};
+use static_assertions::{assert_impl_all, assert_not_impl_any};
+use std::{
+	sync::{mpsc, Arc, Mutex},
+	thread,
+};
+
+assert_impl_all!(OpusEncoder: Send);
+assert_impl_all!(OpusDecoder: Send);
+assert_not_impl_any!(OpusEncoder: Sync);
+assert_not_impl_any!(OpusDecoder: Sync);
+assert_impl_all!(Mutex<OpusEncoder>: Send, Sync);
+assert_impl_all!(Mutex<OpusDecoder>: Send, Sync);
+
+// The other codec states wrap libopus state the same way.
+assert_impl_all!(OpusMSEncoder: Send);
+assert_impl_all!(OpusMSDecoder: Send);
+assert_impl_all!(OpusProjectionEncoder: Send);
+assert_impl_all!(OpusProjectionDecoder: Send);
+assert_impl_all!(OpusRepacketizer<'static>: Send);
+assert_not_impl_any!(OpusMSEncoder: Sync);
+assert_not_impl_any!(OpusMSDecoder: Sync);
+assert_not_impl_any!(OpusProjectionEncoder: Sync);
+assert_not_impl_any!(OpusProjectionDecoder: Sync);
+assert_not_impl_any!(OpusRepacketizer<'static>: Sync);
+
+const FRAME_SIZE: usize = 960;
+const FRAMES: usize = 50;
+
+#[test]
+fn encode_and_decode_on_different_threads() {
+	// The encoder is shared with the "capture callbacks" through a mutex, and
+	// the decoder is moved into the "playback thread".
+	let encoder = Arc::new(Mutex::new(
+		OpusEncoder::new(
+			SampleRate::Hz48000,
+			Channels::Stereo,
+			OpusApplication::Audio,
+		)
+		.expect("failed to create encoder"),
+	));
+	let decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Stereo).expect("failed to create decoder");
+	let (sender, receiver) = mpsc::channel::<Vec<u8>>();
+
+	let playback = thread::spawn(move || {
+		let mut decoder = decoder;
+		let mut decoded = 0;
+		for packet in receiver {
+			let pcm = decoder
+				.decode(Some(&packet), FRAME_SIZE, false)
+				.expect("failed to decode");
+			assert_eq!(pcm.len(), FRAME_SIZE * 2);
+			decoded += 1;
+		}
+		decoded
+	});
+
+	let captures: Vec<_> = (0..2)
+		.map(|_| {
+			let encoder = Arc::clone(&encoder);
+			let sender = sender.clone();
+			thread::spawn(move || {
+				let pcm = vec![0i16; FRAME_SIZE * 2];
+				for _ in 0..FRAMES {
+					// Sending while holding the lock keeps the packets in the
+					// order they were encoded in.
+					let mut encoder = encoder.lock().unwrap();
+					let packet = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+					sender.send(packet).unwrap();
+				}
+			})
+		})
+		.collect();
+	drop(sender);
+
+	for capture in captures {
+		capture.join().unwrap();
+	}
+	assert_eq!(playback.join().unwrap(), FRAMES * 2);
+}
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::{packet, OpusToc, SampleRate};
+use std::time::Duration;
+
+#[test]
+fn agrees_with_libopus_for_every_toc_byte() {
+	for byte in 0..=u8::MAX {
+		let toc = OpusToc::parse(byte);
+		let data = [byte];
+		assert_eq!(toc.byte(), byte);
+		assert_eq!(OpusToc::of_packet(&data), Ok(toc));
+		assert_eq!(packet::bandwidth(&data), Ok(toc.bandwidth()), "{byte:#04x}");
+		assert_eq!(
+			packet::nb_channels(&data),
+			Ok(if toc.is_stereo() { 2 } else { 1 }),
+			"{byte:#04x}"
+		);
+		for sample_rate in [
+			SampleRate::Hz8000,
+			SampleRate::Hz12000,
+			SampleRate::Hz16000,
+			SampleRate::Hz24000,
+			SampleRate::Hz48000,
+		] {
+			assert_eq!(
+				packet::samples_per_frame(&data, sample_rate.as_i32()),
+				Ok(toc.samples_per_frame(sample_rate)),
+				"{byte:#04x} at {sample_rate:?}"
+			);
+		}
+		let samples = toc.samples_per_frame(SampleRate::Hz48000) as u64;
+		assert_eq!(
+			toc.frame_duration(),
+			Duration::from_micros(samples * 1_000_000 / 48_000),
+			"{byte:#04x}"
+		);
+		assert_eq!(toc.code(), byte & 0b11);
+	}
+	assert!(OpusToc::of_packet(&[]).is_err());
+}
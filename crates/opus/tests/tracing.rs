@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MPL-2.0
+#![cfg(feature = "tracing")]
+use meowlouder_opus::{Channels, OpusApplication, OpusDecoder, OpusEncoder, SampleRate};
+use std::{
+	fmt::Debug,
+	sync::{Arc, Mutex},
+};
+use tracing::{
+	field::{Field, Visit},
+	Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+/// An event that was emitted, with the names of its fields.
+#[derive(Debug, Clone)]
+struct RecordedEvent {
+	level: Level,
+	fields: Vec<String>,
+}
+
+/// A layer that records every event emitted while it's the default.
+#[derive(Clone, Default)]
+struct Recorder {
+	events: Arc<Mutex<Vec<RecordedEvent>>>,
+}
+
+struct FieldNames<'a>(&'a mut Vec<String>);
+
+impl Visit for FieldNames<'_> {
+	fn record_debug(&mut self, field: &Field, _value: &dyn Debug) {
+		self.0.push(field.name().to_owned());
+	}
+}
+
+impl<S: Subscriber> Layer<S> for Recorder {
+	fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+		let mut fields = Vec::new();
+		event.record(&mut FieldNames(&mut fields));
+		self.events.lock().unwrap().push(RecordedEvent {
+			level: *event.metadata().level(),
+			fields,
+		});
+	}
+}
+
+impl Recorder {
+	/// Runs `f` with this recorder as the default subscriber, and returns the
+	/// events it emitted.
+	fn record(&self, f: impl FnOnce()) -> Vec<RecordedEvent> {
+		let subscriber = tracing_subscriber::registry().with(self.clone());
+		tracing::subscriber::with_default(subscriber, f);
+		std::mem::take(&mut self.events.lock().unwrap())
+	}
+}
+
+fn has_field(events: &[RecordedEvent], level: Level, name: &str) -> bool {
+	events
+		.iter()
+		.any(|event| event.level == level && event.fields.iter().any(|field| field == name))
+}
+
+#[test]
+fn encode_emits_encoded_bytes() {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let events = Recorder::default().record(|| {
+		encoder.encode(&[0i16; 960], 960).expect("failed to encode");
+	});
+	assert!(
+		has_field(&events, Level::TRACE, "encoded_bytes"),
+		"no encoded_bytes event in {events:?}"
+	);
+}
+
+#[test]
+fn encode_error_is_logged() {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let events = Recorder::default().record(|| {
+		assert!(encoder.encode(&[0i16; 480], 960).is_err());
+	});
+	assert!(
+		has_field(&events, Level::ERROR, "code"),
+		"no error event in {events:?}"
+	);
+	assert!(!has_field(&events, Level::TRACE, "encoded_bytes"));
+}
+
+#[test]
+fn decode_emits_decoded_samples() {
+	let mut encoder = OpusEncoder::new(SampleRate::Hz48000, Channels::Mono, OpusApplication::Audio)
+		.expect("failed to create encoder");
+	let packet = encoder.encode(&[0i16; 960], 960).expect("failed to encode");
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz48000, Channels::Mono).expect("failed to create decoder");
+	let events = Recorder::default().record(|| {
+		decoder
+			.decode(Some(&packet), 960, false)
+			.expect("failed to decode");
+	});
+	assert!(
+		has_field(&events, Level::TRACE, "decoded_samples"),
+		"no decoded_samples event in {events:?}"
+	);
+}
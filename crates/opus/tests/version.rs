@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MPL-2.0
+use meowlouder_opus::{libopus_version, libopus_version_info, LibopusVersion};
+
+#[test]
+fn bundled_version_is_sane() {
+	let info = libopus_version_info();
+	assert_eq!(info.version, libopus_version());
+	assert!(
+		info.at_least(1, 3, 0),
+		"{} parsed as {}.{}.{}",
+		info.version,
+		info.major,
+		info.minor,
+		info.patch
+	);
+	// The bundled libopus is always built with floating point.
+	assert!(!info.is_fixed_point, "{} is fixed-point", info.version);
+}
+
+#[test]
+fn parse_version_strings() {
+	let cases = [
+		("libopus 1.5.2", (1, 5, 2), false),
+		("libopus 1.5.2-fixed", (1, 5, 2), true),
+		("libopus 1.3", (1, 3, 0), false),
+		("libopus 1.4-rc1", (1, 4, 0), false),
+		("libopus v1.5.1-12-gabcdef0", (1, 5, 1), false),
+		("libopus 1.5.1-12-gabcdef0-fixed", (1, 5, 1), true),
+		("libopus unknown", (0, 0, 0), false),
+		("", (0, 0, 0), false),
+		("libopus 99999999999.1", (0, 1, 0), false),
+	];
+	for (version, (major, minor, patch), is_fixed_point) in cases {
+		let info = LibopusVersion::parse(version);
+		assert_eq!(
+			(info.major, info.minor, info.patch, info.is_fixed_point),
+			(major, minor, patch, is_fixed_point),
+			"{version:?}"
+		);
+		assert_eq!(info.version, version);
+	}
+}
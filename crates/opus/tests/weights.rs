@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Tests loading the neural network weights at runtime, with libopus built
+//! without them.
+//!
+//! The weights blob is generated by libopus' `dump_weights_blob` tool, so
+//! build that, run it, and point the `OPUS_WEIGHTS` environment variable to
+//! the `weights_blob.bin` it writes (default: `tests/weights_blob.bin`),
+//! then run:
+//!
+//! ```sh
+//! cargo test -p meowlouder-opus --features weights-file,dred,osce --test weights
+//! ```
+#![cfg(all(feature = "weights-file", feature = "dred"))]
+use meowlouder_opus::{
+	error::OpusErrorCode, Channels, OpusApplication, OpusDecoder, OpusDredDecoder, OpusEncoder,
+	SampleRate,
+};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+/// 20 ms at 16 kHz.
+const FRAME_SIZE: usize = 320;
+
+fn weights_path() -> PathBuf {
+	std::env::var_os("OPUS_WEIGHTS")
+		.map(PathBuf::from)
+		.unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/weights_blob.bin"))
+}
+
+#[test]
+fn loads_weights_blob() {
+	let path = weights_path();
+	let blob = fs::read(&path).unwrap_or_else(|err| {
+		panic!(
+			"failed to read {}: {err}; set OPUS_WEIGHTS to the weights blob",
+			path.display()
+		)
+	});
+	let mut encoder = OpusEncoder::new(SampleRate::Hz16000, Channels::Mono, OpusApplication::Voip)
+		.expect("failed to create encoder");
+	let mut decoder =
+		OpusDecoder::new(SampleRate::Hz16000, Channels::Mono).expect("failed to create decoder");
+	let mut dred_decoder = OpusDredDecoder::new().expect("failed to create DRED decoder");
+
+	// Truncated blobs are rejected rather than read past the end of.
+	for len in [0, 10, 100, blob.len() / 2, blob.len() - 1] {
+		let truncated = &blob[..len];
+		assert_eq!(
+			decoder.set_dnn_blob(truncated).unwrap_err(),
+			OpusErrorCode::BadArg,
+			"{len} bytes"
+		);
+		assert!(encoder.set_dnn_blob(truncated).is_err(), "{len} bytes");
+		assert!(dred_decoder.set_dnn_blob(truncated).is_err(), "{len} bytes");
+	}
+
+	encoder.set_dnn_blob(&blob).expect("failed to load weights");
+	decoder.set_dnn_blob(&blob).expect("failed to load weights");
+	dred_decoder
+		.set_dnn_blob(&blob)
+		.expect("failed to load weights");
+	// The codecs keep their own copy of the weights.
+	drop(blob);
+
+	encoder
+		.set_dred_duration(100)
+		.expect("failed to set DRED duration");
+	encoder
+		.set_expected_packet_loss(20)
+		.expect("failed to set expected packet loss");
+	decoder.set_complexity(7).expect("failed to set complexity");
+	for frame in 0..10 {
+		let pcm = (frame * FRAME_SIZE..(frame + 1) * FRAME_SIZE)
+			.map(|i| {
+				let t = i as f32 / 16000.0;
+				(0.5 * 32767.0 * (t * 300.0 * std::f32::consts::TAU).sin()) as i16
+			})
+			.collect::<Vec<_>>();
+		let data = encoder.encode(&pcm, FRAME_SIZE).expect("failed to encode");
+		let decoded = decoder
+			.decode(Some(&data), FRAME_SIZE, false)
+			.expect("failed to decode");
+		assert_eq!(decoded.len(), FRAME_SIZE);
+	}
+	assert_eq!(
+		decoder.conceal(FRAME_SIZE).map(|pcm| pcm.len()),
+		Ok(FRAME_SIZE)
+	);
+}